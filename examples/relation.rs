@@ -17,7 +17,7 @@ struct B;
 struct C;
 
 #[derive(Clone, Copy, Relation)]
-#[edict(exclusive, owned)]
+#[edict(exclusive, owned, acyclic)]
 struct ChildOf;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Relation)]
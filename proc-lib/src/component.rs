@@ -1,15 +1,21 @@
 use proc_easy::EasyAttributes;
 use syn::spanned::Spanned;
 
-use crate::{merge_where_clauses, Borrow, Name, OnDrop, OnReplace, WhereClause};
+use crate::{
+    kw, merge_where_clauses, Borrow, Name, OnDrop, OnInsert, OnReplace, Require, WhereClause,
+};
 
 proc_easy::easy_attributes! {
     @(edict)
     struct ComponentAttributes {
         name: Option<Name>,
         borrow: Option<Borrow>,
+        on_insert: Option<OnInsert>,
         on_drop: Option<OnDrop>,
         on_replace: Option<OnReplace>,
+        map_entities: Option<kw::map_entities>,
+        clone: Option<kw::clone>,
+        require: Option<Require>,
         where_clauses: Vec<WhereClause>,
     }
 }
@@ -35,6 +41,17 @@ pub fn derive(
         })
     });
 
+    let on_insert = attributes.on_insert.map(|on_insert| {
+            let on_insert = &on_insert.function;
+            quote::quote! {
+                #[allow(unused_variables)]
+                #[inline]
+                fn on_insert(&mut self, entity: #edict_path::entity::EntityId, encoder: #edict_path::action::ActionEncoder<'_>) {
+                    (#on_insert)(self, entity, encoder)
+                }
+            }
+        });
+
     let on_drop = attributes.on_drop.map(|on_drop| {
             let on_drop = &on_drop.function;
             quote::quote! {
@@ -58,6 +75,38 @@ pub fn derive(
         }
     );
 
+    let map_entities = attributes.map_entities.map(|_| {
+        quote::quote! {
+            #[inline]
+            fn map_entities(&mut self, mapper: &mut dyn FnMut(#edict_path::entity::EntityId) -> #edict_path::entity::EntityId) {
+                #edict_path::dump::MapEntities::map_entities(self, mapper)
+            }
+        }
+    });
+
+    let required = attributes.require.map(|require| {
+        let types = require.types.iter();
+        quote::quote! {
+            #[inline]
+            fn required() -> #edict_path::private::Vec<#edict_path::component::ComponentRequirement> {
+                let mut output = #edict_path::private::Vec::new();
+                #(
+                    output.push(#edict_path::component::ComponentRequirement::of::<#types>());
+                )*
+                output
+            }
+        }
+    });
+
+    let clone_fn = attributes.clone.map(|_| {
+        quote::quote! {
+            #[inline]
+            fn clone_fn() -> core::option::Option<fn(&Self) -> Self> {
+                core::option::Option::Some(<Self as core::clone::Clone>::clone)
+            }
+        }
+    });
+
     let insert_borrows = match attributes.borrow {
         None => None,
         Some(borrow) => {
@@ -259,10 +308,18 @@ pub fn derive(
         {
             #fn_name
 
+            #on_insert
+
             #on_drop
 
             #on_replace
 
+            #map_entities
+
+            #clone_fn
+
+            #required
+
             fn borrows() -> #edict_path::private::Vec<#edict_path::component::ComponentBorrow> {
                 let mut output = Vec::new();
                 output.push(#edict_path::component::ComponentBorrow::auto::<Self>());
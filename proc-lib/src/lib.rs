@@ -6,12 +6,18 @@ mod relation;
 mod kw {
     proc_easy::easy_token!(name);
     proc_easy::easy_token!(borrow);
+    proc_easy::easy_token!(on_insert);
     proc_easy::easy_token!(on_drop);
     proc_easy::easy_token!(on_target_drop);
     proc_easy::easy_token!(on_replace);
     proc_easy::easy_token!(exclusive);
     proc_easy::easy_token!(symmetric);
     proc_easy::easy_token!(owned);
+    proc_easy::easy_token!(acyclic);
+    proc_easy::easy_token!(ordered);
+    proc_easy::easy_token!(map_entities);
+    proc_easy::easy_token!(clone);
+    proc_easy::easy_token!(require);
 }
 
 proc_easy::easy_argument_value! {
@@ -28,6 +34,21 @@ proc_easy::easy_argument! {
     }
 }
 
+proc_easy::easy_argument! {
+    struct Require {
+        kw: kw::require,
+        types: proc_easy::EasyParenthesized<proc_easy::EasyTerminated<syn::Type>>,
+    }
+}
+
+proc_easy::easy_argument! {
+    struct OnInsert {
+        kw: kw::on_insert,
+        eq: syn::Token![=],
+        function: syn::Expr,
+    }
+}
+
 proc_easy::easy_argument! {
     struct OnDrop {
         kw: kw::on_drop,
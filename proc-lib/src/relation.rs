@@ -10,6 +10,8 @@ proc_easy::easy_attributes! {
         exclusive: Option<kw::exclusive>,
         symmetric: Option<kw::symmetric>,
         owned: Option<kw::owned>,
+        acyclic: Option<kw::acyclic>,
+        ordered: Option<kw::ordered>,
         on_drop: Option<OnDrop>,
         on_replace: Option<OnReplace>,
         on_target_drop: Option<OnTargetDrop>,
@@ -40,6 +42,14 @@ pub fn derive(
         .owned
         .map(|_| quote::quote! { const OWNED: bool = true; });
 
+    let acyclic = attributes
+        .acyclic
+        .map(|_| quote::quote! { const ACYCLIC: bool = true; });
+
+    let ordered = attributes
+        .ordered
+        .map(|_| quote::quote! { const ORDERED: bool = true; });
+
     let fn_name = attributes.name.map(|name| {
         let name = name.literal;
         Some(quote::quote! {
@@ -94,6 +104,10 @@ pub fn derive(
 
             #owned
 
+            #acyclic
+
+            #ordered
+
             #fn_name
 
             #on_drop
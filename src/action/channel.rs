@@ -56,6 +56,15 @@ impl ActionChannel {
         }
     }
 
+    /// Discards actions recorded into the channel and not yet executed.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.spare_queue.clear();
+        let mut queue = self.shared.queue.lock();
+        queue.clear();
+        self.shared.non_empty.store(false, Ordering::Relaxed);
+    }
+
     #[inline]
     pub fn execute(&mut self) -> Option<impl FnOnce(&mut World, &mut ActionBuffer)> {
         self.spare_queue.pop_front().map(|fun| {
@@ -90,8 +99,8 @@ impl ActionSender {
     where
         B: DynamicComponentBundle + Send + 'static,
     {
-        self.push_fn(move |world, _| {
-            let _ = world.spawn(bundle);
+        self.push_fn(move |world, buffer| {
+            let _ = world.spawn_with_buffer(bundle, buffer);
         });
     }
 
@@ -101,8 +110,8 @@ impl ActionSender {
     where
         B: DynamicBundle + Send + 'static,
     {
-        self.push_fn(move |world, _| {
-            let _ = world.spawn_external(bundle);
+        self.push_fn(move |world, buffer| {
+            let _ = world.spawn_external_with_buffer(bundle, buffer);
         });
     }
 
@@ -219,6 +228,8 @@ impl ActionSender {
     }
 
     /// Encodes an action to add relation between two entities to the [`World`].
+    ///
+    /// Action does nothing if relation is acyclic and would close a cycle.
     #[inline]
     pub fn add_relation<R>(&self, origin: EntityId, relation: R, target: EntityId)
     where
@@ -229,6 +240,32 @@ impl ActionSender {
         });
     }
 
+    /// Encodes an action to add relation between two entities to the [`World`]
+    /// and place origin at specified position among origins of the target.
+    ///
+    /// Action does nothing if relation is acyclic and would close a cycle.
+    #[inline]
+    pub fn add_relation_at<R>(&self, origin: EntityId, relation: R, target: EntityId, index: usize)
+    where
+        R: Relation,
+    {
+        self.push_fn(move |world, buffer| {
+            let _ = world.add_relation_at_with_buffer(origin, relation, target, index, buffer);
+        });
+    }
+
+    /// Encodes an action to move origin of the relation
+    /// to specified position among origins of the target.
+    #[inline]
+    pub fn move_relation<R>(&self, origin: EntityId, target: EntityId, index: usize)
+    where
+        R: Relation,
+    {
+        self.push_fn(move |world, _buffer| {
+            let _ = world.move_relation::<R>(origin, target, index);
+        });
+    }
+
     /// Encodes an action to drop relation between two entities in the [`World`].
     #[inline]
     pub fn drop_relation<R>(&self, origin: EntityId, target: EntityId)
@@ -240,6 +277,22 @@ impl ActionSender {
         });
     }
 
+    /// Encodes an action to add dynamic relation of specified kind between two entities to the [`World`].
+    #[inline]
+    pub fn add_relation_dyn(&self, origin: EntityId, kind: EntityId, target: EntityId) {
+        self.push_fn(move |world, buffer| {
+            let _ = world.add_relation_dyn_with_buffer(origin, kind, target, buffer);
+        });
+    }
+
+    /// Encodes an action to drop dynamic relation of specified kind between two entities in the [`World`].
+    #[inline]
+    pub fn drop_relation_dyn(&self, origin: EntityId, kind: EntityId, target: EntityId) {
+        self.push_fn(move |world, buffer| {
+            let _ = world.remove_relation_dyn_with_buffer(origin, kind, target, buffer);
+        });
+    }
+
     /// Encodes action to insert resource instance.
     pub fn insert_resource<T>(&self, resource: T)
     where
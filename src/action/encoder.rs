@@ -6,8 +6,9 @@ use crate::{
     bundle::{Bundle, ComponentBundle, DynamicBundle, DynamicComponentBundle},
     component::Component,
     entity::{EntityId, EntitySet},
+    query::{ImmutableQuery, IntoQuery},
     relation::Relation,
-    world::{iter_reserve_hint, World},
+    world::{iter_reserve_hint, CloneError, Observer, ObserverEvent, ObserverId, World},
 };
 
 use super::{ActionBuffer, ActionFn};
@@ -110,6 +111,42 @@ impl<'a> ActionEncoder<'a> {
         })
     }
 
+    /// Allocates new entity id and encodes an action to clone specified entity into it.
+    ///
+    /// Components that cannot be cloned are skipped.
+    /// See [`World::clone_entity`].
+    #[inline]
+    pub fn clone_entity(&mut self, id: EntityId) -> EntityId {
+        self.clone_entity_with_report(id, |_| {})
+    }
+
+    /// Allocates new entity id and encodes an action to clone specified entity into it.
+    ///
+    /// Components that cannot be cloned are skipped.
+    /// `report` is called with error if entity is not found
+    /// or some components cannot be cloned.
+    /// Clone is despawned if entity is not found.
+    /// See [`World::clone_entity`].
+    #[inline]
+    pub fn clone_entity_with_report(
+        &mut self,
+        id: EntityId,
+        report: impl FnOnce(CloneError) + Send + 'static,
+    ) -> EntityId {
+        let clone = self.entities.alloc();
+        self.push_fn(move |world, buffer| {
+            match world.clone_entity_with_buffer(id, Some(clone), buffer) {
+                Ok(_) => {}
+                Err(CloneError::NoSuchEntity) => {
+                    let _ = world.despawn_with_buffer(clone, buffer);
+                    report(CloneError::NoSuchEntity);
+                }
+                Err(err) => report(err),
+            }
+        });
+        clone
+    }
+
     /// Encodes an action to insert component to the specified entity.
     #[inline]
     pub fn insert<T>(&mut self, id: EntityId, component: T)
@@ -171,6 +208,15 @@ impl<'a> ActionEncoder<'a> {
         })
     }
 
+    /// Encodes an action to enable or disable specified entity.
+    /// See [`World::set_enabled`].
+    #[inline]
+    pub fn set_enabled(&mut self, id: EntityId, enabled: bool) {
+        self.push_fn(move |world, buffer| {
+            let _ = world.set_enabled_with_buffer(id, enabled, buffer);
+        });
+    }
+
     /// Encodes an action to drop bundle of components from specified entity.
     #[inline]
     pub fn drop_bundle<B>(&mut self, id: EntityId)
@@ -183,6 +229,8 @@ impl<'a> ActionEncoder<'a> {
     }
 
     /// Encodes an action to add relation between two entities to the [`World`].
+    ///
+    /// Action does nothing if relation is acyclic and would close a cycle.
     #[inline]
     pub fn add_relation<R>(&mut self, origin: EntityId, relation: R, target: EntityId)
     where
@@ -193,6 +241,37 @@ impl<'a> ActionEncoder<'a> {
         });
     }
 
+    /// Encodes an action to add relation between two entities to the [`World`]
+    /// and place origin at specified position among origins of the target.
+    ///
+    /// Action does nothing if relation is acyclic and would close a cycle.
+    #[inline]
+    pub fn add_relation_at<R>(
+        &mut self,
+        origin: EntityId,
+        relation: R,
+        target: EntityId,
+        index: usize,
+    ) where
+        R: Relation,
+    {
+        self.push_fn(move |world, buffer| {
+            let _ = world.add_relation_at_with_buffer(origin, relation, target, index, buffer);
+        });
+    }
+
+    /// Encodes an action to move origin of the relation
+    /// to specified position among origins of the target.
+    #[inline]
+    pub fn move_relation<R>(&mut self, origin: EntityId, target: EntityId, index: usize)
+    where
+        R: Relation,
+    {
+        self.push_fn(move |world, _buffer| {
+            let _ = world.move_relation::<R>(origin, target, index);
+        });
+    }
+
     /// Encodes an action to drop relation between two entities in the [`World`].
     #[inline]
     pub fn drop_relation<R>(&mut self, origin: EntityId, target: EntityId)
@@ -204,6 +283,22 @@ impl<'a> ActionEncoder<'a> {
         });
     }
 
+    /// Encodes an action to add dynamic relation of specified kind between two entities to the [`World`].
+    #[inline]
+    pub fn add_relation_dyn(&mut self, origin: EntityId, kind: EntityId, target: EntityId) {
+        self.push_fn(move |world, buffer| {
+            let _ = world.add_relation_dyn_with_buffer(origin, kind, target, buffer);
+        });
+    }
+
+    /// Encodes an action to drop dynamic relation of specified kind between two entities in the [`World`].
+    #[inline]
+    pub fn drop_relation_dyn(&mut self, origin: EntityId, kind: EntityId, target: EntityId) {
+        self.push_fn(move |world, buffer| {
+            let _ = world.remove_relation_dyn_with_buffer(origin, kind, target, buffer);
+        });
+    }
+
     /// Checks if entity is alive.
     #[inline]
     pub fn is_alive(&self, id: EntityId) -> bool {
@@ -229,6 +324,59 @@ impl<'a> ActionEncoder<'a> {
         });
     }
 
+    /// Encodes an action to attach observer of component `T`.
+    /// Returns id of the observer that is valid once the action is executed.
+    ///
+    /// See [`World::observe`] for details.
+    #[inline]
+    pub fn observe<T, F, C>(&mut self, event: ObserverEvent, filter: F, callback: C) -> ObserverId
+    where
+        T: Component,
+        F: IntoQuery + Clone + Send + Sync + 'static,
+        F::Query: ImmutableQuery,
+        C: Fn(EntityId, &World, ActionEncoder) + Send + Sync + 'static,
+    {
+        let observer = Observer::component::<T, F, C>(event, filter, callback);
+        let id = observer.id();
+        self.push_fn(move |world, _| {
+            world.attach_observer(observer);
+        });
+        id
+    }
+
+    /// Encodes an action to attach observer of relation `R`.
+    /// Returns id of the observer that is valid once the action is executed.
+    ///
+    /// See [`World::observe_relation`] for details.
+    #[inline]
+    pub fn observe_relation<R, F, C>(
+        &mut self,
+        event: ObserverEvent,
+        filter: F,
+        callback: C,
+    ) -> ObserverId
+    where
+        R: Relation,
+        F: IntoQuery + Clone + Send + Sync + 'static,
+        F::Query: ImmutableQuery,
+        C: Fn(EntityId, &World, ActionEncoder) + Send + Sync + 'static,
+    {
+        let observer = Observer::relation::<R, F, C>(event, filter, callback);
+        let id = observer.id();
+        self.push_fn(move |world, _| {
+            world.attach_observer(observer);
+        });
+        id
+    }
+
+    /// Encodes an action to detach observer.
+    #[inline]
+    pub fn detach_observer(&mut self, id: ObserverId) {
+        self.push_fn(move |world, _| {
+            world.detach_observer(id);
+        });
+    }
+
     /// Encodes a custom action with a closure that takes mutable reference to `World`.
     #[inline]
     pub fn closure(&mut self, fun: impl FnOnce(&mut World) + Send + 'static) {
@@ -260,7 +408,10 @@ impl<'a> ActionEncoder<'a> {
 
     /// Encodes an action to remove component from specified entity.
     #[inline]
-    fn push_fn(&mut self, fun: impl FnOnce(&mut World, &mut ActionBuffer) + Send + 'static) {
+    pub(crate) fn push_fn(
+        &mut self,
+        fun: impl FnOnce(&mut World, &mut ActionBuffer) + Send + 'static,
+    ) {
         self.actions.push_back(ActionFn::new(fun));
     }
 }
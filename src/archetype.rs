@@ -7,7 +7,7 @@ use core::{
     hint::unreachable_unchecked,
     intrinsics::copy_nonoverlapping,
     iter::FromIterator,
-    mem::{self, size_of, MaybeUninit},
+    mem::{self, size_of, ManuallyDrop, MaybeUninit},
     ops::Deref,
     ptr::{self, NonNull},
 };
@@ -170,6 +170,52 @@ impl ArchetypeComponent {
     }
 }
 
+/// Cloned values of one component of an archetype.
+struct ComponentSnapshot {
+    info: ComponentInfo,
+    ptr: NonNull<u8>,
+    len: usize,
+    epoch: EpochId,
+    entity_epochs: Box<[EpochId]>,
+    chunk_epochs: Box<[EpochId]>,
+}
+
+impl Drop for ComponentSnapshot {
+    fn drop(&mut self) {
+        self.info.final_drop(self.ptr, self.len);
+
+        if self.info.layout().size() != 0 && self.len != 0 {
+            // Safety: layout of existing allocation.
+            let layout = unsafe {
+                Layout::from_size_align_unchecked(
+                    self.info.layout().size() * self.len,
+                    self.info.layout().align(),
+                )
+            };
+
+            unsafe {
+                dealloc(self.ptr.as_ptr(), layout);
+            }
+        }
+    }
+}
+
+/// Cloned entities and components of an archetype.
+pub(crate) struct ArchetypeSnapshot {
+    entities: Vec<EntityId>,
+    components: Vec<ComponentSnapshot>,
+}
+
+impl ArchetypeSnapshot {
+    /// Returns snapshot of an empty archetype.
+    pub(crate) const fn empty() -> Self {
+        ArchetypeSnapshot {
+            entities: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+}
+
 /// Collection of all entities with same set of components.
 /// Archetypes are typically managed by the `World` instance.
 ///
@@ -289,9 +335,16 @@ impl Archetype {
     }
 
     /// Spawns new entity in the archetype.
+    /// Insert hooks are executed if `encoder` is provided.
     ///
     /// Returns index of the newly created entity in the archetype.
-    pub fn spawn<B>(&mut self, id: EntityId, bundle: B, epoch: EpochId) -> u32
+    pub fn spawn<B>(
+        &mut self,
+        id: EntityId,
+        bundle: B,
+        epoch: EpochId,
+        encoder: Option<ActionEncoder>,
+    ) -> u32
     where
         B: DynamicBundle,
     {
@@ -304,7 +357,7 @@ impl Archetype {
             self.reserve(1);
 
             debug_assert_ne!(self.entities.len(), self.entities.capacity());
-            self.write_bundle(id, entity_idx, bundle, epoch, None, |_| false);
+            self.write_bundle(id, entity_idx, bundle, epoch, encoder, |_| false);
         }
 
         self.entities.push(id);
@@ -329,10 +382,32 @@ impl Archetype {
     ///
     /// idx must be in bounds of the archetype entities array.
     pub unsafe fn despawn_unchecked(
+        &mut self,
+        id: EntityId,
+        idx: u32,
+        encoder: ActionEncoder,
+    ) -> Option<EntityId> {
+        unsafe {
+            self.take_unchecked(id, idx, encoder, |info, ptr, encoder| {
+                info.drop_one(ptr, id, encoder)
+            })
+        }
+    }
+
+    /// Removes specified entity from the archetype.
+    /// Each component value is passed to `f` that must either drop it or move it out.
+    ///
+    /// Returns id of the entity that took the place of removed.
+    ///
+    /// # Safety
+    ///
+    /// idx must be in bounds of the archetype entities array.
+    pub(crate) unsafe fn take_unchecked(
         &mut self,
         id: EntityId,
         idx: u32,
         mut encoder: ActionEncoder,
+        mut f: impl FnMut(&ComponentInfo, NonNull<u8>, ActionEncoder),
     ) -> Option<EntityId> {
         let entity_idx = idx as usize;
         debug_assert!(entity_idx < self.entities.len());
@@ -348,7 +423,7 @@ impl Archetype {
             // Or dangling if size is 0, but than result equals `data.ptr`
             let ptr = unsafe { NonNull::new_unchecked(data.ptr.as_ptr().add(entity_idx * size)) };
 
-            component.info.drop_one(ptr, id, encoder.reborrow());
+            f(&component.info, ptr, encoder.reborrow());
 
             if entity_idx != last_entity_idx {
                 let chunk_idx = chunk_idx(entity_idx);
@@ -429,7 +504,7 @@ impl Archetype {
         debug_assert!(entity_idx < self.entities.len());
 
         unsafe {
-            self.write_one(id, entity_idx, value, epoch, Some(encoder));
+            self.write_one(id, entity_idx, value, epoch, encoder, true);
         }
     }
 
@@ -582,6 +657,7 @@ impl Archetype {
         src_idx: u32,
         value: T,
         epoch: EpochId,
+        encoder: ActionEncoder,
     ) -> (u32, Option<EntityId>)
     where
         T: 'static,
@@ -607,7 +683,7 @@ impl Archetype {
         }
 
         unsafe {
-            dst.write_one::<T>(id, dst_entity_idx, value, epoch, None);
+            dst.write_one::<T>(id, dst_entity_idx, value, epoch, encoder, false);
         }
 
         let entity = self.entities.swap_remove(src_entity_idx);
@@ -720,6 +796,59 @@ impl Archetype {
         }
     }
 
+    /// Moves all entities of `other` archetype with the same set of components
+    /// to the end of this archetype.
+    /// Entities get ids from `ids` in order.
+    ///
+    /// Returns index of the first moved entity.
+    pub(crate) fn append(
+        &mut self,
+        other: &mut Archetype,
+        ids: &[EntityId],
+        epoch: EpochId,
+    ) -> u32 {
+        debug_assert!(self.matches(other.ids()));
+        debug_assert_eq!(ids.len(), other.entities.len());
+
+        let start = self.entities.len();
+        let count = other.entities.len();
+        assert!(MAX_IDX_USIZE - start >= count, "Too many entities");
+
+        self.reserve(count);
+
+        for component in self.components.values_mut() {
+            let src = other.components.get_mut(&component.id()).unwrap();
+            let src = src.data.get_mut();
+            let data = component.data.get_mut();
+            let size = component.info.layout().size();
+
+            // Safety: both columns have the same component type.
+            // Space for `count` values is reserved.
+            unsafe {
+                copy_nonoverlapping(
+                    src.ptr.as_ptr(),
+                    data.ptr.as_ptr().add(start * size),
+                    count * size,
+                );
+            }
+
+            data.epoch.bump_again(epoch);
+            for entity_epoch in &mut data.entity_epochs[start..start + count] {
+                entity_epoch.bump(epoch);
+            }
+            for chunk_epoch in &mut data.chunk_epochs[chunk_idx(start)..chunks_count(start + count)]
+            {
+                chunk_epoch.bump_again(epoch);
+            }
+        }
+
+        // Values are moved, archetype must not drop them.
+        other.entities.clear();
+        self.entities.extend_from_slice(ids);
+
+        start as u32
+    }
+
     #[inline]
     pub(crate) fn entities(&self) -> &[EntityId] {
         &self.entities
@@ -762,6 +891,112 @@ impl Archetype {
         }
     }
 
+    /// Clones all entities and components of the archetype.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any component cannot be cloned or is borrowed mutably.
+    pub(crate) fn snapshot(&self) -> ArchetypeSnapshot {
+        let len = self.entities.len();
+
+        let components = self
+            .components
+            .values()
+            .map(|c| {
+                assert!(
+                    c.is_cloneable(),
+                    "Component `{}` cannot be cloned",
+                    c.name()
+                );
+                assert!(
+                    unsafe { c.borrow(Access::Read) },
+                    "Component `{}` is borrowed mutably",
+                    c.name()
+                );
+
+                let data = unsafe { c.data() };
+
+                let mut ptr = NonNull::dangling();
+                if c.layout().size() != 0 && len != 0 {
+                    let layout = Layout::from_size_align(
+                        c.layout().size().checked_mul(len).unwrap(),
+                        c.layout().align(),
+                    )
+                    .unwrap();
+
+                    let Some(new_ptr) = NonNull::new(unsafe { alloc(layout) }) else {
+                        alloc::alloc::handle_alloc_error(layout);
+                    };
+                    ptr = new_ptr;
+                }
+
+                unsafe {
+                    c.clone_to(data.ptr, ptr, len);
+                }
+
+                let snapshot = ComponentSnapshot {
+                    info: c.info.clone(),
+                    ptr,
+                    len,
+                    epoch: data.epoch,
+                    entity_epochs: Box::from(&data.entity_epochs[..len]),
+                    chunk_epochs: Box::from(&data.chunk_epochs[..chunks_count(len)]),
+                };
+
+                unsafe {
+                    c.release(Access::Read);
+                }
+
+                snapshot
+            })
+            .collect();
+
+        ArchetypeSnapshot {
+            entities: self.entities.clone(),
+            components,
+        }
+    }
+
+    /// Replaces entities and components of the archetype with clones from the snapshot.
+    /// Hooks are not called for dropped components.
+    ///
+    /// Snapshot must be taken from archetype with the same set of components.
+    pub(crate) fn restore(&mut self, snapshot: &ArchetypeSnapshot) {
+        let len = self.entities.len();
+
+        // Archetype stays empty if cloning panics.
+        self.entities.clear();
+
+        for c in self.components.values_mut() {
+            let data = c.data.get_mut();
+            c.info.final_drop(data.ptr, len);
+            data.entity_epochs[..len].fill(EpochId::start());
+        }
+
+        self.reserve(snapshot.entities.len());
+
+        for cs in &snapshot.components {
+            let c = self
+                .components
+                .get_mut(&cs.info.id())
+                .expect("Snapshot does not match archetype");
+            let data = c.data.get_mut();
+
+            unsafe {
+                cs.info.clone_to(cs.ptr, data.ptr, cs.len);
+            }
+
+            data.epoch = cs.epoch;
+            data.entity_epochs[..cs.len].copy_from_slice(&cs.entity_epochs);
+
+            let chunks = cs.chunk_epochs.len();
+            data.chunk_epochs[..chunks].copy_from_slice(&cs.chunk_epochs);
+            data.chunk_epochs[chunks..].fill(EpochId::start());
+        }
+
+        self.entities.extend_from_slice(&snapshot.entities);
+    }
+
     #[inline]
     unsafe fn write_bundle<B, F>(
         &mut self,
@@ -794,6 +1029,9 @@ impl Archetype {
                 unsafe {
                     ptr::copy_nonoverlapping(src.as_ptr(), dst.as_ptr(), size);
                 }
+                if let Some(encoder) = &mut encoder {
+                    component.insert_one(dst, id, encoder.reborrow());
+                }
             }
         });
     }
@@ -805,7 +1043,8 @@ impl Archetype {
         entity_idx: usize,
         value: T,
         epoch: EpochId,
-        occupied: Option<ActionEncoder>,
+        encoder: ActionEncoder,
+        occupied: bool,
     ) where
         T: 'static,
     {
@@ -827,12 +1066,15 @@ impl Archetype {
         let dst =
             unsafe { NonNull::new_unchecked(data.ptr.as_ptr().add(entity_idx * size_of::<T>())) };
 
-        if let Some(encoder) = occupied {
-            component.set_one(dst, NonNull::from(&value).cast(), id, encoder)
+        if occupied {
+            // `set_one` moves value out of `src`.
+            let value = ManuallyDrop::new(value);
+            component.set_one(dst, NonNull::from(&*value).cast(), id, encoder)
         } else {
             unsafe {
                 ptr::write(dst.as_ptr().cast(), value);
             }
+            component.insert_one(dst, id, encoder);
         }
     }
 
@@ -323,12 +323,75 @@ impl EntityBuilder {
             return self;
         }
 
+        let value_offset = self.reserve_value(Layout::new::<T>());
+
+        unsafe {
+            ptr::write(self.ptr.as_ptr().add(value_offset).cast(), value);
+        }
+
+        self.ids.push(TypeId::of::<T>());
+        self.infos.push(ComponentInfo::of::<T>());
+        self.offsets.push(value_offset);
+
+        self
+    }
+
+    /// Adds clone of the component to the builder.
+    /// Builder must not contain this component.
+    ///
+    /// # Safety
+    ///
+    /// `src` must point to initialized component described by `info`.
+    /// Component must be cloneable.
+    /// Builder with non-`Send` components must not be sent to another thread.
+    pub(crate) unsafe fn add_clone(&mut self, info: &ComponentInfo, src: NonNull<u8>) {
+        debug_assert!(!self.ids.contains(&info.id()));
+
+        let value_offset = self.reserve_value(info.layout());
+
+        unsafe {
+            let dst = NonNull::new_unchecked(self.ptr.as_ptr().add(value_offset));
+            info.clone_to(src, dst, 1);
+        }
+
+        self.ids.push(info.id());
+        self.infos.push(info.clone());
+        self.offsets.push(value_offset);
+    }
+
+    /// Moves component value into the builder.
+    /// Builder must not contain this component.
+    /// Returns pointer to the value in the builder.
+    ///
+    /// # Safety
+    ///
+    /// `src` must point to initialized component described by `info`.
+    /// Value at `src` must not be used or dropped afterwards.
+    /// Builder with non-`Send` components must not be sent to another thread.
+    pub(crate) unsafe fn add_raw(&mut self, info: &ComponentInfo, src: NonNull<u8>) -> NonNull<u8> {
+        debug_assert!(!self.ids.contains(&info.id()));
+
+        let value_offset = self.reserve_value(info.layout());
+
+        let dst = unsafe { NonNull::new_unchecked(self.ptr.as_ptr().add(value_offset)) };
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), dst.as_ptr(), info.layout().size());
+        }
+
+        self.ids.push(info.id());
+        self.infos.push(info.clone());
+        self.offsets.push(value_offset);
+        dst
+    }
+
+    /// Reserves space for new value with specified layout.
+    /// Returns offset of the value.
+    fn reserve_value(&mut self, layout: Layout) -> usize {
         debug_assert!(self.len <= self.layout.size());
         let value_layout = Layout::from_size_align(self.len, self.layout.align()).unwrap();
 
-        let (new_value_layout, value_offset) = value_layout
-            .extend(Layout::new::<T>())
-            .expect("EntityBuilder overflow");
+        let (new_value_layout, value_offset) =
+            value_layout.extend(layout).expect("EntityBuilder overflow");
 
         self.ids.reserve(1);
         self.infos.reserve(1);
@@ -366,24 +429,19 @@ impl EntityBuilder {
                 let old_ptr = replace(&mut self.ptr, new_ptr);
                 let old_layout = replace(&mut self.layout, new_layout);
 
-                alloc::alloc::dealloc(old_ptr.as_ptr(), old_layout);
+                // Initial dangling pointer is not allocated.
+                if old_layout.size() != 0 {
+                    alloc::alloc::dealloc(old_ptr.as_ptr(), old_layout);
+                }
             }
         }
 
-        unsafe {
-            debug_assert!(self.len <= self.layout.size());
-            debug_assert!(self.len <= value_offset);
-            debug_assert!(value_offset + size_of::<T>() <= self.layout.size());
-
-            ptr::write(self.ptr.as_ptr().add(value_offset).cast(), value);
-            self.len = value_offset + size_of::<T>();
-        }
-
-        self.ids.push(TypeId::of::<T>());
-        self.infos.push(ComponentInfo::of::<T>());
-        self.offsets.push(value_offset);
+        debug_assert!(self.len <= self.layout.size());
+        debug_assert!(self.len <= value_offset);
+        debug_assert!(value_offset + layout.size() <= self.layout.size());
 
-        self
+        self.len = value_offset + layout.size();
+        value_offset
     }
 
     /// Returns reference to component from builder.
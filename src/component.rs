@@ -12,7 +12,12 @@ use core::{
 
 use hashbrown::hash_map::{Entry, HashMap};
 
-use crate::{action::ActionEncoder, entity::EntityId, hash::NoOpHasherBuilder};
+use crate::{
+    action::ActionEncoder,
+    dump::{LoadError, Migration, MigrationStep},
+    entity::EntityId,
+    hash::NoOpHasherBuilder,
+};
 
 pub use edict_proc::Component;
 
@@ -173,6 +178,17 @@ pub trait Component: Sized + 'static {
         core::any::type_name::<Self>()
     }
 
+    /// Hook that is executed when component is attached to an entity.
+    /// That is, when entity is spawned with the component
+    /// or component is inserted to an entity that did not have it.
+    ///
+    /// Assigning new value to existing component executes [`Component::on_replace`] instead.
+    #[inline]
+    fn on_insert(&mut self, id: EntityId, encoder: ActionEncoder) {
+        let _ = id;
+        let _ = encoder;
+    }
+
     /// Hook that is executed when entity with component is dropped.
     #[inline]
     fn on_drop(&mut self, id: EntityId, encoder: ActionEncoder) {
@@ -190,11 +206,97 @@ pub trait Component: Sized + 'static {
         true
     }
 
+    /// Hook that is executed when component is loaded with remapped entity ids.
+    /// Must rewrite all entity ids stored in the component using `mapper`.
+    /// Does nothing by default.
+    ///
+    /// Derive macro implements it using [`MapEntities`] when `#[edict(map_entities)]` is specified.
+    ///
+    /// [`MapEntities`]: edict::dump::MapEntities
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        let _ = mapper;
+    }
+
     /// Returns array of component borrows supported by the type.
     #[inline]
     fn borrows() -> Vec<ComponentBorrow> {
         vec![ComponentBorrow::auto::<Self>()]
     }
+
+    /// Returns components required by this component.
+    /// Missing required components are inserted
+    /// whenever this component is attached to an entity.
+    ///
+    /// Returns empty list by default.
+    /// Derive macro implements it when `#[edict(require(...))]` is specified,
+    /// required components are then constructed with [`Default`].
+    #[inline]
+    fn required() -> Vec<ComponentRequirement> {
+        Vec::new()
+    }
+
+    /// Returns function that clones component value.
+    /// Cloneable components can be captured in [`WorldSnapshot`]
+    /// and copied by [`World::clone_entity`].
+    ///
+    /// Returns `None` by default.
+    /// Derive macro implements it using [`Clone`] when `#[edict(clone)]` is specified.
+    ///
+    /// [`WorldSnapshot`]: edict::world::WorldSnapshot
+    /// [`World::clone_entity`]: edict::world::World::clone_entity
+    #[inline]
+    fn clone_fn() -> Option<fn(&Self) -> Self> {
+        None
+    }
+}
+
+/// Component required by another component.
+/// Defines how missing required component is constructed.
+///
+/// Returned from [`Component::required`]
+/// and registered with [`ComponentInfoRef::require`].
+#[derive(Clone)]
+pub struct ComponentRequirement {
+    id: TypeId,
+    insert: RequireFn,
+}
+
+impl ComponentRequirement {
+    /// Requires component `R` constructed with [`Default`].
+    #[inline]
+    pub fn of<R>() -> Self
+    where
+        R: Component + Default,
+    {
+        Self::with(R::default)
+    }
+
+    /// Requires component `R` constructed with provided function.
+    pub fn with<R, F>(make: F) -> Self
+    where
+        R: Component,
+        F: Fn() -> R + Send + Sync + 'static,
+    {
+        let make = Arc::new(make);
+        ComponentRequirement {
+            id: TypeId::of::<R>(),
+            insert: Arc::new(move |id, encoder| {
+                let make = make.clone();
+                encoder.push_fn(move |world, buffer| {
+                    if world.has_component::<R>(id) == Ok(false) {
+                        let _ = world.insert_with_buffer(id, make(), buffer);
+                    }
+                });
+            }),
+        }
+    }
+
+    /// Returns [`TypeId`] of the required component.
+    #[inline]
+    pub fn id(&self) -> TypeId {
+        self.id
+    }
 }
 
 /// Type information required for components.
@@ -209,6 +311,12 @@ pub struct ComponentInfo {
     /// Name of the component.
     name: &'static str,
 
+    /// Function that calls insert hook for a component.
+    insert_one: InsertOneFn,
+
+    /// Context for `insert_one` command when component is inserted.
+    on_insert: Arc<dyn Any + Send + Sync>,
+
     /// Function that calls drop glue for a component.
     /// Supports custom hooks.
     drop_one: DropOneFn,
@@ -229,8 +337,59 @@ pub struct ComponentInfo {
 
     /// An array of possible component borrows.
     borrows: Arc<[ComponentBorrow]>,
+
+    /// Components inserted along with this one if missing.
+    required: Arc<[ComponentRequirement]>,
+
+    /// Version of the component schema written to dumps.
+    version: u32,
+
+    /// Migrations from older versions sorted by version.
+    migrations: Arc<[MigrationStep]>,
+
+    /// Function that clones component into uninitialized location.
+    /// `None` if component cannot be cloned.
+    clone: Option<CloneFn>,
+
+    /// Defines how relation component is copied to cloned entity.
+    /// `None` for non-relation components.
+    relation_clone: Option<RelationClone>,
+
+    /// Function that rewrites entity ids stored in the component.
+    /// `None` for external components.
+    map_entities: Option<MapEntitiesFn>,
+}
+
+/// Defines how relation component is copied to cloned entity.
+#[derive(Clone, Copy)]
+pub(crate) enum RelationClone {
+    /// Relations are added to the clone with the same targets.
+    /// Function reads component and encodes actions to add its relations to specified entity.
+    /// Targets are mapped with provided function, relations with unmapped targets are skipped.
+    Origin(AddRelationsFn),
+
+    /// Component is not copied.
+    /// Clone does not become target of relations.
+    Target,
 }
 
+/// Function that encodes actions to add relations of the component to specified entity.
+type AddRelationsFn = unsafe fn(
+    NonNull<u8>,
+    EntityId,
+    &mut dyn FnMut(EntityId) -> Option<EntityId>,
+    &mut ActionEncoder,
+);
+
+/// Function that rewrites entity ids stored in the value.
+type MapEntitiesFn = unsafe fn(NonNull<u8>, &mut dyn FnMut(EntityId) -> EntityId);
+
+/// Function that encodes insertion of required component to specified entity if it is missing.
+type RequireFn = Arc<dyn Fn(EntityId, &mut ActionEncoder) + Send + Sync>;
+
+/// Function that writes clone of the value at first location into second location.
+type CloneFn = Arc<dyn Fn(NonNull<u8>, NonNull<u8>) + Send + Sync>;
+
 impl ComponentInfo {
     /// Returns component information for specified component type.
     #[inline(always)]
@@ -242,12 +401,20 @@ impl ComponentInfo {
             id: TypeId::of::<T>(),
             layout: Layout::new::<T>(),
             name: T::name(),
+            insert_one: insert_one::<T, DefaultInsertHook>,
+            on_insert: Arc::new(DefaultInsertHook),
             drop_one: drop_one::<T, DefaultDropHook>,
             on_drop: Arc::new(DefaultDropHook),
             set_one: set_one::<T, DefaultSetHook, DefaultDropHook>,
             on_replace: Arc::new(DefaultSetHook),
             final_drop: final_drop::<T>,
             borrows: Arc::from(T::borrows()),
+            required: Arc::from(T::required()),
+            version: 0,
+            migrations: Arc::new([]),
+            clone: T::clone_fn().map(clone_fn::<T>),
+            relation_clone: None,
+            map_entities: Some(map_entities::<T>),
         }
     }
 
@@ -261,12 +428,20 @@ impl ComponentInfo {
             id: TypeId::of::<T>(),
             layout: Layout::new::<T>(),
             name: type_name::<T>(),
+            insert_one: insert_one::<T, ExternalInsertHook>,
+            on_insert: Arc::new(ExternalInsertHook),
             drop_one: drop_one::<T, ExternalDropHook>,
             on_drop: Arc::new(ExternalDropHook),
             set_one: set_one::<T, ExternalSetHook, ExternalDropHook>,
             on_replace: Arc::new(ExternalSetHook),
             final_drop: final_drop::<T>,
             borrows: Arc::new([]),
+            required: Arc::new([]),
+            version: 0,
+            migrations: Arc::new([]),
+            clone: None,
+            relation_clone: None,
+            map_entities: None,
         }
     }
 
@@ -280,6 +455,22 @@ impl ComponentInfo {
         self.layout
     }
 
+    /// Executes insert hook and encodes insertion of missing required components.
+    #[inline(always)]
+    pub(crate) fn insert_one(&self, ptr: NonNull<u8>, id: EntityId, mut encoder: ActionEncoder) {
+        unsafe {
+            (self.insert_one)(
+                NonNull::from(&*self.on_insert).cast(),
+                ptr,
+                id,
+                encoder.reborrow(),
+            );
+        }
+        for requirement in self.required.iter() {
+            (requirement.insert)(id, &mut encoder);
+        }
+    }
+
     #[inline(always)]
     pub(crate) fn drop_one(&self, ptr: NonNull<u8>, id: EntityId, encoder: ActionEncoder) {
         unsafe {
@@ -323,6 +514,106 @@ impl ComponentInfo {
     pub(crate) fn borrows(&self) -> &[ComponentBorrow] {
         &self.borrows
     }
+
+    #[inline(always)]
+    pub(crate) fn version(&self) -> u32 {
+        self.version
+    }
+
+    #[inline(always)]
+    pub(crate) fn is_cloneable(&self) -> bool {
+        self.clone.is_some()
+    }
+
+    #[inline(always)]
+    pub(crate) fn relation_clone(&self) -> Option<RelationClone> {
+        self.relation_clone
+    }
+
+    /// Rewrites entity ids stored in the component at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to initialized component of this type.
+    #[inline(always)]
+    pub(crate) unsafe fn map_entities(
+        &self,
+        ptr: NonNull<u8>,
+        mapper: &mut dyn FnMut(EntityId) -> EntityId,
+    ) {
+        if let Some(map_entities) = self.map_entities {
+            unsafe {
+                map_entities(ptr, mapper);
+            }
+        }
+    }
+
+    /// Marks component as relation component.
+    #[inline(always)]
+    pub(crate) fn with_relation_clone(mut self, relation_clone: RelationClone) -> Self {
+        self.relation_clone = Some(relation_clone);
+        self
+    }
+
+    /// Clones `count` components from `src` into uninitialized `dst`.
+    ///
+    /// # Safety
+    ///
+    /// `src` must point to `count` initialized components of this type.
+    /// `dst` must be valid for writing `count` components of this type.
+    /// Component must be cloneable.
+    pub(crate) unsafe fn clone_to(&self, src: NonNull<u8>, dst: NonNull<u8>, count: usize) {
+        let clone = self.clone.as_ref().unwrap();
+        let size = self.layout.size();
+        for idx in 0..count {
+            unsafe {
+                clone(
+                    NonNull::new_unchecked(src.as_ptr().add(idx * size)),
+                    NonNull::new_unchecked(dst.as_ptr().add(idx * size)),
+                );
+            }
+        }
+    }
+
+    /// Returns migration of values dumped with specified version.
+    /// Returns `None` if version is the current one.
+    /// Returns error if version is newer than current.
+    pub(crate) fn migration(&self, from_version: u32) -> Result<Option<Migration>, LoadError> {
+        if from_version > self.version {
+            return Err(LoadError::UnsupportedVersion {
+                name: self.name,
+                version: from_version,
+                current: self.version,
+            });
+        }
+        if from_version == self.version {
+            return Ok(None);
+        }
+        Ok(Some(Migration::new(
+            self.name,
+            from_version,
+            self.version,
+            self.migrations.clone(),
+        )))
+    }
+}
+
+/// Trait to be implemented by custom insert hooks.
+/// Has blanket implementation for `Fn(&mut T, EntityId, ActionEncoder)`.
+pub trait InsertHook<T: ?Sized>: Send + Sync + 'static {
+    /// Called when component is attached to an entity.
+    fn on_insert(&self, component: &mut T, id: EntityId, encoder: ActionEncoder);
+}
+
+impl<T, F> InsertHook<T> for F
+where
+    T: ?Sized,
+    F: Fn(&mut T, EntityId, ActionEncoder) + Send + Sync + 'static,
+{
+    #[inline(always)]
+    fn on_insert(&self, component: &mut T, id: EntityId, encoder: ActionEncoder) {
+        self(component, id, encoder);
+    }
 }
 
 /// Trait to be implemented by custom drop hooks.
@@ -374,6 +665,20 @@ where
     }
 }
 
+/// Default insert hook type.
+#[derive(Clone, Copy, Debug)]
+pub struct DefaultInsertHook;
+
+impl<T> InsertHook<T> for DefaultInsertHook
+where
+    T: Component,
+{
+    #[inline(always)]
+    fn on_insert(&self, component: &mut T, id: EntityId, encoder: ActionEncoder) {
+        T::on_insert(component, id, encoder);
+    }
+}
+
 /// Default drop hook type.
 #[derive(Clone, Copy, Debug)]
 pub struct DefaultDropHook;
@@ -402,6 +707,15 @@ where
     }
 }
 
+/// External insert hook type.
+#[derive(Clone, Copy, Debug)]
+pub struct ExternalInsertHook;
+
+impl<T> InsertHook<T> for ExternalInsertHook {
+    #[inline(always)]
+    fn on_insert(&self, _component: &mut T, _id: EntityId, _encoder: ActionEncoder) {}
+}
+
 /// External drop hook type.
 #[derive(Clone, Copy, Debug)]
 pub struct ExternalDropHook;
@@ -429,25 +743,32 @@ impl<T> SetHook<T> for ExternalSetHook {
 }
 
 /// Reference to registered [`ComponentInfo`].
-/// Allows user to setup custom drop and set hooks.
+/// Allows user to setup custom insert, drop and set hooks.
 pub struct ComponentInfoRef<
     'a,
     T: 'static,
     D: DropHook<T> = DefaultDropHook,
     S: SetHook<T> = DefaultSetHook,
+    I: InsertHook<T> = DefaultInsertHook,
 > {
     info: Option<&'a mut ComponentInfo>,
     phantom: PhantomData<T>,
     drop: ManuallyDrop<D>,
     set: ManuallyDrop<S>,
+    insert: ManuallyDrop<I>,
     name: Option<&'static str>,
+    version: Option<u32>,
+    migrations: Vec<MigrationStep>,
+    clone: Option<CloneFn>,
+    required: Vec<ComponentRequirement>,
 }
 
-impl<T, D, S> Drop for ComponentInfoRef<'_, T, D, S>
+impl<T, D, S, I> Drop for ComponentInfoRef<'_, T, D, S, I>
 where
     T: 'static,
     D: DropHook<T>,
     S: SetHook<T>,
+    I: InsertHook<T>,
 {
     #[inline]
     fn drop(&mut self) {
@@ -455,15 +776,18 @@ where
     }
 }
 
-impl<'a, T, D, S> ComponentInfoRef<'a, T, D, S>
+impl<'a, T, D, S, I> ComponentInfoRef<'a, T, D, S, I>
 where
     T: 'static,
     D: DropHook<T>,
     S: SetHook<T>,
+    I: InsertHook<T>,
 {
     #[inline]
     fn drop_impl(&mut self) {
         let info = self.info.as_mut().unwrap();
+        info.insert_one = insert_one::<T, I>;
+        info.on_insert = Arc::new(unsafe { ManuallyDrop::take(&mut self.insert) });
         info.drop_one = drop_one::<T, D>;
         info.on_drop = Arc::new(unsafe { ManuallyDrop::take(&mut self.drop) });
         info.set_one = set_one::<T, S, D>;
@@ -471,6 +795,26 @@ where
         if let Some(name) = self.name {
             info.name = name;
         }
+        if let Some(version) = self.version {
+            info.version = version;
+        }
+        if !self.migrations.is_empty() {
+            let mut migrations = info.migrations.to_vec();
+            migrations.append(&mut self.migrations);
+            migrations.sort_by_key(MigrationStep::from);
+            info.migrations = Arc::from(migrations);
+        }
+        if let Some(clone) = self.clone.take() {
+            info.clone = Some(clone);
+        }
+        if !self.required.is_empty() {
+            let mut required = info.required.to_vec();
+            for requirement in self.required.drain(..) {
+                required.retain(|r| r.id != requirement.id);
+                required.push(requirement);
+            }
+            info.required = Arc::from(required);
+        }
     }
 
     /// Finishes component registration.
@@ -485,7 +829,7 @@ where
     /// Drop hook is executed when component is dropped.
     ///
     /// This hook is not executed on shutdown when `Archetype` is dropped.
-    pub fn on_drop<F>(self, hook: F) -> ComponentInfoRef<'a, T, F, S>
+    pub fn on_drop<F>(self, hook: F) -> ComponentInfoRef<'a, T, F, S, I>
     where
         F: DropHook<T>,
     {
@@ -496,7 +840,12 @@ where
             phantom: me.phantom,
             drop: ManuallyDrop::new(hook),
             set: unsafe { ptr::read(&me.set) },
+            insert: unsafe { ptr::read(&me.insert) },
             name: me.name,
+            version: me.version,
+            migrations: unsafe { ptr::read(&me.migrations) },
+            clone: unsafe { ptr::read(&me.clone) },
+            required: unsafe { ptr::read(&me.required) },
         }
     }
 
@@ -504,7 +853,7 @@ where
     /// Drop hook is executed when component is dropped.
     ///
     /// This hook is not executed on shutdown when `Archetype` is dropped.
-    pub fn on_drop_fn<F>(self, hook: F) -> ComponentInfoRef<'a, T, F, S>
+    pub fn on_drop_fn<F>(self, hook: F) -> ComponentInfoRef<'a, T, F, S, I>
     where
         F: Fn(&mut T, EntityId, ActionEncoder) + Send + Sync + 'static,
     {
@@ -515,7 +864,7 @@ where
     /// Set hook is executed when component is assigned a new value.
     ///
     /// By default, set hook is calling `on_drop`.
-    pub fn on_replace<F>(self, hook: F) -> ComponentInfoRef<'a, T, D, F>
+    pub fn on_replace<F>(self, hook: F) -> ComponentInfoRef<'a, T, D, F, I>
     where
         F: SetHook<T>,
     {
@@ -526,7 +875,12 @@ where
             phantom: me.phantom,
             drop: unsafe { ptr::read(&me.drop) },
             set: ManuallyDrop::new(hook),
+            insert: unsafe { ptr::read(&me.insert) },
             name: me.name,
+            version: me.version,
+            migrations: unsafe { ptr::read(&me.migrations) },
+            clone: unsafe { ptr::read(&me.clone) },
+            required: unsafe { ptr::read(&me.required) },
         }
     }
 
@@ -534,18 +888,124 @@ where
     /// Set hook is executed when component is assigned a new value.
     ///
     /// By default, set hook is calling `on_drop`.
-    pub fn on_replace_fn<F>(self, hook: F) -> ComponentInfoRef<'a, T, D, F>
+    pub fn on_replace_fn<F>(self, hook: F) -> ComponentInfoRef<'a, T, D, F, I>
     where
         F: Fn(&mut T, &T, EntityId, ActionEncoder) -> bool + Send + Sync + 'static,
     {
         self.on_replace(hook)
     }
 
+    /// Configures insert hook for this component.
+    /// Insert hook is executed when component is attached to an entity.
+    ///
+    /// Assigning new value to existing component executes set hook instead.
+    pub fn on_insert<F>(self, hook: F) -> ComponentInfoRef<'a, T, D, S, F>
+    where
+        F: InsertHook<T>,
+    {
+        let me = ManuallyDrop::new(self);
+
+        ComponentInfoRef {
+            info: unsafe { ptr::read(&me.info) },
+            phantom: me.phantom,
+            drop: unsafe { ptr::read(&me.drop) },
+            set: unsafe { ptr::read(&me.set) },
+            insert: ManuallyDrop::new(hook),
+            name: me.name,
+            version: me.version,
+            migrations: unsafe { ptr::read(&me.migrations) },
+            clone: unsafe { ptr::read(&me.clone) },
+            required: unsafe { ptr::read(&me.required) },
+        }
+    }
+
+    /// Configures insert hook for this component.
+    /// Insert hook is executed when component is attached to an entity.
+    ///
+    /// Assigning new value to existing component executes set hook instead.
+    pub fn on_insert_fn<F>(self, hook: F) -> ComponentInfoRef<'a, T, D, S, F>
+    where
+        F: Fn(&mut T, EntityId, ActionEncoder) + Send + Sync + 'static,
+    {
+        self.on_insert(hook)
+    }
+
     /// Overrides default component type name.
     pub fn name(mut self, name: &'static str) -> Self {
         self.name = Some(name);
         self
     }
+
+    /// Sets version of the component schema.
+    ///
+    /// Version is written to world dumps.
+    /// When older version is loaded, migrations are applied before value is inserted.
+    /// Default version is `0`.
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Registers migration of values dumped with version `from`.
+    ///
+    /// Loaders find `decoder` by its type with [`Migration::decoder`],
+    /// decode dumped value into `Old` and `migration` converts it
+    /// into value of version `from + 1`.
+    /// Backends provide methods that register migrations with their decoders,
+    /// e.g. `migration_bin` for `nanoserde` binary format.
+    pub fn migration_with<Dec, Old, New, F>(mut self, from: u32, decoder: Dec, migration: F) -> Self
+    where
+        Dec: Send + Sync + 'static,
+        Old: 'static,
+        New: 'static,
+        F: Fn(Old) -> New + Send + Sync + 'static,
+    {
+        self.migrations
+            .push(MigrationStep::new(from, decoder, migration));
+        self
+    }
+
+    /// Sets function that clones component value.
+    /// Overrides [`Component::clone_fn`].
+    ///
+    /// Cloneable components can be captured in [`WorldSnapshot`]
+    /// and copied by [`World::clone_entity`].
+    ///
+    /// [`WorldSnapshot`]: edict::world::WorldSnapshot
+    /// [`World::clone_entity`]: edict::world::World::clone_entity
+    pub fn clone_fn<F>(mut self, clone: F) -> Self
+    where
+        F: Fn(&T) -> T + Send + Sync + 'static,
+    {
+        self.clone = Some(clone_fn::<T>(clone));
+        self
+    }
+
+    /// Adds component `R` required by this component.
+    /// Missing `R` is inserted with [`Default`] value
+    /// whenever this component is attached to an entity.
+    ///
+    /// Overrides requirement of the same type from [`Component::required`].
+    pub fn require<R>(self) -> Self
+    where
+        R: Component + Default,
+    {
+        self.require_with(R::default)
+    }
+
+    /// Adds component `R` required by this component.
+    /// Missing `R` is inserted with value returned by `make`
+    /// whenever this component is attached to an entity.
+    ///
+    /// Overrides requirement of the same type from [`Component::required`].
+    pub fn require_with<R, F>(mut self, make: F) -> Self
+    where
+        R: Component,
+        F: Fn() -> R + Send + Sync + 'static,
+    {
+        self.required.push(ComponentRequirement::with(make));
+        self
+    }
 }
 
 /// Container for [`ComponentInfo`]s.
@@ -569,6 +1029,14 @@ impl ComponentRegistry {
             .or_insert_with(ComponentInfo::of::<T>)
     }
 
+    pub fn get_or_register_with(
+        &mut self,
+        id: TypeId,
+        f: impl FnOnce() -> ComponentInfo,
+    ) -> &ComponentInfo {
+        self.components.entry(id).or_insert_with(f)
+    }
+
     pub fn get_or_register_raw(&mut self, info: ComponentInfo) -> &ComponentInfo {
         self.components.entry(info.id()).or_insert(info)
     }
@@ -610,13 +1078,18 @@ impl ComponentRegistry {
             phantom: PhantomData,
             drop: ManuallyDrop::new(DefaultDropHook),
             set: ManuallyDrop::new(DefaultSetHook),
+            insert: ManuallyDrop::new(DefaultInsertHook),
             name: None,
+            version: None,
+            migrations: Vec::new(),
+            clone: None,
+            required: Vec::new(),
         }
     }
 
     pub fn register_external<'a, T>(
         &'a mut self,
-    ) -> ComponentInfoRef<'a, T, ExternalDropHook, ExternalSetHook>
+    ) -> ComponentInfoRef<'a, T, ExternalDropHook, ExternalSetHook, ExternalInsertHook>
     where
         T: 'static,
     {
@@ -630,7 +1103,12 @@ impl ComponentRegistry {
             phantom: PhantomData,
             drop: ManuallyDrop::new(ExternalDropHook),
             set: ManuallyDrop::new(ExternalSetHook),
+            insert: ManuallyDrop::new(ExternalInsertHook),
             name: None,
+            version: None,
+            migrations: Vec::new(),
+            clone: None,
+            required: Vec::new(),
         }
     }
 
@@ -645,11 +1123,27 @@ impl ComponentRegistry {
 
 struct Opaque;
 
+type InsertOneFn = unsafe fn(NonNull<Opaque>, NonNull<u8>, EntityId, ActionEncoder);
 type DropOneFn = unsafe fn(NonNull<Opaque>, NonNull<u8>, EntityId, ActionEncoder);
 type SetOneFn =
     unsafe fn(NonNull<Opaque>, NonNull<Opaque>, NonNull<u8>, NonNull<u8>, EntityId, ActionEncoder);
 type FinalDrop = unsafe fn(NonNull<u8>, usize);
 
+unsafe fn insert_one<T, I>(
+    hook: NonNull<Opaque>,
+    ptr: NonNull<u8>,
+    id: EntityId,
+    encoder: ActionEncoder,
+) where
+    T: 'static,
+    I: InsertHook<T>,
+{
+    let mut ptr = ptr.cast::<T>();
+    let hook = unsafe { hook.cast::<I>().as_ref() };
+    let value = unsafe { ptr.as_mut() };
+    hook.on_insert(value, id, encoder);
+}
+
 unsafe fn drop_one<T, D>(
     hook: NonNull<Opaque>,
     ptr: NonNull<u8>,
@@ -692,6 +1186,25 @@ unsafe fn set_one<T, S, D>(
     }
 }
 
+fn clone_fn<T>(clone: impl Fn(&T) -> T + Send + Sync + 'static) -> CloneFn
+where
+    T: 'static,
+{
+    Arc::new(move |src: NonNull<u8>, dst: NonNull<u8>| {
+        let value = clone(unsafe { src.cast::<T>().as_ref() });
+        unsafe { dst.cast::<T>().as_ptr().write(value) };
+    })
+}
+
+unsafe fn map_entities<T>(ptr: NonNull<u8>, mapper: &mut dyn FnMut(EntityId) -> EntityId)
+where
+    T: Component,
+{
+    unsafe {
+        ptr.cast::<T>().as_mut().map_entities(mapper);
+    }
+}
+
 /// This drop is always called for all components when `Archetype` is dropped.
 /// Does not invoke any hooks.
 unsafe fn final_drop<T>(ptr: NonNull<u8>, count: usize) {
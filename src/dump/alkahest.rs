@@ -3,15 +3,30 @@
 //! This module provides the [`ComponentDump`](ComponentDump) trait, which can be implemented for
 
 use alkahest::{
-    advanced::{slice_writer, write_field, BareFormula, Buffer, Deserializer, Sizes, SliceWriter},
-    deserialize_with_size, serialize_to_vec, DeIter, Deserialize, DeserializeError, Formula, Lazy,
-    Serialize,
+    advanced::{
+        slice_writer, write_field, write_slice, BareFormula, Buffer, Deserializer, Sizes,
+        SliceWriter,
+    },
+    deserialize_with_size, read_packet, serialize_to_vec, write_packet_to_vec, DeIter, Deserialize,
+    DeserializeError, Formula, Lazy, Serialize, SerializeRef,
 };
 
-use crate::{action::ActionEncoder, component::Component, query::ImmutableQuery};
+use core::marker::PhantomData;
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use crate::{
+    action::ActionEncoder,
+    component::{Component, ComponentInfoRef, DropHook, InsertHook, SetHook},
+    query::ImmutableQuery,
+    relation::ChildOf,
+};
 
 use super::{
-    DumpSet, DumpSlot, Dumper, EntityDump, LoadSet, LoadSlot, Loader, Mark, WorldDump, WorldLoad,
+    named::{NamedComponentDump, NamedDump, NamedEntityDump, NamedRegistry},
+    Decoded, DumpSet, DumpSlot, Dumpable, Dumper, EntityDump, EntityRemap, LoadEntry, LoadError,
+    LoadSet, LoadSlot, Loader, Mark, Migration, Relations, ResourceDumper, ResourceLoader,
+    ResourceSet, TombstoneDump, VersionDump, WorldDump, WorldLoad,
 };
 
 /// Formula for single entity.
@@ -29,13 +44,312 @@ where
 impl<F> BareFormula for DumpFormula<F> where F: Formula {}
 
 /// Formula for serializing world with set of components.
-pub type WorldFormula<F> = [([u64; 3], DumpFormula<F>)];
+///
+/// Each record is a kind tag, an integer triple and component values.
+pub type WorldFormula<F> = [(u8, [u64; 3], DumpFormula<F>)];
+
+/// Formula for serializing world with set of components and set of resources.
+///
+/// Resources are serialized with mask of present resources before entities.
+pub type WorldResourcesFormula<RF, F> = ((u64, DumpFormula<RF>), WorldFormula<F>);
+
+/// [`Relations<R>`] are serialized with `Vec<(u64, F)>` formula,
+/// where `F` is formula for `R`.
+impl<'ser, F, R> Serialize<[(u64, F)]> for &'ser Relations<R>
+where
+    F: Formula,
+    &'ser R: Serialize<F>,
+{
+    fn serialize<B>(self, sizes: &mut Sizes, buffer: B) -> Result<(), B::Error>
+    where
+        B: Buffer,
+    {
+        write_slice::<(u64, F), _, _>(
+            self.targets
+                .iter()
+                .map(|(target, relation)| (*target, relation)),
+            sizes,
+            buffer,
+        )
+    }
+
+    fn size_hint(&self) -> Option<Sizes> {
+        None
+    }
+}
+
+impl<'de, F, R> Deserialize<'de, [(u64, F)]> for Relations<R>
+where
+    F: Formula,
+    R: Deserialize<'de, F>,
+{
+    fn deserialize(de: Deserializer<'de>) -> Result<Self, DeserializeError> {
+        Ok(Relations {
+            targets: <Vec<(u64, R)> as Deserialize<'de, [(u64, F)]>>::deserialize(de)?,
+        })
+    }
+
+    fn deserialize_in_place(&mut self, de: Deserializer<'de>) -> Result<(), DeserializeError> {
+        <Vec<(u64, R)> as Deserialize<'de, [(u64, F)]>>::deserialize_in_place(&mut self.targets, de)
+    }
+}
+
+/// [`ChildOf`] is serialized with `()` formula.
+impl SerializeRef<()> for ChildOf {
+    fn serialize<B>(&self, _sizes: &mut Sizes, _buffer: B) -> Result<(), B::Error>
+    where
+        B: Buffer,
+    {
+        Ok(())
+    }
+
+    fn size_hint(&self) -> Option<Sizes> {
+        Some(Sizes::ZERO)
+    }
+}
+
+impl Deserialize<'_, ()> for ChildOf {
+    fn deserialize(_de: Deserializer) -> Result<Self, DeserializeError> {
+        Ok(ChildOf)
+    }
+
+    fn deserialize_in_place(&mut self, _de: Deserializer) -> Result<(), DeserializeError> {
+        Ok(())
+    }
+}
+
+/// Formula for [`NamedComponentDump`].
+/// Key, version and serialized component value.
+pub type NamedComponentFormula = (String, u32, Vec<u8>);
+
+/// Formula for [`NamedEntityDump`].
+/// Opaque entity id and its components.
+pub type NamedEntityFormula = (u64, Vec<NamedComponentFormula>);
+
+/// Formula for [`NamedDump`].
+pub type NamedDumpFormula = [NamedEntityFormula];
+
+impl SerializeRef<NamedComponentFormula> for NamedComponentDump {
+    fn serialize<B>(&self, sizes: &mut Sizes, buffer: B) -> Result<(), B::Error>
+    where
+        B: Buffer,
+    {
+        <(&String, u32, &Vec<u8>) as Serialize<NamedComponentFormula>>::serialize(
+            (&self.key, self.version, &self.data),
+            sizes,
+            buffer,
+        )
+    }
+
+    fn size_hint(&self) -> Option<Sizes> {
+        None
+    }
+}
+
+impl Deserialize<'_, NamedComponentFormula> for NamedComponentDump {
+    fn deserialize(de: Deserializer) -> Result<Self, DeserializeError> {
+        let (key, version, data) =
+            <(String, u32, Vec<u8>) as Deserialize<NamedComponentFormula>>::deserialize(de)?;
+        Ok(NamedComponentDump { key, version, data })
+    }
+
+    fn deserialize_in_place(&mut self, de: Deserializer) -> Result<(), DeserializeError> {
+        *self = <Self as Deserialize<NamedComponentFormula>>::deserialize(de)?;
+        Ok(())
+    }
+}
+
+impl SerializeRef<NamedEntityFormula> for NamedEntityDump {
+    fn serialize<B>(&self, sizes: &mut Sizes, buffer: B) -> Result<(), B::Error>
+    where
+        B: Buffer,
+    {
+        <(u64, &Vec<NamedComponentDump>) as Serialize<NamedEntityFormula>>::serialize(
+            (self.id, &self.components),
+            sizes,
+            buffer,
+        )
+    }
+
+    fn size_hint(&self) -> Option<Sizes> {
+        None
+    }
+}
+
+impl Deserialize<'_, NamedEntityFormula> for NamedEntityDump {
+    fn deserialize(de: Deserializer) -> Result<Self, DeserializeError> {
+        let (id, components) =
+            <(u64, Vec<NamedComponentDump>) as Deserialize<NamedEntityFormula>>::deserialize(de)?;
+        Ok(NamedEntityDump { id, components })
+    }
+
+    fn deserialize_in_place(&mut self, de: Deserializer) -> Result<(), DeserializeError> {
+        *self = <Self as Deserialize<NamedEntityFormula>>::deserialize(de)?;
+        Ok(())
+    }
+}
+
+impl SerializeRef<NamedDumpFormula> for NamedDump {
+    fn serialize<B>(&self, sizes: &mut Sizes, buffer: B) -> Result<(), B::Error>
+    where
+        B: Buffer,
+    {
+        <&Vec<NamedEntityDump> as Serialize<NamedDumpFormula>>::serialize(
+            &self.entities,
+            sizes,
+            buffer,
+        )
+    }
+
+    fn size_hint(&self) -> Option<Sizes> {
+        None
+    }
+}
+
+impl Deserialize<'_, NamedDumpFormula> for NamedDump {
+    fn deserialize(de: Deserializer) -> Result<Self, DeserializeError> {
+        Ok(NamedDump {
+            entities: <Vec<NamedEntityDump> as Deserialize<NamedDumpFormula>>::deserialize(de)?,
+        })
+    }
+
+    fn deserialize_in_place(&mut self, de: Deserializer) -> Result<(), DeserializeError> {
+        <Vec<NamedEntityDump> as Deserialize<NamedDumpFormula>>::deserialize_in_place(
+            &mut self.entities,
+            de,
+        )
+    }
+}
+
+impl NamedRegistry {
+    /// Registers component type keyed by [`Component::name`]
+    /// that is serialized as `alkahest` packet with formula `F`.
+    ///
+    /// Values dumped with older versions are upgraded
+    /// with migrations registered with [`ComponentInfoRef::migration_alkahest`].
+    pub fn register_alkahest<T, F>(&mut self) -> &mut Self
+    where
+        F: BareFormula + ?Sized,
+        T: Component + Send + Sync + SerializeRef<F> + for<'de> Deserialize<'de, F>,
+    {
+        self.register_with(
+            T::name(),
+            |comp: &T, output: &mut Vec<u8>| {
+                write_packet_to_vec::<F, _>(comp, output);
+            },
+            |bytes: &[u8], migration: Option<&Migration>| match migration
+                .and_then(|m| Some((m, m.decoder::<DecodeAlkahest>()?)))
+            {
+                None => read_packet::<F, T>(bytes).ok().map(|(comp, _)| comp),
+                Some((migration, decode)) => {
+                    let old = (decode.read_packet)(bytes).ok()?;
+                    migration.upgrade::<DecodeAlkahest, T>(old).ok()
+                }
+            },
+        )
+    }
+}
+
+/// Decoder of values dumped with older version with `alkahest` formula.
+struct DecodeAlkahest {
+    read: fn(&mut Deserializer) -> Decoded<DeserializeError>,
+    read_packet: fn(&[u8]) -> Decoded<DeserializeError>,
+}
+
+fn decode_alkahest<F, T>(de: &mut Deserializer) -> Decoded<DeserializeError>
+where
+    F: Formula + ?Sized,
+    T: for<'de> Deserialize<'de, F> + 'static,
+{
+    Ok(Box::new(de.read_value::<F, T>(false)?))
+}
+
+fn decode_alkahest_packet<F, T>(bytes: &[u8]) -> Decoded<DeserializeError>
+where
+    F: Formula + ?Sized,
+    T: for<'de> Deserialize<'de, F> + 'static,
+{
+    let (value, _) = read_packet::<F, T>(bytes)?;
+    Ok(Box::new(value))
+}
+
+impl<T, D, S, I> ComponentInfoRef<'_, T, D, S, I>
+where
+    T: 'static,
+    D: DropHook<T>,
+    S: SetHook<T>,
+    I: InsertHook<T>,
+{
+    /// Registers migration of values dumped with version `from`
+    /// with `alkahest` formula `F`.
+    ///
+    /// Dumped value is decoded as `Old`
+    /// and `migration` converts it into value of version `from + 1`.
+    /// Also applies to [`NamedRegistry::register_alkahest`].
+    pub fn migration_alkahest<F, Old, New, M>(self, from: u32, migration: M) -> Self
+    where
+        F: Formula + ?Sized,
+        Old: for<'de> Deserialize<'de, F> + 'static,
+        New: 'static,
+        M: Fn(Old) -> New + Send + Sync + 'static,
+    {
+        let decoder = DecodeAlkahest {
+            read: decode_alkahest::<F, Old>,
+            read_packet: decode_alkahest_packet::<F, Old>,
+        };
+        self.migration_with(from, decoder, migration)
+    }
+}
 
 struct LoaderAlkahest<'de, F> {
-    iter: DeIter<'de, ([u64; 3], DumpFormula<F>), ([u64; 3], Lazy<'de, DumpFormula<F>>)>,
+    iter: DeIter<'de, (u8, [u64; 3], DumpFormula<F>), (u8, [u64; 3], Lazy<'de, DumpFormula<F>>)>,
     next: Option<Lazy<'de, DumpFormula<F>>>,
 }
 
+struct ResourceWriter<'a, RF, B> {
+    sizes: &'a mut Sizes,
+    buffer: B,
+    marker: PhantomData<fn() -> RF>,
+}
+
+impl<RF, Bu, Re> ResourceDumper<Re> for ResourceWriter<'_, RF, Bu>
+where
+    RF: Formula,
+    Bu: Buffer,
+    Re: ResourceSet,
+    for<'a> Re::DumpSlots<'a>: Serialize<DumpFormula<RF>>,
+{
+    type Error = Bu::Error;
+
+    fn dump_resources(&mut self, present: u64, slots: Re::DumpSlots<'_>) -> Result<(), Bu::Error> {
+        write_field::<(u64, DumpFormula<RF>), _, _>(
+            (present, slots),
+            self.sizes,
+            self.buffer.reborrow(),
+            false,
+        )
+    }
+}
+
+struct ResourceReader<'de, RF> {
+    present: u64,
+    lazy: Lazy<'de, DumpFormula<RF>>,
+}
+
+impl<'de, RF, Re> ResourceLoader<Re> for ResourceReader<'de, RF>
+where
+    RF: Formula,
+    Re: ResourceSet,
+    for<'a> Re::LoadSlots<'a>: Deserialize<'de, DumpFormula<RF>>,
+{
+    type Error = DeserializeError;
+
+    fn load_resources(&mut self, slots: &mut Re::LoadSlots<'_>) -> Result<(), DeserializeError> {
+        Re::skip_absent(self.present, slots);
+        self.lazy.get_in_place(slots)
+    }
+}
+
 macro_rules! dumper {
     (,) => {};
     ($($c:ident)+, $($f:ident)+) => {
@@ -63,14 +377,25 @@ macro_rules! dumper {
             }
         }
 
-        impl<'a, Bu $(, $f)+ $(, $c)+> Dumper<($($c,)+)> for SliceWriter<'a, ([u64; 3], DumpFormula<($($f,)+)>), Bu>
+        impl<'a, Bu $(, $f)+ $(, $c)+> Dumper<($($c,)+)> for SliceWriter<'a, (u8, [u64; 3], DumpFormula<($($f,)+)>), Bu>
         where
             Bu: Buffer + ?Sized,
-            $($f: Formula, $c: Sync + 'static, for<'b> &'b $c: Serialize<$f>,)+
+            $($f: Formula, $c: Dumpable, for<'b> &'b $c: Serialize<$f>,)+
         {
             type Error = Bu::Error;
             fn dump(&mut self, entity: EntityDump, tuple: ($(DumpSlot<'_, $c>,)+)) -> Result<(), Bu::Error> {
-                self.write_elem((entity.0, tuple))
+                let (tag, triple) = entity.to_record();
+                self.write_elem((tag, triple, tuple))
+            }
+
+            fn tombstone(&mut self, tombstone: TombstoneDump) -> Result<(), Bu::Error> {
+                let (tag, triple) = tombstone.to_record();
+                self.write_elem((tag, triple, ($(DumpSlot::<'_, $c>::Skipped,)+)))
+            }
+
+            fn version(&mut self, version: VersionDump) -> Result<(), Bu::Error> {
+                let (tag, triple) = version.to_record();
+                self.write_elem((tag, triple, ($(DumpSlot::<'_, $c>::Skipped,)+)))
             }
         }
 
@@ -82,24 +407,70 @@ macro_rules! dumper {
             pub fn dump_alkahest<$($f),+>(self, output: &mut Vec<u8>) -> (usize, usize)
             where
                 $($f: Formula,)+
-                $($c: Sync + 'static, for<'b> &'b $c: Serialize<$f>,)+
+                $($c: Dumpable, for<'b> &'b $c: Serialize<$f>,)+
             {
                 serialize_to_vec::<WorldFormula<($($f,)+)>, _>(self, output)
             }
         }
 
+        impl<'a $(, $c)+, Fi, Re> WorldDump<'a, ($($c,)+), Fi, Re>
+        where
+            Fi: ImmutableQuery,
+            Re: ResourceSet,
+        {
+            /// Serialize the world and resources with the given alkahest serializer.
+            pub fn dump_alkahest_with_resources<RF $(, $f)+>(self, output: &mut Vec<u8>) -> (usize, usize)
+            where
+                RF: Formula,
+                for<'b> Re::DumpSlots<'b>: Serialize<DumpFormula<RF>>,
+                $($f: Formula,)+
+                $($c: Dumpable, for<'b> &'b $c: Serialize<$f>,)+
+            {
+                serialize_to_vec::<WorldResourcesFormula<RF, ($($f,)+)>, _>(self, output)
+            }
+        }
+
+        impl<Fi, RF $(, $f)+ $(, $c)+, Re> Serialize<WorldResourcesFormula<RF, ($($f,)+)>> for WorldDump<'_, ($($c,)+), Fi, Re>
+        where
+            Fi: ImmutableQuery,
+            RF: Formula,
+            Re: ResourceSet,
+            for<'a> Re::DumpSlots<'a>: Serialize<DumpFormula<RF>>,
+            $($f: Formula,)+
+            $($c: Dumpable, for<'a> &'a $c: Serialize<$f>,)+
+        {
+            fn serialize<Bu>(self, sizes: &mut Sizes, mut buffer: Bu) -> Result<(), Bu::Error>
+            where
+                Self: Sized,
+                Bu: Buffer,
+            {
+                Re::dump_resources(self.world, &mut ResourceWriter::<RF, _> {
+                    sizes,
+                    buffer: buffer.reborrow(),
+                    marker: PhantomData,
+                })?;
+
+                let entities = WorldDump::<($($c,)+), Fi>::new(self.world, self.filter, self.epoch);
+                write_field::<WorldFormula<($($f,)+)>, _, _>(entities, sizes, buffer, true)
+            }
+
+            fn size_hint(&self) -> Option<Sizes> {
+                None
+            }
+        }
+
         impl<Fi $(, $f)+ $(, $c)+> Serialize<WorldFormula<($($f,)+)>> for WorldDump<'_, ($($c,)+), Fi>
         where
             Fi: ImmutableQuery,
             $($f: Formula,)+
-            $($c: Sync + 'static, for<'a> &'a $c: Serialize<$f>,)+
+            $($c: Dumpable, for<'a> &'a $c: Serialize<$f>,)+
         {
             fn serialize<Bu>(self, sizes: &mut Sizes, mut buffer: Bu) -> Result<(), Bu::Error>
             where
                 Self: Sized,
                 Bu: Buffer,
             {
-                let mut writer = slice_writer::<([u64; 3], DumpFormula<($($f,)+)>), _>(sizes, &mut buffer);
+                let mut writer = slice_writer::<(u8, [u64; 3], DumpFormula<($($f,)+)>), _>(sizes, &mut buffer);
                 <($($c,)+)>::dump_world(self.world, self.filter, self.epoch, &mut writer)?;
                 writer.finish()
             }
@@ -113,7 +484,7 @@ macro_rules! dumper {
         impl<'de $(, $f)+ $(, $c)+> Deserialize<'de, DumpFormula<($($f,)+)>> for ($(LoadSlot<'_, $c>,)+)
         where
             $($f: Formula,)+
-            $($c: Deserialize<'de, $f>,)+
+            $($c: Deserialize<'de, $f> + 'static,)+
         {
             fn deserialize(_: Deserializer<'de>) -> Result<Self, DeserializeError> {
                 unreachable!("This method should not be called")
@@ -135,6 +506,13 @@ macro_rules! dumper {
                         LoadSlot::Existing(comp) => {
                             de.read_in_place::<$f, _>(*comp, false)?;
                         }
+                        LoadSlot::Migrate(migration) => {
+                            let comp: $c = match migration.decoder::<DecodeAlkahest>() {
+                                None => de.read_value::<$f, _>(false)?,
+                                Some(decode) => migration.upgrade::<DecodeAlkahest, $c>((decode.read)(&mut de)?).map_err(|_| DeserializeError::Incompatible)?,
+                            };
+                            *$c = LoadSlot::Created(comp);
+                        }
                         LoadSlot::Created(_) => unreachable!(),
                     }
                 )+
@@ -145,21 +523,27 @@ macro_rules! dumper {
         impl<'de $(, $f)+ $(, $c)+> Loader<($($c,)+)> for LoaderAlkahest<'de, ($($f,)+)>
         where
             $($f: Formula,)+
-            $($c: Deserialize<'de, $f> + Component + Send + 'static,)+
+            $($c: Deserialize<'de, $f> + Dumpable,)+
         {
             type Error = DeserializeError;
 
-            fn next(&mut self) -> Result<Option<EntityDump>, DeserializeError> {
+            fn next(&mut self) -> Result<Option<LoadEntry>, DeserializeError> {
                 match self.iter.next() {
-                    Some(Ok((entity, lazy))) => {
+                    Some(Ok((tag, triple, lazy))) => {
                         self.next = Some(lazy);
-                        Ok(Some(EntityDump(entity)))
+                        LoadEntry::from_record(tag, triple)
+                            .map(Some)
+                            .ok_or(DeserializeError::WrongVariant(u32::from(tag)))
                     }
                     Some(Err(e)) => Err(e),
                     None => Ok(None),
                 }
             }
 
+            fn error(&self, _error: LoadError) -> DeserializeError {
+                DeserializeError::Incompatible
+            }
+
             fn load(&mut self, slots: &mut ($(LoadSlot<'_, $c>,)+)) -> Result<(), DeserializeError> {
                 let lazy = self.next.take().unwrap();
                 lazy.get_in_place(slots)
@@ -169,7 +553,7 @@ macro_rules! dumper {
         impl<Ma $(, $c)+> WorldLoad<'_, ($($c,)+), Ma>
         where
             Ma: Mark,
-            $($c: Component + Send + 'static,)+
+            $($c: Dumpable,)+
         {
             /// Deserialize the world with the given alkahest deserializer.
             pub fn load_alkahest_lazy<'de $(, $f)+>(
@@ -181,7 +565,7 @@ macro_rules! dumper {
                 $($f: Formula,)+
                 $($c: Deserialize<'de, $f>,)+
             {
-                let iter = lazy.iter::<([u64; 3], Lazy<'de, DumpFormula<($($f,)+)>>)>();
+                let iter = lazy.iter::<(u8, [u64; 3], Lazy<'de, DumpFormula<($($f,)+)>>)>();
 
                 <($($c,)+)>::load_world(self.world, self.marker, actions, &mut LoaderAlkahest {
                     iter,
@@ -203,6 +587,60 @@ macro_rules! dumper {
                 let lazy = deserialize_with_size::<WorldFormula<($($f,)+)>, _>(buffer, root)?;
                 self.load_alkahest_lazy(actions, lazy)
             }
+
+            /// Deserialize the world with the given alkahest deserializer.
+            /// Entities are loaded under fresh ids recorded in `remap`.
+            pub fn load_alkahest_remap<'de $(, $f)+>(
+                &self,
+                actions: &mut ActionEncoder,
+                buffer: &'de [u8],
+                root: usize,
+                remap: &mut EntityRemap,
+            ) -> Result<(), DeserializeError>
+            where
+                $($f: Formula,)+
+                $($c: Deserialize<'de, $f>,)+
+            {
+                let lazy: Lazy<'de, WorldFormula<($($f,)+)>> = deserialize_with_size::<WorldFormula<($($f,)+)>, _>(buffer, root)?;
+                let iter = lazy.iter::<(u8, [u64; 3], Lazy<'de, DumpFormula<($($f,)+)>>)>();
+
+                <($($c,)+)>::load_world_remap(self.world, self.marker, actions, &mut LoaderAlkahest {
+                    iter,
+                    next: None,
+                }, remap)
+            }
+        }
+
+        impl<Ma $(, $c)+, Re> WorldLoad<'_, ($($c,)+), Ma, Re>
+        where
+            Ma: Mark,
+            $($c: Dumpable,)+
+            Re: ResourceSet,
+        {
+            /// Deserialize the world and resources with the given alkahest deserializer.
+            pub fn load_alkahest_with_resources<'de, RF $(, $f)+>(
+                &self,
+                actions: &mut ActionEncoder,
+                buffer: &'de [u8],
+                root: usize,
+            ) -> Result<(), DeserializeError>
+            where
+                RF: Formula,
+                for<'b> Re::LoadSlots<'b>: Deserialize<'de, DumpFormula<RF>>,
+                $($f: Formula,)+
+                $($c: Deserialize<'de, $f>,)+
+            {
+                let ((present, resources), lazy): ((u64, Lazy<'de, DumpFormula<RF>>), Lazy<'de, WorldFormula<($($f,)+)>>) =
+                    deserialize_with_size::<WorldResourcesFormula<RF, ($($f,)+)>, _>(buffer, root)?;
+
+                Re::load_resources(actions, &mut ResourceReader { present, lazy: resources })?;
+
+                let iter = lazy.iter::<(u8, [u64; 3], Lazy<'de, DumpFormula<($($f,)+)>>)>();
+                <($($c,)+)>::load_world(self.world, self.marker, actions, &mut LoaderAlkahest {
+                    iter,
+                    next: None,
+                })
+            }
         }
     };
 }
@@ -215,7 +653,7 @@ fn test_dump() {
     use ::alkahest_proc::{Deserialize, Formula, SerializeRef};
 
     use super::NoMark;
-    use crate::{action::ActionBuffer, epoch::EpochId, world::World};
+    use crate::{action::ActionBuffer, component::Component, epoch::EpochId, world::World};
 
     let mut world = World::new();
 
@@ -288,3 +726,214 @@ fn test_dump() {
         Ok((Some(&Foo), Some(&Bar(155)), Some(&Baz("123456".into()))))
     );
 }
+
+#[test]
+fn test_dump_relations() {
+    use ::alkahest_proc::{Deserialize, Formula, SerializeRef};
+
+    use super::NoMark;
+    use crate::{
+        action::ActionBuffer,
+        component::Component,
+        epoch::EpochId,
+        relation::{ChildOf, Related, RelatesExclusive},
+        world::World,
+    };
+
+    let mut world = World::new();
+
+    #[derive(Component, Debug, PartialEq, Eq, Formula, SerializeRef, Deserialize)]
+    struct Bar(u32);
+
+    let parent = world.spawn((Bar(1),));
+    let child = world.spawn((Bar(2),));
+
+    world.add_relation(child, ChildOf, parent).unwrap();
+
+    type Set = (Bar, Relations<ChildOf>);
+
+    let mut data = Vec::new();
+    let (size, root) = WorldDump::<Set, _>::new(&world, (), EpochId::start())
+        .dump_alkahest::<Bar, Vec<(u64, ())>>(&mut data);
+
+    let mut world2 = World::new();
+
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world2);
+
+    WorldLoad::<Set, _>::new(&world2, NoMark)
+        .load_alkahest::<Bar, Vec<(u64, ())>>(&mut actions, &data[..size], root)
+        .unwrap();
+    buffer.execute(&mut world2);
+
+    assert_eq!(
+        world2.query_one_mut::<RelatesExclusive<&ChildOf>>(child),
+        Ok((&ChildOf, parent))
+    );
+    assert_eq!(
+        world2.query_one_mut::<Related<ChildOf>>(parent),
+        Ok(&[child][..])
+    );
+    assert_eq!(world2.query_one_mut::<&Bar>(child), Ok(&Bar(2)));
+}
+
+#[test]
+fn test_dump_resources() {
+    use ::alkahest_proc::{Deserialize, Formula, SerializeRef};
+
+    use super::NoMark;
+    use crate::{action::ActionBuffer, component::Component, epoch::EpochId, world::World};
+
+    #[derive(Component, Debug, PartialEq, Eq, Formula, SerializeRef, Deserialize)]
+    struct Bar(u32);
+
+    #[derive(Debug, PartialEq, Eq, Formula, SerializeRef, Deserialize)]
+    struct Score(u32);
+
+    let mut world = World::new();
+    let a = world.spawn((Bar(1),));
+    world.insert_resource(Score(42));
+
+    type Set = (Bar,);
+    type Res = (Score,);
+
+    let mut data = Vec::new();
+    let (size, root) = WorldDump::<Set, _>::new(&world, (), EpochId::start())
+        .with_resources::<Res>()
+        .dump_alkahest_with_resources::<(Score,), Bar>(&mut data);
+
+    let mut world2 = World::new();
+
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world2);
+
+    WorldLoad::<Set, _>::new(&world2, NoMark)
+        .with_resources::<Res>()
+        .load_alkahest_with_resources::<(Score,), Bar>(&mut actions, &data[..size], root)
+        .unwrap();
+    buffer.execute(&mut world2);
+
+    assert_eq!(world2.query_one_mut::<&Bar>(a), Ok(&Bar(1)));
+    assert_eq!(*world2.expect_resource::<Score>(), Score(42));
+}
+
+#[test]
+fn test_named_dump() {
+    use ::alkahest_proc::{Deserialize, Formula, SerializeRef};
+
+    use super::named::{Preserved, Unknown};
+    use crate::{component::Component, world::World};
+
+    #[derive(Component, Debug, PartialEq, Eq, Formula, SerializeRef, Deserialize)]
+    struct Foo(u32);
+
+    #[derive(Component, Debug, PartialEq, Eq, Formula, SerializeRef, Deserialize)]
+    struct Bar(String);
+
+    let mut world = World::new();
+    let a = world.spawn((Foo(1), Bar("a".into())));
+    let b = world.spawn((Bar("b".into()),));
+
+    let mut full = NamedRegistry::new();
+    full.register_alkahest::<Foo, Foo>()
+        .register_alkahest::<Bar, Bar>();
+
+    let dump = full.dump(&world);
+    let mut data = Vec::new();
+    write_packet_to_vec::<NamedDumpFormula, _>(&dump, &mut data);
+    let (loaded, _) = read_packet::<NamedDumpFormula, NamedDump>(&data).unwrap();
+    assert_eq!(loaded, dump);
+
+    // Registry without `Bar` preserves it.
+    let mut partial = NamedRegistry::new();
+    partial.register_alkahest::<Foo, Foo>();
+
+    let mut world2 = World::new();
+    partial
+        .load(&mut world2, &loaded, Unknown::Preserve)
+        .unwrap();
+    assert_eq!(world2.query_one_mut::<&Foo>(a), Ok(&Foo(1)));
+    assert!(world2.query_one_mut::<&Preserved>(b).is_ok());
+
+    // Preserved components are written back and loaded by full registry.
+    let mut data = Vec::new();
+    write_packet_to_vec::<NamedDumpFormula, _>(&partial.dump(&world2), &mut data);
+    let (loaded, _) = read_packet::<NamedDumpFormula, NamedDump>(&data).unwrap();
+
+    let mut world3 = World::new();
+    full.load(&mut world3, &loaded, Unknown::Skip).unwrap();
+    assert_eq!(
+        world3.query_one_mut::<(&Foo, &Bar)>(a),
+        Ok((&Foo(1), &Bar("a".into())))
+    );
+    assert_eq!(world3.query_one_mut::<&Bar>(b), Ok(&Bar("b".into())));
+}
+
+#[test]
+fn test_load_migrations() {
+    use ::alkahest_proc::{Deserialize, Formula, SerializeRef};
+
+    use super::{named::Unknown, NoMark};
+    use crate::{action::ActionBuffer, component::Component, epoch::EpochId, world::World};
+
+    #[derive(Component, Formula, SerializeRef, Deserialize)]
+    struct Pos0 {
+        x: u32,
+    }
+
+    #[derive(Component, Debug, PartialEq, Eq, Formula, SerializeRef, Deserialize)]
+    struct Pos {
+        x: u64,
+        y: u64,
+    }
+
+    let build = || {
+        let mut builder = World::builder();
+        builder
+            .register_component::<Pos>()
+            .version(1)
+            .migration_alkahest::<Pos0, _, _, _>(0, |old: Pos0| Pos {
+                x: u64::from(old.x),
+                y: 7,
+            });
+        builder.build()
+    };
+
+    let mut world0 = World::new();
+    let a = world0.spawn((Pos0 { x: 1 },));
+    let mut old = Vec::new();
+    let (old_size, old_root) =
+        WorldDump::<(Pos0,), _>::new(&world0, (), EpochId::start()).dump_alkahest::<Pos0>(&mut old);
+
+    let mut world1 = build();
+    world1.spawn(());
+    let b = world1.spawn((Pos { x: 2, y: 3 },));
+    let mut current = Vec::new();
+    let (size, root) = WorldDump::<(Pos,), _>::new(&world1, (), EpochId::start())
+        .dump_alkahest::<Pos>(&mut current);
+
+    let mut world = build();
+    let mut buffer = ActionBuffer::new();
+    for (data, root) in [(&old[..old_size], old_root), (&current[..size], root)] {
+        let mut actions = buffer.encoder(&world);
+        WorldLoad::<(Pos,), _>::new(&world, NoMark)
+            .load_alkahest::<Pos>(&mut actions, data, root)
+            .unwrap();
+        buffer.execute(&mut world);
+    }
+
+    assert_eq!(world.query_one_mut::<&Pos>(a), Ok(&Pos { x: 1, y: 7 }));
+    assert_eq!(world.query_one_mut::<&Pos>(b), Ok(&Pos { x: 2, y: 3 }));
+
+    // Named dumps use the same migrations.
+    let mut registry = NamedRegistry::new();
+    registry.register_alkahest::<Pos0, Pos0>();
+    let mut dump = registry.dump(&world0);
+    dump.entities[0].components[0].key = Pos::name().into();
+
+    let mut registry = NamedRegistry::new();
+    registry.register_alkahest::<Pos, Pos>();
+    let mut world2 = build();
+    registry.load(&mut world2, &dump, Unknown::Skip).unwrap();
+    assert_eq!(world2.query_one_mut::<&Pos>(a), Ok(&Pos { x: 1, y: 7 }));
+}
@@ -6,17 +6,48 @@
 //! - `alkahest`
 //!
 //! Each can be enabled with a feature named as serialization crate.
+//!
+//! Dump and load sets are tuples of components.
+//! Relations are dumped and loaded by adding [`Relations<R>`] to the set.
+//!
+//! Loaded entities keep their ids by default.
+//! To load entities under freshly allocated ids use [`LoadSet::load_world_remap`]
+//! with [`EntityRemap`] table.
+//! Entity ids stored in components are rewritten via [`Component::map_entities`].
+//!
+//! For dumps that are not tied to a tuple of components see [`named`] module.
+//!
+//! Resources are dumped and loaded alongside entities
+//! with [`WorldDump::with_resources`] and [`WorldLoad::with_resources`].
+//!
+//! Component versions set with [`ComponentInfoRef::version`] are written to dumps.
+//! Values dumped with older versions are decoded as the old type
+//! registered for the backend, e.g. with `ComponentInfoRef::migration_bin`,
+//! and converted up to the current version before they are inserted.
+//! Versions without registered migration are decoded as the next version.
+//!
+//! [`ComponentInfoRef::version`]: crate::component::ComponentInfoRef::version
 
 mod query;
 
-use core::marker::PhantomData;
+use core::{
+    any::{type_name, Any, TypeId},
+    borrow::Borrow,
+    fmt,
+    marker::PhantomData,
+    ptr::NonNull,
+};
+
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use hashbrown::HashMap;
 
 use crate::{
     action::ActionEncoder,
     component::Component,
     entity::EntityId,
     epoch::EpochId,
-    query::ImmutableQuery,
+    query::{DefaultQuery, ImmutableQuery, QueryItem},
+    relation::{OriginComponent, Relation},
     world::{QueryOneError, World},
 };
 
@@ -26,6 +57,8 @@ use self::query::DumpQuery;
 #[cfg(feature = "alkahest")]
 pub mod alkahest;
 
+pub mod named;
+
 #[cfg(feature = "nanoserde")]
 pub mod nanoserde;
 
@@ -35,6 +68,431 @@ pub mod serde;
 /// Opaque integer triple that needs to be serialized and deserialized.
 pub struct EntityDump(pub [u64; 3]);
 
+impl EntityDump {
+    /// Packs entity into record kind tag and integer triple.
+    #[inline]
+    pub fn to_record(&self) -> (u8, [u64; 3]) {
+        (RecordKind::Entity.tag(), self.0)
+    }
+}
+
+/// Opaque integer of despawned entity that needs to be serialized and deserialized.
+///
+/// Incremental dumps contain tombstones of entities despawned after dumped epoch.
+/// See [`WorldBuilder::with_tombstones`](crate::world::WorldBuilder::with_tombstones).
+pub struct TombstoneDump(pub u64);
+
+impl TombstoneDump {
+    /// Packs tombstone into record kind tag and integer triple.
+    #[inline]
+    pub fn to_record(&self) -> (u8, [u64; 3]) {
+        (RecordKind::Tombstone.tag(), [self.0, 0, 0])
+    }
+}
+
+/// Version of component in one slot of dump and load sets.
+///
+/// Dumped only for components with non-zero version.
+pub struct VersionDump {
+    /// Index of the slot in the set.
+    pub slot: u32,
+
+    /// Version of the component.
+    pub version: u32,
+}
+
+impl VersionDump {
+    /// Packs version into record kind tag and integer triple.
+    #[inline]
+    pub fn to_record(&self) -> (u8, [u64; 3]) {
+        (
+            RecordKind::Version.tag(),
+            [u64::from(self.slot), u64::from(self.version), 0],
+        )
+    }
+}
+
+/// Kind of record in a dump.
+///
+/// Backends that store all records in the same shape
+/// write tag of the kind next to the record's integer triple.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordKind {
+    /// Entity with components.
+    Entity,
+
+    /// Despawned entity.
+    Tombstone,
+
+    /// Version of component in a slot.
+    Version,
+}
+
+impl RecordKind {
+    /// Returns tag of the kind.
+    #[inline]
+    pub fn tag(self) -> u8 {
+        match self {
+            RecordKind::Entity => 0,
+            RecordKind::Tombstone => 1,
+            RecordKind::Version => 2,
+        }
+    }
+
+    /// Returns kind with specified tag.
+    /// Returns `None` if tag is unknown.
+    #[inline]
+    pub fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(RecordKind::Entity),
+            1 => Some(RecordKind::Tombstone),
+            2 => Some(RecordKind::Version),
+            _ => None,
+        }
+    }
+}
+
+/// Entry to load.
+pub enum LoadEntry {
+    /// Entity with components.
+    Entity(EntityDump),
+
+    /// Despawned entity.
+    Tombstone(TombstoneDump),
+
+    /// Version of component in a slot.
+    Version(VersionDump),
+}
+
+impl LoadEntry {
+    /// Unpacks entry from record created by
+    /// [`EntityDump::to_record`], [`TombstoneDump::to_record`]
+    /// or [`VersionDump::to_record`].
+    ///
+    /// Returns `None` if tag is unknown.
+    #[inline]
+    pub fn from_record(tag: u8, triple: [u64; 3]) -> Option<Self> {
+        let entry = match RecordKind::from_tag(tag)? {
+            RecordKind::Entity => LoadEntry::Entity(EntityDump(triple)),
+            RecordKind::Tombstone => LoadEntry::Tombstone(TombstoneDump(triple[0])),
+            RecordKind::Version => LoadEntry::Version(VersionDump {
+                slot: triple[0] as u32,
+                version: triple[1] as u32,
+            }),
+        };
+        Some(entry)
+    }
+}
+
+/// Type that can be an element of [`DumpSet`] and [`LoadSet`].
+///
+/// Implemented for all components and for [`Relations<R>`].
+pub trait Dumpable: Send + Sync + Sized + 'static {
+    #[doc(hidden)]
+    type Dump<'a>: Borrow<Self>;
+
+    #[doc(hidden)]
+    type Existing: DefaultQuery;
+
+    /// Returns id of the component that stores this value.
+    #[doc(hidden)]
+    fn component_id() -> TypeId;
+
+    /// Returns value to dump from component at specified index.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to the array of components with id [`Dumpable::component_id`].
+    /// `idx` must be in bounds.
+    #[doc(hidden)]
+    unsafe fn dump<'a>(ptr: NonNull<u8>, idx: usize) -> Self::Dump<'a>;
+
+    /// Returns existing value that can be loaded in place.
+    #[doc(hidden)]
+    fn existing<'a>(item: QueryItem<'a, Self::Existing>) -> Option<&'a mut Self>;
+
+    /// Inserts loaded value to the entity.
+    #[doc(hidden)]
+    fn insert(self, world: &mut World, id: EntityId);
+
+    /// Removes value from the entity.
+    #[doc(hidden)]
+    fn remove(world: &mut World, id: EntityId);
+
+    /// Rewrites entity ids stored in the value.
+    #[doc(hidden)]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId);
+
+    /// Returns current version of the value schema.
+    #[doc(hidden)]
+    fn version(world: &World) -> u32;
+
+    /// Returns migration of values dumped with specified version.
+    /// Returns `None` if values are loaded as is.
+    /// Returns error if dumped version is newer than the current one.
+    #[doc(hidden)]
+    fn migration(world: &World, from_version: u32) -> Result<Option<Migration>, LoadError>;
+}
+
+impl<T> Dumpable for T
+where
+    T: Component + Send + Sync,
+{
+    type Dump<'a> = &'a T;
+    type Existing = Option<&'static mut T>;
+
+    #[inline]
+    fn component_id() -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    #[inline]
+    unsafe fn dump<'a>(ptr: NonNull<u8>, idx: usize) -> Self::Dump<'a> {
+        unsafe { &*ptr.cast::<T>().as_ptr().add(idx) }
+    }
+
+    #[inline]
+    fn existing<'a>(item: QueryItem<'a, Self::Existing>) -> Option<&'a mut T> {
+        item
+    }
+
+    #[inline]
+    fn insert(self, world: &mut World, id: EntityId) {
+        let _ = world.insert(id, self);
+    }
+
+    #[inline]
+    fn remove(world: &mut World, id: EntityId) {
+        let _ = world.drop::<T>(id);
+    }
+
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        Component::map_entities(self, mapper);
+    }
+
+    #[inline]
+    fn version(world: &World) -> u32 {
+        match world.component_info(TypeId::of::<T>()) {
+            None => 0,
+            Some(info) => info.version(),
+        }
+    }
+
+    #[inline]
+    fn migration(world: &World, from_version: u32) -> Result<Option<Migration>, LoadError> {
+        match world.component_info(TypeId::of::<T>()) {
+            None => Ok(None),
+            Some(info) => info.migration(from_version),
+        }
+    }
+}
+
+/// Relations of type `R` of one entity.
+///
+/// Add this type to dump and load sets to dump and load relations.
+/// Targets are stored as opaque integers
+/// that are mapped back to entities the same way as loaded entities.
+/// On load relations are added with [`World::add_relation`]
+/// which restores both origin and target sides.
+/// Missing target entities are spawned.
+pub struct Relations<R> {
+    /// Opaque target ids and relation values.
+    pub targets: Vec<(u64, R)>,
+}
+
+impl<R> Dumpable for Relations<R>
+where
+    R: Relation,
+{
+    type Dump<'a> = Relations<R>;
+    type Existing = ();
+
+    #[inline]
+    fn component_id() -> TypeId {
+        TypeId::of::<OriginComponent<R>>()
+    }
+
+    #[inline]
+    unsafe fn dump<'a>(ptr: NonNull<u8>, idx: usize) -> Self::Dump<'a> {
+        let component = unsafe { &*ptr.cast::<OriginComponent<R>>().as_ptr().add(idx) };
+        Relations {
+            targets: component
+                .origins()
+                .iter()
+                .map(|origin| (origin.target.bits(), origin.relation))
+                .collect(),
+        }
+    }
+
+    #[inline]
+    fn existing<'a>((): QueryItem<'a, Self::Existing>) -> Option<&'a mut Self> {
+        None
+    }
+
+    fn insert(self, world: &mut World, id: EntityId) {
+        Self::remove(world, id);
+
+        for (bits, relation) in self.targets {
+            if let Some(target) = EntityId::from_bits(bits) {
+                world.spawn_if_missing(target);
+                let _ = world.add_relation(id, relation, target);
+            }
+        }
+    }
+
+    #[inline]
+    fn remove(world: &mut World, id: EntityId) {
+        let _ = world.drop::<OriginComponent<R>>(id);
+    }
+
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        for (bits, _) in &mut self.targets {
+            if let Some(target) = EntityId::from_bits(*bits) {
+                *bits = mapper(target).bits();
+            }
+        }
+    }
+
+    #[inline]
+    fn version(_world: &World) -> u32 {
+        0
+    }
+
+    #[inline]
+    fn migration(_world: &World, from_version: u32) -> Result<Option<Migration>, LoadError> {
+        if from_version != 0 {
+            return Err(LoadError::UnsupportedVersion {
+                name: type_name::<R>(),
+                version: from_version,
+                current: 0,
+            });
+        }
+        Ok(None)
+    }
+}
+
+/// Trait for values that store entity ids.
+///
+/// Used to rewrite entity ids when entities are loaded under new ids.
+/// Components can use it with `#[edict(map_entities)]` attribute of the derive macro.
+pub trait MapEntities {
+    /// Replaces every stored entity id with the value returned by `mapper`.
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId);
+}
+
+impl MapEntities for EntityId {
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        *self = mapper(*self);
+    }
+}
+
+impl<T> MapEntities for Option<T>
+where
+    T: MapEntities,
+{
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        if let Some(value) = self {
+            value.map_entities(mapper);
+        }
+    }
+}
+
+impl<T> MapEntities for [T]
+where
+    T: MapEntities,
+{
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        for value in self {
+            value.map_entities(mapper);
+        }
+    }
+}
+
+impl<T, const N: usize> MapEntities for [T; N]
+where
+    T: MapEntities,
+{
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        self[..].map_entities(mapper);
+    }
+}
+
+impl<T> MapEntities for Vec<T>
+where
+    T: MapEntities,
+{
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        self[..].map_entities(mapper);
+    }
+}
+
+impl<T> MapEntities for Box<T>
+where
+    T: MapEntities + ?Sized,
+{
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        (**self).map_entities(mapper);
+    }
+}
+
+/// Table that maps dumped entity ids to entities allocated on load.
+///
+/// Keep the same table across loads to map entities consistently,
+/// e.g. when delta dumps are applied after initial one.
+#[derive(Default)]
+pub struct EntityRemap {
+    map: HashMap<EntityId, EntityId>,
+}
+
+impl EntityRemap {
+    /// Returns new empty remap table.
+    #[inline]
+    pub fn new() -> Self {
+        EntityRemap {
+            map: HashMap::new(),
+        }
+    }
+
+    /// Returns entity allocated for dumped entity id.
+    #[inline]
+    pub fn get(&self, dumped: EntityId) -> Option<EntityId> {
+        self.map.get(&dumped).copied()
+    }
+
+    /// Returns entity allocated for dumped entity id.
+    /// Allocates new entity in the world if dumped id is not mapped yet.
+    #[inline]
+    pub fn map(&mut self, dumped: EntityId, world: &World) -> EntityId {
+        *self.map.entry(dumped).or_insert_with(|| world.allocate())
+    }
+
+    /// Returns iterator over pairs of dumped and allocated entity ids.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, EntityId)> + '_ {
+        self.map.iter().map(|(&dumped, &id)| (dumped, id))
+    }
+
+    /// Returns number of mapped entities.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if no entities are mapped.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
 /// Serializer implementation.
 pub trait Dumper<T: DumpSet + ?Sized> {
     /// Type of possible errors that can occur during serialization.
@@ -42,6 +500,12 @@ pub trait Dumper<T: DumpSet + ?Sized> {
 
     /// Serialize entity with provided component slots.
     fn dump(&mut self, entity: EntityDump, tuple: T::DumpSlots<'_>) -> Result<(), Self::Error>;
+
+    /// Serialize tombstone of despawned entity.
+    fn tombstone(&mut self, tombstone: TombstoneDump) -> Result<(), Self::Error>;
+
+    /// Serialize version of component in a slot.
+    fn version(&mut self, version: VersionDump) -> Result<(), Self::Error>;
 }
 
 /// Slot of component value to be serialized.
@@ -64,17 +528,211 @@ pub enum LoadSlot<'a, T> {
     /// Deserialize into existing component value.
     Existing(&'a mut T),
 
+    /// Deserialize value dumped with older version
+    /// and upgrade it with the migration.
+    /// Loader must replace it with [`LoadSlot::Created`].
+    Migrate(&'a Migration),
+
     /// Newly created component.
     Created(T),
 }
 
+/// Result of decoding value dumped with older version.
+pub(crate) type Decoded<E> = Result<Box<dyn Any>, E>;
+
+/// Migration step registered for a component.
+#[derive(Clone)]
+pub(crate) struct MigrationStep {
+    /// Version of values decoded by this step.
+    from: u32,
+
+    /// Backend-specific decoder of values dumped with version `from`.
+    decoder: Arc<dyn Any + Send + Sync>,
+
+    /// Converts decoded value into value of the next version.
+    convert: ConvertFn,
+}
+
+/// Function that converts decoded value into value of the next version.
+/// Returns `None` if decoded value has unexpected type.
+type ConvertFn = Arc<dyn Fn(Box<dyn Any>) -> Option<Box<dyn Any>> + Send + Sync>;
+
+impl MigrationStep {
+    pub(crate) fn new<D, Old, New, F>(from: u32, decoder: D, convert: F) -> Self
+    where
+        D: Send + Sync + 'static,
+        Old: 'static,
+        New: 'static,
+        F: Fn(Old) -> New + Send + Sync + 'static,
+    {
+        MigrationStep {
+            from,
+            decoder: Arc::new(decoder),
+            convert: Arc::new(move |old: Box<dyn Any>| match old.downcast::<Old>() {
+                Ok(old) => Some(Box::new(convert(*old))),
+                Err(_) => None,
+            }),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn from(&self) -> u32 {
+        self.from
+    }
+}
+
+/// Migration of component values dumped with older version.
+///
+/// Migration steps are registered per backend.
+/// Loader decodes dumped value with the decoder of the first step
+/// registered for the backend with version not older than dumped one.
+/// Then decoded value is converted by that step and each following step
+/// of the same backend up to the current version.
+/// If there is no such step, value is decoded as the current type.
+#[derive(Clone)]
+pub struct Migration {
+    name: &'static str,
+    from: u32,
+    to: u32,
+    steps: Arc<[MigrationStep]>,
+}
+
+impl Migration {
+    pub(crate) fn new(name: &'static str, from: u32, to: u32, steps: Arc<[MigrationStep]>) -> Self {
+        Migration {
+            name,
+            from,
+            to,
+            steps,
+        }
+    }
+
+    /// Returns version of dumped values.
+    #[inline]
+    pub fn version(&self) -> u32 {
+        self.from
+    }
+
+    fn steps<D: 'static>(&self) -> impl Iterator<Item = &MigrationStep> + '_ {
+        self.steps
+            .iter()
+            .filter(move |step| step.from >= self.from && step.from < self.to)
+            .filter(|step| step.decoder.is::<D>())
+    }
+
+    /// Returns decoder of dumped values registered for the backend
+    /// with decoder type `D`.
+    ///
+    /// Returns `None` if dumped values should be decoded as the current type.
+    #[inline]
+    pub fn decoder<D: 'static>(&self) -> Option<&D> {
+        self.steps::<D>()
+            .next()
+            .map(|step| step.decoder.downcast_ref().unwrap())
+    }
+
+    /// Upgrades value decoded by [`Migration::decoder`] to the current version.
+    ///
+    /// Returns [`LoadError::IncompleteMigration`] if registered migrations
+    /// do not produce value of type `T`,
+    /// e.g. when steps for some versions are not registered for the backend.
+    pub fn upgrade<D: 'static, T: 'static>(&self, value: Box<dyn Any>) -> Result<T, LoadError> {
+        let incomplete = LoadError::IncompleteMigration {
+            name: self.name,
+            version: self.from,
+        };
+
+        let mut value = value;
+        for step in self.steps::<D>() {
+            value = (step.convert)(value).ok_or(incomplete)?;
+        }
+
+        match value.downcast::<T>() {
+            Ok(value) => Ok(*value),
+            Err(_) => Err(incomplete),
+        }
+    }
+}
+
+/// Error that may occur when entities are loaded with any backend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadError {
+    /// Component was dumped with version newer than the current one.
+    UnsupportedVersion {
+        /// Name of the component.
+        name: &'static str,
+
+        /// Dumped version.
+        version: u32,
+
+        /// Current version.
+        current: u32,
+    },
+
+    /// Registered migrations do not upgrade dumped values to the current version.
+    IncompleteMigration {
+        /// Name of the component.
+        name: &'static str,
+
+        /// Dumped version.
+        version: u32,
+    },
+
+    /// Loader did not load value into a slot that requires it.
+    MissingValue {
+        /// Name of the value type.
+        name: &'static str,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion {
+                name,
+                version,
+                current,
+            } => write!(
+                f,
+                "Component `{}` is dumped with version {} newer than current version {}",
+                name, version, current
+            ),
+            Self::IncompleteMigration { name, version } => write!(
+                f,
+                "Migrations of component `{}` from version {} do not reach current version",
+                name, version
+            ),
+            Self::MissingValue { name } => {
+                write!(f, "Loader did not load value of `{}`", name)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LoadError {}
+
 /// Deserializer implementation.
 pub trait Loader<T: LoadSet + ?Sized> {
     /// Type of possible errors that can occur during deserialization.
     type Error;
 
-    /// Returns next entity to load.
-    fn next(&mut self) -> Result<Option<EntityDump>, Self::Error>;
+    /// Returns next entry to load.
+    fn next(&mut self) -> Result<Option<LoadEntry>, Self::Error>;
+
+    /// Converts error that is not specific to the backend.
+    fn error(&self, error: LoadError) -> Self::Error;
+
+    /// Sets migration of values in the slot.
+    /// Called before first entry and after each [`LoadEntry::Version`].
+    ///
+    /// Loaders that decode values before [`Loader::load`] is called
+    /// use it to decode values dumped with older version.
+    /// Other loaders may ignore it and handle [`LoadSlot::Migrate`] instead.
+    #[inline]
+    fn migration(&mut self, slot: u32, migration: Option<&Migration>) {
+        let _ = (slot, migration);
+    }
 
     /// Loads entity with provided component slots.
     fn load(&mut self, slots: &mut T::LoadSlots<'_>) -> Result<(), Self::Error>;
@@ -112,8 +770,11 @@ pub trait DumpSet {
     type DumpSlots<'a>;
 
     /// Serializes entities from the world.
-    /// Calls `dumper` for each entity
+    /// Calls `dumper` for each component with non-zero version first.
+    /// Then calls `dumper` for each entity
     /// with opaque integers and tuple of slots.
+    /// Then calls `dumper` for each tombstone of entity despawned after `after_epoch`.
+    /// Tombstones are not filtered.
     /// The `dumper` should perform serialization of those values.
     /// The loading process will run in reverse, where loader will have to
     /// deserialize integer triples and tuple of slots
@@ -127,6 +788,13 @@ pub trait DumpSet {
     where
         F: ImmutableQuery,
         D: for<'a> Dumper<Self, Error = E>;
+
+    /// Returns number of entries [`DumpSet::dump_world`] passes to the dumper
+    /// with the same arguments.
+    /// Allows backends to write length of the dump upfront.
+    fn dump_len<F>(world: &World, filter: F, after_epoch: EpochId) -> usize
+    where
+        F: ImmutableQuery;
 }
 
 /// Tuple of components that can be loaded.
@@ -135,19 +803,58 @@ pub trait LoadSet {
     type LoadSlots<'a>;
 
     /// Loads serialized entities into the world.
+    #[inline]
     fn load_world<L, E, M>(
         world: &World,
         marker: M,
         actions: &mut ActionEncoder,
         loader: &mut L,
     ) -> Result<(), E>
+    where
+        L: for<'a> Loader<Self, Error = E>,
+        M: Mark,
+    {
+        Self::load_world_with(world, marker, actions, loader, None)
+    }
+
+    /// Loads serialized entities into the world under fresh ids.
+    ///
+    /// Each dumped entity is mapped to an entity allocated in the world
+    /// and mapping is recorded in `remap`.
+    /// Entity ids stored in loaded values are rewritten using the same mapping.
+    #[inline]
+    fn load_world_remap<L, E, M>(
+        world: &World,
+        marker: M,
+        actions: &mut ActionEncoder,
+        loader: &mut L,
+        remap: &mut EntityRemap,
+    ) -> Result<(), E>
+    where
+        L: for<'a> Loader<Self, Error = E>,
+        M: Mark,
+    {
+        Self::load_world_with(world, marker, actions, loader, Some(remap))
+    }
+
+    #[doc(hidden)]
+    fn load_world_with<L, E, M>(
+        world: &World,
+        marker: M,
+        actions: &mut ActionEncoder,
+        loader: &mut L,
+        remap: Option<&mut EntityRemap>,
+    ) -> Result<(), E>
     where
         L: for<'a> Loader<Self, Error = E>,
         M: Mark;
 }
 
 /// Wrapper for `World` that implements `Serialize`.
-pub struct WorldDump<'a, T, F> {
+///
+/// Dumps entities with components from set `T`
+/// and resources from set `R`.
+pub struct WorldDump<'a, T, F, R = ()> {
     /// World to dump from.
     pub world: &'a World,
 
@@ -156,7 +863,7 @@ pub struct WorldDump<'a, T, F> {
 
     /// Epoch to dump after.
     pub epoch: EpochId,
-    marker: PhantomData<fn() -> T>,
+    marker: PhantomData<fn() -> (T, R)>,
 }
 
 impl<'a, T, F> WorldDump<'a, T, F> {
@@ -171,14 +878,34 @@ impl<'a, T, F> WorldDump<'a, T, F> {
     }
 }
 
+impl<'a, T, F, R> WorldDump<'a, T, F, R> {
+    /// Returns `WorldDump` that also dumps resources from set `S`.
+    ///
+    /// Resources are dumped before entities regardless of epoch.
+    pub fn with_resources<S>(self) -> WorldDump<'a, T, F, S>
+    where
+        S: ResourceSet,
+    {
+        WorldDump {
+            world: self.world,
+            filter: self.filter,
+            epoch: self.epoch,
+            marker: PhantomData,
+        }
+    }
+}
+
 /// Wrapper for `World` that implements `Serialize`.
-pub struct WorldLoad<'a, T, M> {
+///
+/// Loads entities with components from set `T`
+/// and resources from set `R`.
+pub struct WorldLoad<'a, T, M, R = ()> {
     /// World to load into.
     pub world: &'a World,
 
     /// Marker to mark loaded entities.
     pub marker: M,
-    _marker: PhantomData<fn() -> T>,
+    _marker: PhantomData<fn() -> (T, R)>,
 }
 
 impl<'a, T, M> WorldLoad<'a, T, M> {
@@ -192,6 +919,163 @@ impl<'a, T, M> WorldLoad<'a, T, M> {
     }
 }
 
+impl<'a, T, M, R> WorldLoad<'a, T, M, R> {
+    /// Returns `WorldLoad` that also loads resources from set `S`.
+    ///
+    /// Set must match the one used to dump the world.
+    pub fn with_resources<S>(self) -> WorldLoad<'a, T, M, S>
+    where
+        S: ResourceSet,
+    {
+        WorldLoad {
+            world: self.world,
+            marker: self.marker,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Serializer implementation for resources.
+pub trait ResourceDumper<R: ResourceSet + ?Sized> {
+    /// Type of possible errors that can occur during serialization.
+    type Error;
+
+    /// Serialize resources with provided slots.
+    /// Bits of `present` mask are set for resources that are present in the world.
+    fn dump_resources(&mut self, present: u64, slots: R::DumpSlots<'_>) -> Result<(), Self::Error>;
+}
+
+/// Deserializer implementation for resources.
+pub trait ResourceLoader<R: ResourceSet + ?Sized> {
+    /// Type of possible errors that can occur during deserialization.
+    type Error;
+
+    /// Loads resources into provided slots.
+    /// All slots are [`LoadSlot::Missing`] initially.
+    /// Loader must replace them with [`LoadSlot::Created`] for resources that were dumped
+    /// and [`LoadSlot::Skipped`] for resources that were missing.
+    fn load_resources(&mut self, slots: &mut R::LoadSlots<'_>) -> Result<(), Self::Error>;
+}
+
+/// Tuple of resources that can be dumped and loaded.
+///
+/// Empty tuple does not dump or load anything
+/// and keeps format of the dump unchanged.
+pub trait ResourceSet {
+    /// Number of resources in the set.
+    const LEN: usize;
+
+    /// Tuple of dump slots of resource types.
+    type DumpSlots<'a>;
+
+    /// Tuple of load slots of resource types.
+    type LoadSlots<'a>;
+
+    /// Serializes resources from the world.
+    fn dump_resources<D, E>(world: &World, dumper: &mut D) -> Result<(), E>
+    where
+        D: ResourceDumper<Self, Error = E>;
+
+    /// Loads serialized resources into the world.
+    fn load_resources<L, E>(actions: &mut ActionEncoder, loader: &mut L) -> Result<(), E>
+    where
+        L: ResourceLoader<Self, Error = E>;
+
+    /// Marks slots of resources with unset bits in `present` mask as [`LoadSlot::Skipped`].
+    fn skip_absent(present: u64, slots: &mut Self::LoadSlots<'_>);
+}
+
+impl ResourceSet for () {
+    const LEN: usize = 0;
+    type DumpSlots<'a> = ();
+    type LoadSlots<'a> = ();
+
+    #[inline]
+    fn dump_resources<D, E>(_world: &World, _dumper: &mut D) -> Result<(), E>
+    where
+        D: ResourceDumper<Self, Error = E>,
+    {
+        Ok(())
+    }
+
+    #[inline]
+    fn load_resources<L, E>(_actions: &mut ActionEncoder, _loader: &mut L) -> Result<(), E>
+    where
+        L: ResourceLoader<Self, Error = E>,
+    {
+        Ok(())
+    }
+
+    #[inline]
+    fn skip_absent(_present: u64, _slots: &mut ()) {}
+}
+
+macro_rules! resource_set {
+    () => {};
+    ($($a:ident)+) => {
+        #[allow(non_snake_case)]
+        #[allow(unused_assignments)]
+        impl<$($a),+> ResourceSet for ($($a,)+)
+        where
+            $($a: Send + Sync + 'static,)+
+        {
+            const LEN: usize = [$(stringify!($a)),+].len();
+
+            type DumpSlots<'a> = ($(DumpSlot<'a, $a>,)+);
+            type LoadSlots<'a> = ($(LoadSlot<'a, $a>,)+);
+
+            fn dump_resources<Du, Er>(world: &World, dumper: &mut Du) -> Result<(), Er>
+            where
+                Du: ResourceDumper<Self, Error = Er>,
+            {
+                $(let $a = world.get_resource::<$a>();)+
+
+                let mut present = 0;
+                let slots = indexed_tuple!(idx => $(match &$a {
+                    None => DumpSlot::Skipped,
+                    Some(res) => {
+                        present |= 1 << idx;
+                        DumpSlot::Component(&**res)
+                    }
+                }),+);
+
+                dumper.dump_resources(present, slots)
+            }
+
+            fn load_resources<Lo, Er>(actions: &mut ActionEncoder, loader: &mut Lo) -> Result<(), Er>
+            where
+                Lo: ResourceLoader<Self, Error = Er>,
+            {
+                let mut slots = ($(LoadSlot::<$a>::Missing,)+);
+                loader.load_resources(&mut slots)?;
+
+                let ($($a,)+) = slots;
+                $(
+                    match $a {
+                        LoadSlot::Created(res) => actions.closure(move |world| world.insert_resource(res)),
+                        LoadSlot::Skipped => {}
+                        LoadSlot::Missing | LoadSlot::Existing(_) | LoadSlot::Migrate(_) => unreachable!("Must be created or skipped by loader"),
+                    }
+                )+
+                Ok(())
+            }
+
+            fn skip_absent(present: u64, slots: &mut ($(LoadSlot<'_, $a>,)+)) {
+                let ($($a,)+) = slots;
+                let mut idx = 0;
+                $(
+                    if present & (1 << idx) == 0 {
+                        *$a = LoadSlot::Skipped;
+                    }
+                    idx += 1;
+                )+
+            }
+        }
+    };
+}
+
+for_tuple!(resource_set);
+
 macro_rules! set {
     () => {
         /* Don't implement for empty tuple */
@@ -202,7 +1086,7 @@ macro_rules! set {
         #[allow(unused_parens)]
         impl<$($a),+> DumpSet for ($($a,)+)
         where
-            $($a: Sync + 'static,)+
+            $($a: Dumpable,)+
         {
             type DumpSlots<'a> = ($(DumpSlot<'a, $a>,)+);
 
@@ -212,17 +1096,26 @@ macro_rules! set {
                 Fi: ImmutableQuery,
                 Du: for<'a> Dumper<($($a,)+), Error = Er>,
             {
+                let mut slot = 0;
+                $(
+                    let version = <$a as Dumpable>::version(world);
+                    if version != 0 {
+                        dumper.version(VersionDump { slot, version })?;
+                    }
+                    slot += 1;
+                )+
+
                 let mut query = world.query_with(DumpQuery::<($($a,)+)>::new(after_epoch)).filter(filter);
 
                 query.try_for_each(|(id, ($($a),+))| {
                     let mut present = 0;
                     let mut modified = 0;
 
-                    let slots = indexed_tuple!(idx => $(match $a {
+                    let slots = indexed_tuple!(idx => $(match &$a {
                         DumpItem::Missing => DumpSlot::Skipped,
                         DumpItem::Modified(comp) => {
                             modified |= (1 << idx);
-                            DumpSlot::Component(comp)
+                            DumpSlot::Component(comp.borrow())
                         }
                         DumpItem::Unmodified => {
                             present |= (1 << idx);
@@ -231,7 +1124,33 @@ macro_rules! set {
                     }),+);
                     let bits = id.bits();
                     dumper.dump(EntityDump([bits, present | modified, modified]), slots)
-                })
+                })?;
+
+                for id in world.tombstones(after_epoch) {
+                    dumper.tombstone(TombstoneDump(id.bits()))?;
+                }
+
+                Ok(())
+            }
+
+            #[inline]
+            fn dump_len<Fi>(world: &World, filter: Fi, after_epoch: EpochId) -> usize
+            where
+                Fi: ImmutableQuery,
+            {
+                let mut len = 0;
+                $(
+                    if <$a as Dumpable>::version(world) != 0 {
+                        len += 1;
+                    }
+                )+
+
+                world
+                    .query_with(DumpQuery::<($($a,)+)>::new(after_epoch))
+                    .filter(filter)
+                    .for_each(|_| len += 1);
+
+                len + world.tombstones(after_epoch).count()
             }
         }
 
@@ -240,35 +1159,78 @@ macro_rules! set {
         #[allow(unused_parens)]
         impl<$($a),+> LoadSet for ($($a,)+)
         where
-            $($a: Component + Send + 'static,)+
+            $($a: Dumpable,)+
         {
             type LoadSlots<'a> = ($(LoadSlot<'a, $a>,)+);
 
-            fn load_world<Lo, Er, Ma>(
+            fn load_world_with<Lo, Er, Ma>(
                 world: &World,
                 marker: Ma,
                 actions: &mut ActionEncoder,
                 loader: &mut Lo,
+                mut remap: Option<&mut EntityRemap>,
             ) -> Result<(), Er>
             where
                 Lo: for<'a> Loader<($($a,)+), Error = Er>,
                 Ma: Mark,
             {
-                let mut query = world.query::<($(Option<&mut $a>,)+)>();
+                let mut query = world.query::<($($a::Existing,)+)>().include_disabled();
+
+                // Migrations of values dumped with older versions by slot.
+                let mut migrations: [Option<Migration>; 64] = [(); 64].map(|()| None);
+
+                let mut slot = 0u32;
+                $(
+                    migrations[slot as usize] = <$a as Dumpable>::migration(world, 0).map_err(|err| loader.error(err))?;
+                    loader.migration(slot, migrations[slot as usize].as_ref());
+                    slot += 1;
+                )+
 
                 while let Some(next) = loader.next()? {
-                    let EntityDump([bits, present, modified]) = next;
+                    let EntityDump([bits, present, modified]) = match next {
+                        LoadEntry::Entity(entity) => entity,
+                        LoadEntry::Version(VersionDump { slot: version_slot, version }) => {
+                            let mut slot = 0u32;
+                            $(
+                                if slot == version_slot {
+                                    migrations[slot as usize] = <$a as Dumpable>::migration(world, version).map_err(|err| loader.error(err))?;
+                                    loader.migration(slot, migrations[slot as usize].as_ref());
+                                }
+                                slot += 1;
+                            )+
+                            continue;
+                        }
+                        LoadEntry::Tombstone(TombstoneDump(bits)) => {
+                            let Some(id) = EntityId::from_bits(bits) else {
+                                continue;
+                            };
+                            let id = match &remap {
+                                None => Some(id),
+                                Some(remap) => remap.get(id),
+                            };
+                            if let Some(id) = id {
+                                actions.despawn(id);
+                            }
+                            continue;
+                        }
+                    };
                     let Some(id) = EntityId::from_bits(bits) else {
                         continue;
                     };
+                    let id = match &mut remap {
+                        None => id,
+                        Some(remap) => remap.map(id, world),
+                    };
 
                     let mut slots = match query.get_one(id) {
                         Ok(($($a),+)) => {
                             indexed_tuple!(idx => $(
                                 if modified & (1 << idx) == 0 {
                                     LoadSlot::Skipped
+                                } else if let Some(migration) = &migrations[idx] {
+                                    LoadSlot::Migrate(migration)
                                 } else {
-                                    match $a {
+                                    match $a::existing($a) {
                                         Some(comp) => LoadSlot::Existing(comp),
                                         None => LoadSlot::Missing,
                                     }
@@ -278,10 +1240,12 @@ macro_rules! set {
                         Err(QueryOneError::NotSatisfied) => unreachable!("Tuple of options is always satisfied"),
                         Err(QueryOneError::NoSuchEntity) => {
                             indexed_tuple!(idx => $(
-                                if modified & (1 << idx) != 0 {
-                                    LoadSlot::<$a>::Missing
-                                } else {
+                                if modified & (1 << idx) == 0 {
                                     LoadSlot::<$a>::Skipped
+                                } else if let Some(migration) = &migrations[idx] {
+                                    LoadSlot::Migrate(migration)
+                                } else {
+                                    LoadSlot::<$a>::Missing
                                 }
                             ),+)
                         }
@@ -289,12 +1253,22 @@ macro_rules! set {
 
                     loader.load(&mut slots)?;
 
-                    let ($($a,)+) = slots;
+                    let ($(mut $a,)+) = slots;
+
+                    if let Some(remap) = &mut remap {
+                        let mut mapper = |dumped| remap.map(dumped, world);
+                        $(match &mut $a {
+                            LoadSlot::Existing(comp) => Dumpable::map_entities(&mut **comp, &mut mapper),
+                            LoadSlot::Created(comp) => Dumpable::map_entities(comp, &mut mapper),
+                            _ => {}
+                        })+
+                    }
+
                     $(
                         let $a = match $a {
                             LoadSlot::Skipped => None,
-                            LoadSlot::Missing => {
-                                unreachable!("Must be created by loader");
+                            LoadSlot::Missing | LoadSlot::Migrate(_) => {
+                                return Err(loader.error(LoadError::MissingValue { name: type_name::<$a>() }));
                             }
                             LoadSlot::Existing(_) => None,
                             LoadSlot::Created(comp) => Some(comp),
@@ -306,9 +1280,9 @@ macro_rules! set {
                         world.spawn_if_missing(id);
                         marker.mark(world, id);
                         indexed_tuple!(idx => $(match $a {
-                            Some(comp) => { let _ = world.insert(id, comp); }
-                            None => if present & (1 << idx) != 0 {
-                                let _ = world.drop::<$a>(id);
+                            Some(comp) => comp.insert(world, id),
+                            None => if present & (1 << idx) == 0 {
+                                $a::remove(world, id);
                             }
                         }),+);
                     });
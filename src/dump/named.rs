@@ -0,0 +1,410 @@
+//! Self-describing world dumps keyed by component names.
+//!
+//! Unlike [`DumpSet`](super::DumpSet) tuples, [`NamedRegistry`] is configured at runtime.
+//! Each registered component has a key and a pair of functions
+//! that serialize component into bytes and deserialize it back.
+//! Key defaults to [`Component::name`] and may be any stable string.
+//!
+//! Each component in [`NamedDump`] is stored with its key,
+//! so components can be added to or removed from the registry
+//! without breaking older dumps.
+//! Components with unknown keys are either skipped or preserved
+//! in [`Preserved`] component to be written back on next dump.
+//!
+//! Components are stored with their versions.
+//! Values dumped with older version are decoded with migrations
+//! registered with [`ComponentInfoRef::migration_named`].
+
+use core::{
+    any::{Any, TypeId},
+    fmt,
+};
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use hashbrown::HashMap;
+
+use crate::{
+    component::{Component, ComponentInfoRef, DropHook, InsertHook, SetHook},
+    entity::EntityId,
+    query::Entities,
+    world::World,
+};
+
+use super::{LoadError, Migration};
+
+/// Serialized component with its key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NamedComponentDump {
+    /// Key of the component in [`NamedRegistry`].
+    pub key: String,
+
+    /// Version of the component schema the value was dumped with.
+    pub version: u32,
+
+    /// Serialized component value.
+    pub data: Vec<u8>,
+}
+
+/// Serialized entity with its components.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NamedEntityDump {
+    /// Opaque entity id.
+    pub id: u64,
+
+    /// Serialized components of the entity.
+    pub components: Vec<NamedComponentDump>,
+}
+
+/// World dump produced by [`NamedRegistry::dump`].
+///
+/// Backends implement serialization for this type.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NamedDump {
+    /// Serialized entities.
+    pub entities: Vec<NamedEntityDump>,
+}
+
+/// Component that keeps components with keys unknown to [`NamedRegistry`]
+/// when loaded with [`Unknown::Preserve`].
+///
+/// Preserved components are written back by [`NamedRegistry::dump`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Preserved {
+    /// Components with unknown keys.
+    pub components: Vec<NamedComponentDump>,
+}
+
+impl Component for Preserved {
+    #[inline]
+    fn clone_fn() -> Option<fn(&Self) -> Self> {
+        Some(Self::clone)
+    }
+}
+
+/// Specifies what to do with components with keys unknown to [`NamedRegistry`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Unknown {
+    /// Skip unknown components.
+    Skip,
+
+    /// Keep unknown components in [`Preserved`] component.
+    Preserve,
+}
+
+/// Error that may occur when [`NamedDump`] is loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NamedLoadError {
+    /// Component with specified key failed to deserialize.
+    InvalidComponent {
+        /// Key of the component.
+        key: String,
+    },
+
+    /// Component with specified key can't be loaded with dumped version.
+    Version {
+        /// Key of the component.
+        key: String,
+
+        /// Reason of the failure.
+        error: LoadError,
+    },
+}
+
+impl fmt::Display for NamedLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidComponent { key } => {
+                write!(f, "Failed to deserialize component `{}`", key)
+            }
+            Self::Version { key, error } => {
+                write!(f, "Failed to load component `{}`: {}", key, error)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NamedLoadError {}
+
+type DumpFn = Box<dyn Fn(&World, &mut dyn FnMut(EntityId, Vec<u8>)) + Send + Sync>;
+type LoadFn = Box<
+    dyn Fn(&mut World, EntityId, &[u8], Option<&Migration>) -> Result<(), InvalidData>
+        + Send
+        + Sync,
+>;
+
+/// Component data cannot be deserialized.
+struct InvalidData;
+
+struct NamedEntry {
+    key: String,
+    id: TypeId,
+    dump: DumpFn,
+    load: LoadFn,
+}
+
+/// Decoder of values dumped with older version
+/// by functions passed to [`NamedRegistry::register`].
+struct DecodeNamed(DecodeNamedFn);
+
+type DecodeNamedFn = Box<dyn Fn(&[u8]) -> Option<Box<dyn Any>> + Send + Sync>;
+
+impl<T, D, S, I> ComponentInfoRef<'_, T, D, S, I>
+where
+    T: 'static,
+    D: DropHook<T>,
+    S: SetHook<T>,
+    I: InsertHook<T>,
+{
+    /// Registers migration of values dumped with version `from`
+    /// into [`NamedDump`] by functions passed to [`NamedRegistry::register`].
+    ///
+    /// `deserialize` reads old value from bytes
+    /// and `migration` converts it into value of version `from + 1`.
+    pub fn migration_named<Old, New, De, F>(self, from: u32, deserialize: De, migration: F) -> Self
+    where
+        Old: 'static,
+        New: 'static,
+        De: Fn(&[u8]) -> Option<Old> + Send + Sync + 'static,
+        F: Fn(Old) -> New + Send + Sync + 'static,
+    {
+        let decoder = DecodeNamed(Box::new(move |data| {
+            let old = deserialize(data)?;
+            Some(Box::new(old) as Box<dyn Any>)
+        }));
+        self.migration_with(from, decoder, migration)
+    }
+}
+
+/// Registry of components that can be dumped and loaded by key.
+#[derive(Default)]
+pub struct NamedRegistry {
+    entries: Vec<NamedEntry>,
+    by_key: HashMap<String, usize>,
+    by_type: HashMap<TypeId, usize>,
+}
+
+impl NamedRegistry {
+    /// Returns new empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        NamedRegistry {
+            entries: Vec::new(),
+            by_key: HashMap::new(),
+            by_type: HashMap::new(),
+        }
+    }
+
+    /// Registers component type keyed by [`Component::name`].
+    ///
+    /// `serialize` writes component into the buffer.
+    /// `deserialize` reads component from bytes written by `serialize`
+    /// and returns `None` if data is invalid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if component type or key is already registered.
+    pub fn register<T, S, D>(&mut self, serialize: S, deserialize: D) -> &mut Self
+    where
+        T: Component + Send + Sync,
+        S: Fn(&T, &mut Vec<u8>) + Send + Sync + 'static,
+        D: Fn(&[u8]) -> Option<T> + Send + Sync + 'static,
+    {
+        self.register_as(T::name(), serialize, deserialize)
+    }
+
+    /// Registers component type with specified key.
+    ///
+    /// Use this method to give components keys
+    /// that do not change when types are renamed or moved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if component type or key is already registered.
+    pub fn register_as<T, S, D>(
+        &mut self,
+        key: impl Into<String>,
+        serialize: S,
+        deserialize: D,
+    ) -> &mut Self
+    where
+        T: Component + Send + Sync,
+        S: Fn(&T, &mut Vec<u8>) + Send + Sync + 'static,
+        D: Fn(&[u8]) -> Option<T> + Send + Sync + 'static,
+    {
+        self.register_with(key, serialize, move |data, migration| {
+            match migration.and_then(|m| Some((m, m.decoder::<DecodeNamed>()?))) {
+                None => deserialize(data),
+                Some((migration, decode)) => {
+                    let old = (decode.0)(data)?;
+                    migration.upgrade::<DecodeNamed, T>(old).ok()
+                }
+            }
+        })
+    }
+
+    /// Registers component type with specified key
+    /// and function that loads value dumped with optional migration.
+    pub(crate) fn register_with<T, S, L>(
+        &mut self,
+        key: impl Into<String>,
+        serialize: S,
+        load: L,
+    ) -> &mut Self
+    where
+        T: Component + Send + Sync,
+        S: Fn(&T, &mut Vec<u8>) + Send + Sync + 'static,
+        L: Fn(&[u8], Option<&Migration>) -> Option<T> + Send + Sync + 'static,
+    {
+        let key = key.into();
+        assert!(
+            !self.by_key.contains_key(&key),
+            "Component key `{}` is already registered",
+            key
+        );
+        assert!(
+            !self.by_type.contains_key(&TypeId::of::<T>()),
+            "Component `{}` is already registered",
+            T::name()
+        );
+
+        let idx = self.entries.len();
+        self.by_key.insert(key.clone(), idx);
+        self.by_type.insert(TypeId::of::<T>(), idx);
+
+        self.entries.push(NamedEntry {
+            key,
+            id: TypeId::of::<T>(),
+            dump: Box::new(move |world, f| {
+                world
+                    .query::<(Entities, &T)>()
+                    .include_disabled()
+                    .for_each(|(id, comp)| {
+                        let mut data = Vec::new();
+                        serialize(comp, &mut data);
+                        f(id, data);
+                    });
+            }),
+            load: Box::new(move |world, id, data, migration| {
+                let comp = load(data, migration).ok_or(InvalidData)?;
+                let _ = world.insert(id, comp);
+                Ok(())
+            }),
+        });
+        self
+    }
+
+    /// Returns key of registered component type.
+    pub fn key<T>(&self) -> Option<&str>
+    where
+        T: 'static,
+    {
+        let idx = *self.by_type.get(&TypeId::of::<T>())?;
+        Some(&self.entries[idx].key)
+    }
+
+    /// Dumps all entities of the world with registered components.
+    ///
+    /// Components kept in [`Preserved`] are written back
+    /// unless entity has registered component with the same key.
+    pub fn dump(&self, world: &World) -> NamedDump {
+        let mut entities = Vec::new();
+        let mut indices = HashMap::new();
+
+        world.query::<Entities>().include_disabled().for_each(|id| {
+            indices.insert(id, entities.len());
+            entities.push(NamedEntityDump {
+                id: id.bits(),
+                components: Vec::new(),
+            });
+        });
+
+        for entry in &self.entries {
+            let version = world
+                .component_info(entry.id)
+                .map_or(0, |info| info.version());
+
+            (entry.dump)(world, &mut |id, data| {
+                entities[indices[&id]].components.push(NamedComponentDump {
+                    key: entry.key.clone(),
+                    version,
+                    data,
+                });
+            });
+        }
+
+        world
+            .query::<(Entities, &Preserved)>()
+            .include_disabled()
+            .for_each(|(id, preserved)| {
+                let entity = &mut entities[indices[&id]];
+                for comp in &preserved.components {
+                    if !entity.components.iter().any(|c| c.key == comp.key) {
+                        entity.components.push(comp.clone());
+                    }
+                }
+            });
+
+        NamedDump { entities }
+    }
+
+    /// Loads entities from the dump into the world.
+    ///
+    /// Entities are spawned with dumped ids if missing.
+    /// Components are inserted, replacing existing values.
+    /// Values dumped with older versions are upgraded with registered migrations.
+    /// `unknown` specifies what to do with components with unknown keys.
+    pub fn load(
+        &self,
+        world: &mut World,
+        dump: &NamedDump,
+        unknown: Unknown,
+    ) -> Result<(), NamedLoadError> {
+        for entity in &dump.entities {
+            let Some(id) = EntityId::from_bits(entity.id) else {
+                continue;
+            };
+            world.spawn_if_missing(id);
+
+            let mut preserved = Vec::new();
+
+            for comp in &entity.components {
+                match self.by_key.get(&comp.key) {
+                    Some(&idx) => {
+                        let entry = &self.entries[idx];
+                        let migration = match world.component_info(entry.id) {
+                            None => None,
+                            Some(info) => info.migration(comp.version).map_err(|error| {
+                                NamedLoadError::Version {
+                                    key: comp.key.clone(),
+                                    error,
+                                }
+                            })?,
+                        };
+
+                        (entry.load)(world, id, &comp.data, migration.as_ref()).map_err(
+                            |InvalidData| NamedLoadError::InvalidComponent {
+                                key: comp.key.clone(),
+                            },
+                        )?;
+                    }
+                    None => match unknown {
+                        Unknown::Skip => {}
+                        Unknown::Preserve => preserved.push(comp.clone()),
+                    },
+                }
+            }
+
+            if !preserved.is_empty() {
+                let _ = world.insert(
+                    id,
+                    Preserved {
+                        components: preserved,
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -2,14 +2,29 @@
 //!
 //! This module provides the [`ComponentDump`](ComponentDump) trait, which can be implemented for
 
-use core::convert::Infallible;
+use core::{convert::Infallible, str::Chars};
 
-pub use nanoserde::{DeBin, DeBinErr, SerBin};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+use nanoserde::{DeJsonState, DeJsonTok, DeRonState, DeRonTok, SerJsonState, SerRonState};
+
+pub use nanoserde::{DeBin, DeBinErr, DeJson, DeJsonErr, DeRon, DeRonErr, SerBin, SerJson, SerRon};
 
-use crate::{action::ActionEncoder, component::Component, query::ImmutableQuery};
+use crate::{
+    action::ActionEncoder,
+    component::{Component, ComponentInfoRef, DropHook, InsertHook, SetHook},
+    entity::EntityId,
+    query::ImmutableQuery,
+    relation::ChildOf,
+};
 
 use super::{
-    DumpSet, DumpSlot, Dumper, EntityDump, LoadSet, LoadSlot, Loader, Mark, WorldDump, WorldLoad,
+    named::{NamedComponentDump, NamedDump, NamedEntityDump, NamedRegistry},
+    Decoded, DumpSet, DumpSlot, Dumpable, Dumper, EntityDump, EntityRemap, LoadEntry, LoadError,
+    LoadSet, LoadSlot, Loader, Mark, Migration, Relations, ResourceDumper, ResourceLoader,
+    ResourceSet, TombstoneDump, VersionDump, WorldDump, WorldLoad,
 };
 
 /// Dumps world using [`nanoserde::SerBin`].
@@ -28,17 +43,224 @@ impl<'a> LoaderBin<'a> {
     }
 }
 
+/// Decoder of values dumped with older version with [`nanoserde::DeBin`].
+struct DecodeBin(fn(&mut usize, &[u8]) -> Decoded<DeBinErr>);
+
+/// Decoder of values dumped with older version with [`nanoserde::DeJson`].
+struct DecodeJson(fn(&mut DeJsonState, &mut Chars) -> Decoded<DeJsonErr>);
+
+/// Decoder of values dumped with older version with [`nanoserde::DeRon`].
+struct DecodeRon(fn(&mut DeRonState, &mut Chars) -> Decoded<DeRonErr>);
+
+fn decode_bin<T>(offset: &mut usize, bytes: &[u8]) -> Decoded<DeBinErr>
+where
+    T: DeBin + 'static,
+{
+    Ok(Box::new(T::de_bin(offset, bytes)?))
+}
+
+fn decode_json<T>(s: &mut DeJsonState, i: &mut Chars) -> Decoded<DeJsonErr>
+where
+    T: DeJson + 'static,
+{
+    Ok(Box::new(T::de_json(s, i)?))
+}
+
+fn decode_ron<T>(s: &mut DeRonState, i: &mut Chars) -> Decoded<DeRonErr>
+where
+    T: DeRon + 'static,
+{
+    Ok(Box::new(T::de_ron(s, i)?))
+}
+
+impl<T, D, S, I> ComponentInfoRef<'_, T, D, S, I>
+where
+    T: 'static,
+    D: DropHook<T>,
+    S: SetHook<T>,
+    I: InsertHook<T>,
+{
+    /// Registers migration of values dumped with version `from`
+    /// with [`nanoserde::SerBin`].
+    ///
+    /// Dumped value is decoded as `Old`
+    /// and `migration` converts it into value of version `from + 1`.
+    /// Also applies to [`NamedRegistry::register_bin`].
+    pub fn migration_bin<Old, New, F>(self, from: u32, migration: F) -> Self
+    where
+        Old: DeBin + 'static,
+        New: 'static,
+        F: Fn(Old) -> New + Send + Sync + 'static,
+    {
+        self.migration_with(from, DecodeBin(decode_bin::<Old>), migration)
+    }
+
+    /// Registers migration of values dumped with version `from`
+    /// with [`nanoserde::SerJson`].
+    ///
+    /// Dumped value is decoded as `Old`
+    /// and `migration` converts it into value of version `from + 1`.
+    pub fn migration_json<Old, New, F>(self, from: u32, migration: F) -> Self
+    where
+        Old: DeJson + 'static,
+        New: 'static,
+        F: Fn(Old) -> New + Send + Sync + 'static,
+    {
+        self.migration_with(from, DecodeJson(decode_json::<Old>), migration)
+    }
+
+    /// Registers migration of values dumped with version `from`
+    /// with [`nanoserde::SerRon`].
+    ///
+    /// Dumped value is decoded as `Old`
+    /// and `migration` converts it into value of version `from + 1`.
+    pub fn migration_ron<Old, New, F>(self, from: u32, migration: F) -> Self
+    where
+        Old: DeRon + 'static,
+        New: 'static,
+        F: Fn(Old) -> New + Send + Sync + 'static,
+    {
+        self.migration_with(from, DecodeRon(decode_ron::<Old>), migration)
+    }
+}
+
+impl<R> SerBin for Relations<R>
+where
+    R: SerBin,
+{
+    fn ser_bin(&self, output: &mut Vec<u8>) {
+        self.targets.ser_bin(output);
+    }
+}
+
+impl<R> DeBin for Relations<R>
+where
+    R: DeBin,
+{
+    fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(Relations {
+            targets: DeBin::de_bin(offset, bytes)?,
+        })
+    }
+}
+
+impl SerBin for EntityId {
+    fn ser_bin(&self, output: &mut Vec<u8>) {
+        self.bits().ser_bin(output);
+    }
+}
+
+impl DeBin for EntityId {
+    fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr> {
+        let start = *offset;
+        let bits = u64::de_bin(offset, bytes)?;
+        EntityId::from_bits(bits).ok_or(DeBinErr {
+            o: start,
+            l: core::mem::size_of::<u64>(),
+            s: bytes.len(),
+        })
+    }
+}
+
+impl SerBin for NamedComponentDump {
+    fn ser_bin(&self, output: &mut Vec<u8>) {
+        self.key.ser_bin(output);
+        self.version.ser_bin(output);
+        self.data.ser_bin(output);
+    }
+}
+
+impl DeBin for NamedComponentDump {
+    fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(NamedComponentDump {
+            key: DeBin::de_bin(offset, bytes)?,
+            version: DeBin::de_bin(offset, bytes)?,
+            data: DeBin::de_bin(offset, bytes)?,
+        })
+    }
+}
+
+impl SerBin for NamedEntityDump {
+    fn ser_bin(&self, output: &mut Vec<u8>) {
+        self.id.ser_bin(output);
+        self.components.ser_bin(output);
+    }
+}
+
+impl DeBin for NamedEntityDump {
+    fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(NamedEntityDump {
+            id: DeBin::de_bin(offset, bytes)?,
+            components: DeBin::de_bin(offset, bytes)?,
+        })
+    }
+}
+
+impl SerBin for NamedDump {
+    fn ser_bin(&self, output: &mut Vec<u8>) {
+        self.entities.ser_bin(output);
+    }
+}
+
+impl DeBin for NamedDump {
+    fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(NamedDump {
+            entities: DeBin::de_bin(offset, bytes)?,
+        })
+    }
+}
+
+impl NamedRegistry {
+    /// Registers component type keyed by [`Component::name`]
+    /// that is serialized with [`nanoserde::SerBin`] and [`nanoserde::DeBin`].
+    ///
+    /// Values dumped with older versions are upgraded
+    /// with migrations registered with [`ComponentInfoRef::migration_bin`].
+    ///
+    /// [`Component::name`]: crate::component::Component::name
+    pub fn register_bin<T>(&mut self) -> &mut Self
+    where
+        T: Component + SerBin + DeBin + Send + Sync,
+    {
+        self.register_with(
+            T::name(),
+            |comp: &T, output: &mut Vec<u8>| comp.ser_bin(output),
+            |bytes: &[u8], migration: Option<&Migration>| match migration
+                .and_then(|m| Some((m, m.decoder::<DecodeBin>()?)))
+            {
+                None => T::deserialize_bin(bytes).ok(),
+                Some((migration, decode)) => {
+                    let old = (decode.0)(&mut 0, bytes).ok()?;
+                    migration.upgrade::<DecodeBin, T>(old).ok()
+                }
+            },
+        )
+    }
+}
+
+impl SerBin for ChildOf {
+    fn ser_bin(&self, _output: &mut Vec<u8>) {}
+}
+
+impl DeBin for ChildOf {
+    fn de_bin(_offset: &mut usize, _bytes: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(ChildOf)
+    }
+}
+
 macro_rules! dumper {
     () => {};
     ($($a:ident)+) => {
         #[allow(non_snake_case)]
         impl<'a $(, $a)+> Dumper<($($a,)+)> for DumperBin<'a>
         where
-            $($a: SerBin + Sync + 'static,)+
+            $($a: SerBin + Dumpable,)+
         {
             type Error = Infallible;
             fn dump(&mut self, entity: EntityDump, slots: ($(DumpSlot<'_, $a>,)+)) -> Result<(), Infallible> {
-                entity.0.ser_bin(self.0);
+                let (tag, triple) = entity.to_record();
+                tag.ser_bin(self.0);
+                triple.ser_bin(self.0);
                 let ($($a,)+) = slots;
                 $(
                     match $a {
@@ -48,25 +270,45 @@ macro_rules! dumper {
                 )+
                 Ok(())
             }
+
+            fn tombstone(&mut self, tombstone: TombstoneDump) -> Result<(), Infallible> {
+                let (tag, triple) = tombstone.to_record();
+                tag.ser_bin(self.0);
+                triple.ser_bin(self.0);
+                Ok(())
+            }
+
+            fn version(&mut self, version: VersionDump) -> Result<(), Infallible> {
+                let (tag, triple) = version.to_record();
+                tag.ser_bin(self.0);
+                triple.ser_bin(self.0);
+                Ok(())
+            }
         }
 
-        impl<'a $(, $a)+, Fi> SerBin for WorldDump<'a, ($($a,)+), Fi>
+        impl<'a $(, $a)+, Fi, Re> SerBin for WorldDump<'a, ($($a,)+), Fi, Re>
         where
             Fi: ImmutableQuery + Copy,
-            $($a: SerBin + Sync + 'static,)+
+            $($a: SerBin + Dumpable,)+
+            Re: ResourceSet,
+            for<'b> DumperBin<'b>: ResourceDumper<Re, Error = Infallible>,
         {
             fn ser_bin(&self, buf: &mut Vec<u8>) {
                 self.dump_bin(buf);
             }
         }
 
-        impl<'a $(, $a)+, Fi> WorldDump<'a, ($($a,)+), Fi>
+        impl<'a $(, $a)+, Fi, Re> WorldDump<'a, ($($a,)+), Fi, Re>
         where
             Fi: ImmutableQuery + Copy,
-            $($a: SerBin + Sync + 'static,)+
+            $($a: SerBin + Dumpable,)+
+            Re: ResourceSet,
+            for<'b> DumperBin<'b>: ResourceDumper<Re, Error = Infallible>,
         {
             fn dump_bin(&self, buf: &mut Vec<u8>) {
-                let result = <($($a,)+) as DumpSet>::dump_world(self.world, self.filter, self.epoch, &mut DumperBin(buf));
+                let result = Re::dump_resources(self.world, &mut DumperBin(buf)).and_then(|()| {
+                    <($($a,)+) as DumpSet>::dump_world(self.world, self.filter, self.epoch, &mut DumperBin(buf))
+                });
                 match result {
                     Ok(()) => {}
                     Err(never) => match never {},
@@ -77,18 +319,36 @@ macro_rules! dumper {
         #[allow(non_snake_case)]
         impl<'a $(, $a)+> Loader<($($a,)+)> for LoaderBin<'a>
         where
-            $($a: DeBin + Component + Send + 'static,)+
+            $($a: DeBin + Dumpable,)+
         {
             /// Type of possible errors that can occur during deserialization.
             type Error = DeBinErr;
 
-            /// Returns next entity to load.
-            fn next(&mut self) -> Result<Option<EntityDump>, DeBinErr> {
+            /// Returns next entry to load.
+            fn next(&mut self) -> Result<Option<LoadEntry>, DeBinErr> {
                 if self.offset == self.buf.len() {
                     return Ok(None);
                 }
-                let idxs = <[u64;3]>::de_bin(&mut self.offset, self.buf)?;
-                Ok(Some(EntityDump(idxs)))
+                let start = self.offset;
+                let tag = u8::de_bin(&mut self.offset, self.buf)?;
+                let triple = <[u64;3]>::de_bin(&mut self.offset, self.buf)?;
+                match LoadEntry::from_record(tag, triple) {
+                    Some(entry) => Ok(Some(entry)),
+                    None => Err(DeBinErr {
+                        o: start,
+                        l: 1,
+                        s: self.buf.len(),
+                    }),
+                }
+            }
+
+            // Binary errors carry only the position.
+            fn error(&self, _error: LoadError) -> DeBinErr {
+                DeBinErr {
+                    o: self.offset,
+                    l: 0,
+                    s: self.buf.len(),
+                }
             }
 
             /// Loads entity with provided component slots.
@@ -104,6 +364,21 @@ macro_rules! dumper {
                         LoadSlot::Existing(comp) => {
                             **comp = $a::de_bin(&mut self.offset, self.buf)?;
                         }
+                        LoadSlot::Migrate(migration) => {
+                            let comp: $a = match migration.decoder::<DecodeBin>() {
+                                None => $a::de_bin(&mut self.offset, self.buf)?,
+                                Some(decode) => {
+                                    let old = (decode.0)(&mut self.offset, self.buf)?;
+                                    let end = self.offset;
+                                    migration.upgrade::<DecodeBin, $a>(old).map_err(|_| DeBinErr {
+                                        o: end,
+                                        l: 0,
+                                        s: self.buf.len(),
+                                    })?
+                                }
+                            };
+                            *$a = LoadSlot::Created(comp);
+                        }
                         LoadSlot::Created(_) => unreachable!(),
                     }
                 )+
@@ -111,14 +386,26 @@ macro_rules! dumper {
             }
         }
 
-        impl<'a $(, $a)+, Ma> WorldLoad<'a, ($($a,)+), Ma>
+        impl<'a $(, $a)+, Ma, Re> WorldLoad<'a, ($($a,)+), Ma, Re>
         where
             Ma: Mark,
-            $($a: Component + DeBin + Send + 'static,)+
+            $($a: DeBin + Dumpable,)+
+            Re: ResourceSet,
+            for<'b> LoaderBin<'b>: ResourceLoader<Re, Error = DeBinErr>,
         {
             /// Loads world from buffer using [`nanoserde::DeBin`].
             pub fn load_bin(&self, actions: &mut ActionEncoder, buf: &[u8]) -> Result<(), DeBinErr> {
-                <($($a,)+) as LoadSet>::load_world(self.world, self.marker, actions, &mut LoaderBin::new(buf))
+                let mut loader = LoaderBin::new(buf);
+                Re::load_resources(actions, &mut loader)?;
+                <($($a,)+) as LoadSet>::load_world(self.world, self.marker, actions, &mut loader)
+            }
+
+            /// Loads world from buffer using [`nanoserde::DeBin`].
+            /// Entities are loaded under fresh ids recorded in `remap`.
+            pub fn load_bin_remap(&self, actions: &mut ActionEncoder, buf: &[u8], remap: &mut EntityRemap) -> Result<(), DeBinErr> {
+                let mut loader = LoaderBin::new(buf);
+                Re::load_resources(actions, &mut loader)?;
+                <($($a,)+) as LoadSet>::load_world_remap(self.world, self.marker, actions, &mut loader, remap)
             }
         }
     };
@@ -126,79 +413,1423 @@ macro_rules! dumper {
 
 for_tuple!(dumper);
 
-#[test]
-fn test_dump() {
-    use ::nanoserde::{DeBin, SerBin};
+impl ResourceDumper<()> for DumperBin<'_> {
+    type Error = Infallible;
 
-    use super::NoMark;
-    use crate::{action::ActionBuffer, epoch::EpochId, world::World};
+    fn dump_resources(&mut self, _present: u64, _slots: ()) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
 
-    let mut world = World::new();
+impl ResourceLoader<()> for LoaderBin<'_> {
+    type Error = DeBinErr;
 
-    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
-    struct Foo;
+    fn load_resources(&mut self, _slots: &mut ()) -> Result<(), DeBinErr> {
+        Ok(())
+    }
+}
 
-    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
-    struct Bar(u32);
+macro_rules! resources {
+    () => {};
+    ($($a:ident)+) => {
+        #[allow(non_snake_case)]
+        impl<'a $(, $a)+> ResourceDumper<($($a,)+)> for DumperBin<'a>
+        where
+            $($a: SerBin + Send + Sync + 'static,)+
+        {
+            type Error = Infallible;
 
-    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
-    struct Baz(String);
+            fn dump_resources(&mut self, present: u64, slots: ($(DumpSlot<'_, $a>,)+)) -> Result<(), Infallible> {
+                present.ser_bin(self.0);
+                let ($($a,)+) = slots;
+                $(
+                    match $a {
+                        DumpSlot::Skipped => {}
+                        DumpSlot::Component($a) => $a.ser_bin(self.0),
+                    }
+                )+
+                Ok(())
+            }
+        }
 
-    let foo = world.spawn((Foo,));
-    let bar = world.spawn((Bar(42),));
-    let baz = world.spawn((Baz("qwerty".into()),));
+        #[allow(non_snake_case)]
+        impl<'a $(, $a)+> ResourceLoader<($($a,)+)> for LoaderBin<'a>
+        where
+            $($a: DeBin + Send + Sync + 'static,)+
+        {
+            type Error = DeBinErr;
 
-    let foo_bar = world.spawn((Foo, Bar(11)));
-    let foo_baz = world.spawn((Foo, Baz("asdfgh".into())));
-    let bar_baz = world.spawn((Bar(23), Baz("zxcvbn".into())));
-    let foo_bar_baz = world.spawn((Foo, Bar(155), Baz("123456".into())));
+            fn load_resources(&mut self, slots: &mut <($($a,)+) as ResourceSet>::LoadSlots<'_>) -> Result<(), DeBinErr> {
+                let present = u64::de_bin(&mut self.offset, self.buf)?;
+                <($($a,)+)>::skip_absent(present, slots);
+                let ($($a,)+) = slots;
+                $(
+                    if let LoadSlot::Missing = $a {
+                        *$a = LoadSlot::Created($a::de_bin(&mut self.offset, self.buf)?);
+                    }
+                )+
+                Ok(())
+            }
+        }
+    };
+}
 
-    type Set = (Foo, Bar, Baz);
+for_tuple!(resources);
 
-    let data = WorldDump::<Set, _>::new(&mut world, (), EpochId::start()).serialize_bin();
+/// Dumps world using [`nanoserde::SerJson`].
+///
+/// Each entry is written as JSON object with named fields.
+/// Entries are separated with commas,
+/// enclosing brackets are written by the caller.
+pub struct DumperJson<'a> {
+    state: &'a mut SerJsonState,
+    first: bool,
+}
 
-    let mut world2 = World::new();
+impl<'a> DumperJson<'a> {
+    /// Creates new JSON dumper that writes into the state.
+    pub fn new(state: &'a mut SerJsonState) -> Self {
+        DumperJson { state, first: true }
+    }
 
-    let mut buffer = ActionBuffer::new();
-    let mut actions = buffer.encoder(&world2);
+    fn begin_entry(&mut self) {
+        if !self.first {
+            self.state.conl();
+        }
+        self.first = false;
+        self.state.st_pre();
+    }
+}
 
-    WorldLoad::<Set, _>::new(&world2, NoMark)
-        .load_bin(&mut actions, &data)
-        .unwrap();
-    buffer.execute(&mut world2);
+/// Loads world using [`nanoserde::DeJson`].
+pub struct LoaderJson<'a> {
+    state: DeJsonState,
+    chars: Chars<'a>,
+    started: bool,
+}
 
-    assert_eq!(
-        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(foo),
-        Ok((Some(&Foo), None, None))
-    );
+impl<'a> LoaderJson<'a> {
+    /// Creates new JSON loader from string.
+    pub fn new(input: &'a str) -> Self {
+        let mut state = DeJsonState::default();
+        let mut chars = input.chars();
+        state.next(&mut chars);
+        LoaderJson {
+            state,
+            chars,
+            started: false,
+        }
+    }
 
-    assert_eq!(
-        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(bar),
-        Ok((None, Some(&Bar(42)), None))
-    );
+    fn key(&mut self) -> Result<String, DeJsonErr> {
+        let key = self.state.as_string()?;
+        self.state.next_colon(&mut self.chars)?;
+        Ok(key)
+    }
 
-    assert_eq!(
-        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(baz),
-        Ok((None, None, Some(&Baz("qwerty".into()))))
-    );
+    fn expect_key(&mut self, expected: &str) -> Result<(), DeJsonErr> {
+        let key = self.key()?;
+        if key != expected {
+            return Err(self.state.err_exp(expected));
+        }
+        Ok(())
+    }
 
-    assert_eq!(
-        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(foo_bar),
-        Ok((Some(&Foo), Some(&Bar(11)), None))
-    );
+    fn field<T: DeJson>(&mut self) -> Result<T, DeJsonErr> {
+        let value = T::de_json(&mut self.state, &mut self.chars)?;
+        self.state.eat_comma_curly(&mut self.chars)?;
+        Ok(value)
+    }
 
-    assert_eq!(
-        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(foo_baz),
-        Ok((Some(&Foo), None, Some(&Baz("asdfgh".into()))))
-    );
+    fn end_entry(&mut self) -> Result<(), DeJsonErr> {
+        self.state.curly_close(&mut self.chars)?;
+        self.state.eat_comma_block(&mut self.chars)
+    }
 
-    assert_eq!(
-        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(bar_baz),
-        Ok((None, Some(&Bar(23)), Some(&Baz("zxcvbn".into()))))
-    );
+    fn next_entry(&mut self) -> Result<Option<LoadEntry>, DeJsonErr> {
+        if !self.started {
+            self.state.next_tok(&mut self.chars)?;
+            self.state.block_open(&mut self.chars)?;
+            self.started = true;
+        }
+        if self.state.tok == DeJsonTok::BlockClose {
+            return Ok(None);
+        }
 
-    assert_eq!(
-        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(foo_bar_baz),
-        Ok((Some(&Foo), Some(&Bar(155)), Some(&Baz("123456".into()))))
-    );
+        self.state.curly_open(&mut self.chars)?;
+        let key = self.key()?;
+        match &*key {
+            "id" => {
+                let id = self.field()?;
+                self.expect_key("present")?;
+                let present = self.field()?;
+                self.expect_key("modified")?;
+                let modified = self.field()?;
+                self.expect_key("components")?;
+                self.state.block_open(&mut self.chars)?;
+                Ok(Some(LoadEntry::Entity(EntityDump([id, present, modified]))))
+            }
+            "despawned" => {
+                let bits = self.field()?;
+                self.end_entry()?;
+                Ok(Some(LoadEntry::Tombstone(TombstoneDump(bits))))
+            }
+            "slot" => {
+                let slot = self.field()?;
+                self.expect_key("version")?;
+                let version = self.field()?;
+                self.end_entry()?;
+                Ok(Some(LoadEntry::Version(VersionDump { slot, version })))
+            }
+            _ => Err(self.state.err_nf(&key)),
+        }
+    }
+}
+
+impl<R> SerJson for Relations<R>
+where
+    R: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        self.targets.ser_json(d, s);
+    }
+}
+
+impl<R> DeJson for Relations<R>
+where
+    R: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        Ok(Relations {
+            targets: DeJson::de_json(s, i)?,
+        })
+    }
+}
+
+impl SerJson for EntityId {
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        self.bits().ser_json(d, s);
+    }
+}
+
+impl DeJson for EntityId {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        let bits = u64::de_json(s, i)?;
+        EntityId::from_bits(bits).ok_or_else(|| s.err_range("entity id"))
+    }
+}
+
+impl SerJson for ChildOf {
+    fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
+        s.out.push_str("{}");
+    }
+}
+
+impl DeJson for ChildOf {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        s.curly_open(i)?;
+        s.curly_close(i)?;
+        Ok(ChildOf)
+    }
+}
+
+/// Dumps world using [`nanoserde::SerRon`].
+///
+/// Each entry is written as RON struct with named fields
+/// followed by a comma.
+/// Enclosing brackets are written by the caller.
+pub struct DumperRon<'a>(pub &'a mut SerRonState);
+
+impl DumperRon<'_> {
+    fn begin_entry(&mut self) {
+        self.0.indent(1);
+        self.0.st_pre();
+    }
+
+    fn end_entry(&mut self) {
+        self.0.st_post(1);
+        self.0.conl();
+    }
+}
+
+/// Loads world using [`nanoserde::DeRon`].
+pub struct LoaderRon<'a> {
+    state: DeRonState,
+    chars: Chars<'a>,
+    started: bool,
+}
+
+impl<'a> LoaderRon<'a> {
+    /// Creates new RON loader from string.
+    pub fn new(input: &'a str) -> Self {
+        let mut state = DeRonState::default();
+        let mut chars = input.chars();
+        state.next(&mut chars);
+        LoaderRon {
+            state,
+            chars,
+            started: false,
+        }
+    }
+
+    fn key(&mut self) -> Result<String, DeRonErr> {
+        if self.state.tok != DeRonTok::Ident {
+            return Err(self.state.err_token("field name"));
+        }
+        let key = core::mem::take(&mut self.state.identbuf);
+        self.state.next_colon(&mut self.chars)?;
+        Ok(key)
+    }
+
+    fn expect_key(&mut self, expected: &str) -> Result<(), DeRonErr> {
+        let key = self.key()?;
+        if key != expected {
+            return Err(self.state.err_exp(expected));
+        }
+        Ok(())
+    }
+
+    fn field<T: DeRon>(&mut self) -> Result<T, DeRonErr> {
+        let value = T::de_ron(&mut self.state, &mut self.chars)?;
+        self.state.eat_comma_paren(&mut self.chars)?;
+        Ok(value)
+    }
+
+    fn skip_none(&mut self) -> Result<(), DeRonErr> {
+        if self.state.tok != DeRonTok::Ident || self.state.identbuf != "None" {
+            return Err(self.state.err_token("None"));
+        }
+        self.state.next_tok(&mut self.chars)
+    }
+
+    fn end_entry(&mut self) -> Result<(), DeRonErr> {
+        self.state.paren_close(&mut self.chars)?;
+        self.state.eat_comma_block(&mut self.chars)
+    }
+
+    fn next_entry(&mut self) -> Result<Option<LoadEntry>, DeRonErr> {
+        if !self.started {
+            self.state.next_tok(&mut self.chars)?;
+            self.state.block_open(&mut self.chars)?;
+            self.started = true;
+        }
+        if self.state.tok == DeRonTok::BlockClose {
+            return Ok(None);
+        }
+
+        self.state.paren_open(&mut self.chars)?;
+        let key = self.key()?;
+        match &*key {
+            "id" => {
+                let id = self.field()?;
+                self.expect_key("present")?;
+                let present = self.field()?;
+                self.expect_key("modified")?;
+                let modified = self.field()?;
+                self.expect_key("components")?;
+                self.state.block_open(&mut self.chars)?;
+                Ok(Some(LoadEntry::Entity(EntityDump([id, present, modified]))))
+            }
+            "despawned" => {
+                let bits = self.field()?;
+                self.end_entry()?;
+                Ok(Some(LoadEntry::Tombstone(TombstoneDump(bits))))
+            }
+            "slot" => {
+                let slot = self.field()?;
+                self.expect_key("version")?;
+                let version = self.field()?;
+                self.end_entry()?;
+                Ok(Some(LoadEntry::Version(VersionDump { slot, version })))
+            }
+            _ => Err(self.state.err_nf(&key)),
+        }
+    }
+}
+
+impl<R> SerRon for Relations<R>
+where
+    R: SerRon,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        self.targets.ser_ron(d, s);
+    }
+}
+
+impl<R> DeRon for Relations<R>
+where
+    R: DeRon,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        Ok(Relations {
+            targets: DeRon::de_ron(s, i)?,
+        })
+    }
+}
+
+impl SerRon for EntityId {
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        self.bits().ser_ron(d, s);
+    }
+}
+
+impl DeRon for EntityId {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        let bits = u64::de_ron(s, i)?;
+        EntityId::from_bits(bits).ok_or_else(|| s.err_range("entity id"))
+    }
+}
+
+impl SerRon for ChildOf {
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        ().ser_ron(d, s);
+    }
+}
+
+impl DeRon for ChildOf {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        <()>::de_ron(s, i)?;
+        Ok(ChildOf)
+    }
+}
+
+macro_rules! text_dumper {
+    () => {};
+    ($($a:ident)+) => {
+        #[allow(non_snake_case)]
+        #[allow(unused_assignments)]
+        impl<'a $(, $a)+> Dumper<($($a,)+)> for DumperJson<'a>
+        where
+            $($a: SerJson + Dumpable,)+
+        {
+            type Error = Infallible;
+
+            fn dump(&mut self, entity: EntityDump, slots: ($(DumpSlot<'_, $a>,)+)) -> Result<(), Infallible> {
+                let [id, present, modified] = entity.0;
+                self.begin_entry();
+                let s = &mut *self.state;
+                s.field(1, "id");
+                id.ser_json(1, s);
+                s.conl();
+                s.field(1, "present");
+                present.ser_json(1, s);
+                s.conl();
+                s.field(1, "modified");
+                modified.ser_json(1, s);
+                s.conl();
+                s.field(1, "components");
+                s.out.push('[');
+                let ($($a,)+) = slots;
+                let mut first = true;
+                $(
+                    if !first {
+                        s.conl();
+                    }
+                    first = false;
+                    match $a {
+                        DumpSlot::Skipped => s.out.push_str("null"),
+                        DumpSlot::Component($a) => $a.ser_json(2, s),
+                    }
+                )+
+                s.out.push(']');
+                s.st_post(1);
+                Ok(())
+            }
+
+            fn tombstone(&mut self, tombstone: TombstoneDump) -> Result<(), Infallible> {
+                self.begin_entry();
+                self.state.field(1, "despawned");
+                tombstone.0.ser_json(1, self.state);
+                self.state.st_post(1);
+                Ok(())
+            }
+
+            fn version(&mut self, version: VersionDump) -> Result<(), Infallible> {
+                self.begin_entry();
+                self.state.field(1, "slot");
+                version.slot.ser_json(1, self.state);
+                self.state.conl();
+                self.state.field(1, "version");
+                version.version.ser_json(1, self.state);
+                self.state.st_post(1);
+                Ok(())
+            }
+        }
+
+        #[allow(non_snake_case)]
+        impl<'a $(, $a)+> Loader<($($a,)+)> for LoaderJson<'a>
+        where
+            $($a: DeJson + Dumpable,)+
+        {
+            type Error = DeJsonErr;
+
+            fn next(&mut self) -> Result<Option<LoadEntry>, DeJsonErr> {
+                self.next_entry()
+            }
+
+            fn error(&self, error: LoadError) -> DeJsonErr {
+                self.state.err_parse(&error.to_string())
+            }
+
+            fn load(&mut self, slots: &mut ($(LoadSlot<'_, $a>,)+)) -> Result<(), DeJsonErr> {
+                let s = &mut self.state;
+                let i = &mut self.chars;
+                let ($($a,)+) = slots;
+                $(
+                    match $a {
+                        LoadSlot::Skipped => <()>::de_json(s, i)?,
+                        LoadSlot::Missing => {
+                            let comp: $a = $a::de_json(s, i)?;
+                            *$a = LoadSlot::Created(comp);
+                        }
+                        LoadSlot::Existing(comp) => {
+                            **comp = $a::de_json(s, i)?;
+                        }
+                        LoadSlot::Migrate(migration) => {
+                            let comp: $a = match migration.decoder::<DecodeJson>() {
+                                None => $a::de_json(s, i)?,
+                                Some(decode) => migration.upgrade::<DecodeJson, $a>((decode.0)(s, i)?).map_err(|err| s.err_parse(&err.to_string()))?,
+                            };
+                            *$a = LoadSlot::Created(comp);
+                        }
+                        LoadSlot::Created(_) => unreachable!(),
+                    }
+                    s.eat_comma_block(i)?;
+                )+
+                s.block_close(i)?;
+                s.eat_comma_curly(i)?;
+                self.end_entry()
+            }
+        }
+
+        #[allow(non_snake_case)]
+        impl<'a $(, $a)+> Dumper<($($a,)+)> for DumperRon<'a>
+        where
+            $($a: SerRon + Dumpable,)+
+        {
+            type Error = Infallible;
+
+            fn dump(&mut self, entity: EntityDump, slots: ($(DumpSlot<'_, $a>,)+)) -> Result<(), Infallible> {
+                let [id, present, modified] = entity.0;
+                self.begin_entry();
+                let s = &mut *self.0;
+                s.field(2, "id");
+                id.ser_ron(2, s);
+                s.conl();
+                s.field(2, "present");
+                present.ser_ron(2, s);
+                s.conl();
+                s.field(2, "modified");
+                modified.ser_ron(2, s);
+                s.conl();
+                s.field(2, "components");
+                s.out.push_str("[\n");
+                let ($($a,)+) = slots;
+                $(
+                    s.indent(3);
+                    match $a {
+                        DumpSlot::Skipped => s.out.push_str("None"),
+                        DumpSlot::Component($a) => $a.ser_ron(3, s),
+                    }
+                    s.conl();
+                )+
+                s.indent(2);
+                s.out.push(']');
+                s.conl();
+                self.end_entry();
+                Ok(())
+            }
+
+            fn tombstone(&mut self, tombstone: TombstoneDump) -> Result<(), Infallible> {
+                self.begin_entry();
+                self.0.field(2, "despawned");
+                tombstone.0.ser_ron(2, self.0);
+                self.0.conl();
+                self.end_entry();
+                Ok(())
+            }
+
+            fn version(&mut self, version: VersionDump) -> Result<(), Infallible> {
+                self.begin_entry();
+                self.0.field(2, "slot");
+                version.slot.ser_ron(2, self.0);
+                self.0.conl();
+                self.0.field(2, "version");
+                version.version.ser_ron(2, self.0);
+                self.0.conl();
+                self.end_entry();
+                Ok(())
+            }
+        }
+
+        #[allow(non_snake_case)]
+        impl<'a $(, $a)+> Loader<($($a,)+)> for LoaderRon<'a>
+        where
+            $($a: DeRon + Dumpable,)+
+        {
+            type Error = DeRonErr;
+
+            fn next(&mut self) -> Result<Option<LoadEntry>, DeRonErr> {
+                self.next_entry()
+            }
+
+            fn error(&self, error: LoadError) -> DeRonErr {
+                self.state.err_parse(&error.to_string())
+            }
+
+            fn load(&mut self, slots: &mut ($(LoadSlot<'_, $a>,)+)) -> Result<(), DeRonErr> {
+                let ($($a,)+) = slots;
+                $(
+                    match $a {
+                        LoadSlot::Skipped => self.skip_none()?,
+                        LoadSlot::Missing => {
+                            let comp: $a = $a::de_ron(&mut self.state, &mut self.chars)?;
+                            *$a = LoadSlot::Created(comp);
+                        }
+                        LoadSlot::Existing(comp) => {
+                            **comp = $a::de_ron(&mut self.state, &mut self.chars)?;
+                        }
+                        LoadSlot::Migrate(migration) => {
+                            let comp: $a = match migration.decoder::<DecodeRon>() {
+                                None => $a::de_ron(&mut self.state, &mut self.chars)?,
+                                Some(decode) => {
+                                    let old = (decode.0)(&mut self.state, &mut self.chars)?;
+                                    migration.upgrade::<DecodeRon, $a>(old).map_err(|err| self.state.err_parse(&err.to_string()))?
+                                }
+                            };
+                            *$a = LoadSlot::Created(comp);
+                        }
+                        LoadSlot::Created(_) => unreachable!(),
+                    }
+                    self.state.eat_comma_block(&mut self.chars)?;
+                )+
+                self.state.block_close(&mut self.chars)?;
+                self.state.eat_comma_paren(&mut self.chars)?;
+                self.end_entry()
+            }
+        }
+
+        impl<'a $(, $a)+, Fi> SerJson for WorldDump<'a, ($($a,)+), Fi>
+        where
+            Fi: ImmutableQuery + Copy,
+            $($a: SerJson + Dumpable,)+
+        {
+            fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
+                s.out.push('[');
+                let result = <($($a,)+) as DumpSet>::dump_world(self.world, self.filter, self.epoch, &mut DumperJson::new(s));
+                match result {
+                    Ok(()) => {}
+                    Err(never) => match never {},
+                }
+                s.out.push(']');
+            }
+        }
+
+        impl<'a $(, $a)+, Fi> SerRon for WorldDump<'a, ($($a,)+), Fi>
+        where
+            Fi: ImmutableQuery + Copy,
+            $($a: SerRon + Dumpable,)+
+        {
+            fn ser_ron(&self, _d: usize, s: &mut SerRonState) {
+                s.out.push_str("[\n");
+                let result = <($($a,)+) as DumpSet>::dump_world(self.world, self.filter, self.epoch, &mut DumperRon(s));
+                match result {
+                    Ok(()) => {}
+                    Err(never) => match never {},
+                }
+                s.out.push(']');
+            }
+        }
+
+        impl<'a $(, $a)+, Ma> WorldLoad<'a, ($($a,)+), Ma>
+        where
+            Ma: Mark,
+        {
+            /// Loads world from JSON string using [`nanoserde::DeJson`].
+            pub fn load_json(&self, actions: &mut ActionEncoder, input: &str) -> Result<(), DeJsonErr>
+            where
+                $($a: DeJson + Dumpable,)+
+            {
+                <($($a,)+) as LoadSet>::load_world(self.world, self.marker, actions, &mut LoaderJson::new(input))
+            }
+
+            /// Loads world from JSON string using [`nanoserde::DeJson`].
+            /// Entities are loaded under fresh ids recorded in `remap`.
+            pub fn load_json_remap(&self, actions: &mut ActionEncoder, input: &str, remap: &mut EntityRemap) -> Result<(), DeJsonErr>
+            where
+                $($a: DeJson + Dumpable,)+
+            {
+                <($($a,)+) as LoadSet>::load_world_remap(self.world, self.marker, actions, &mut LoaderJson::new(input), remap)
+            }
+
+            /// Loads world from RON string using [`nanoserde::DeRon`].
+            pub fn load_ron(&self, actions: &mut ActionEncoder, input: &str) -> Result<(), DeRonErr>
+            where
+                $($a: DeRon + Dumpable,)+
+            {
+                <($($a,)+) as LoadSet>::load_world(self.world, self.marker, actions, &mut LoaderRon::new(input))
+            }
+
+            /// Loads world from RON string using [`nanoserde::DeRon`].
+            /// Entities are loaded under fresh ids recorded in `remap`.
+            pub fn load_ron_remap(&self, actions: &mut ActionEncoder, input: &str, remap: &mut EntityRemap) -> Result<(), DeRonErr>
+            where
+                $($a: DeRon + Dumpable,)+
+            {
+                <($($a,)+) as LoadSet>::load_world_remap(self.world, self.marker, actions, &mut LoaderRon::new(input), remap)
+            }
+        }
+    };
+}
+
+for_tuple!(text_dumper);
+
+#[test]
+fn test_dump() {
+    use ::nanoserde::{DeBin, SerBin};
+
+    use super::NoMark;
+    use crate::{action::ActionBuffer, component::Component, epoch::EpochId, world::World};
+
+    let mut world = World::new();
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Foo;
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Bar(u32);
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Baz(String);
+
+    let foo = world.spawn((Foo,));
+    let bar = world.spawn((Bar(42),));
+    let baz = world.spawn((Baz("qwerty".into()),));
+
+    let foo_bar = world.spawn((Foo, Bar(11)));
+    let foo_baz = world.spawn((Foo, Baz("asdfgh".into())));
+    let bar_baz = world.spawn((Bar(23), Baz("zxcvbn".into())));
+    let foo_bar_baz = world.spawn((Foo, Bar(155), Baz("123456".into())));
+
+    type Set = (Foo, Bar, Baz);
+
+    let data = WorldDump::<Set, _>::new(&mut world, (), EpochId::start()).serialize_bin();
+
+    let mut world2 = World::new();
+
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world2);
+
+    WorldLoad::<Set, _>::new(&world2, NoMark)
+        .load_bin(&mut actions, &data)
+        .unwrap();
+    buffer.execute(&mut world2);
+
+    assert_eq!(
+        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(foo),
+        Ok((Some(&Foo), None, None))
+    );
+
+    assert_eq!(
+        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(bar),
+        Ok((None, Some(&Bar(42)), None))
+    );
+
+    assert_eq!(
+        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(baz),
+        Ok((None, None, Some(&Baz("qwerty".into()))))
+    );
+
+    assert_eq!(
+        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(foo_bar),
+        Ok((Some(&Foo), Some(&Bar(11)), None))
+    );
+
+    assert_eq!(
+        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(foo_baz),
+        Ok((Some(&Foo), None, Some(&Baz("asdfgh".into()))))
+    );
+
+    assert_eq!(
+        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(bar_baz),
+        Ok((None, Some(&Bar(23)), Some(&Baz("zxcvbn".into()))))
+    );
+
+    assert_eq!(
+        world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(foo_bar_baz),
+        Ok((Some(&Foo), Some(&Bar(155)), Some(&Baz("123456".into()))))
+    );
+}
+
+#[test]
+fn test_load_incremental() {
+    use ::nanoserde::{DeBin, SerBin};
+
+    use super::NoMark;
+    use crate::{action::ActionBuffer, component::Component, epoch::EpochId, world::World};
+
+    let mut world = World::new();
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Foo(u32);
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Bar(u32);
+
+    type Set = (Foo, Bar);
+
+    let a = world.spawn((Foo(1), Bar(1)));
+    let b = world.spawn((Foo(2), Bar(2)));
+
+    let data = WorldDump::<Set, _>::new(&world, (), EpochId::start()).serialize_bin();
+
+    let mut world2 = World::new();
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world2);
+    WorldLoad::<Set, _>::new(&world2, NoMark)
+        .load_bin(&mut actions, &data)
+        .unwrap();
+    buffer.execute(&mut world2);
+
+    let epoch = world.epoch();
+    *world.query_one_mut::<&mut Foo>(a).unwrap() = Foo(3);
+    world.drop::<Bar>(b).unwrap();
+
+    let data = WorldDump::<Set, _>::new(&world, (), epoch).serialize_bin();
+
+    let mut actions = buffer.encoder(&world2);
+    WorldLoad::<Set, _>::new(&world2, NoMark)
+        .load_bin(&mut actions, &data)
+        .unwrap();
+    buffer.execute(&mut world2);
+
+    // Unmodified components are kept and removed ones are dropped.
+    assert_eq!(
+        world2.query_one_mut::<(&Foo, Option<&Bar>)>(a),
+        Ok((&Foo(3), Some(&Bar(1))))
+    );
+    assert_eq!(
+        world2.query_one_mut::<(&Foo, Option<&Bar>)>(b),
+        Ok((&Foo(2), None))
+    );
+}
+
+#[test]
+fn test_dump_relations() {
+    use ::nanoserde::{DeBin, SerBin};
+
+    use super::NoMark;
+    use crate::{
+        action::ActionBuffer,
+        component::Component,
+        epoch::EpochId,
+        relation::{ChildOf, Related, RelatesExclusive},
+        world::World,
+    };
+
+    let mut world = World::new();
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Bar(u32);
+
+    let parent = world.spawn((Bar(1),));
+    let child_a = world.spawn((Bar(2),));
+    let child_b = world.spawn((Bar(3),));
+    let grandchild = world.spawn((Bar(4),));
+
+    world.add_relation(child_a, ChildOf, parent).unwrap();
+    world.add_relation(child_b, ChildOf, parent).unwrap();
+    world.add_relation(grandchild, ChildOf, child_a).unwrap();
+
+    type Set = (Bar, Relations<ChildOf>);
+
+    let data = WorldDump::<Set, _>::new(&world, (), EpochId::start()).serialize_bin();
+
+    let mut world2 = World::new();
+
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world2);
+
+    WorldLoad::<Set, _>::new(&world2, NoMark)
+        .load_bin(&mut actions, &data)
+        .unwrap();
+    buffer.execute(&mut world2);
+
+    for (child, parent) in [(child_a, parent), (child_b, parent), (grandchild, child_a)] {
+        assert_eq!(
+            world2.query_one_mut::<RelatesExclusive<&ChildOf>>(child),
+            Ok((&ChildOf, parent))
+        );
+    }
+
+    let mut children = world2
+        .query_one_mut::<Related<ChildOf>>(parent)
+        .unwrap()
+        .to_vec();
+    children.sort();
+    assert_eq!(children, vec![child_a, child_b]);
+
+    assert_eq!(
+        world2.query_one_mut::<Related<ChildOf>>(child_a),
+        Ok(&[grandchild][..])
+    );
+
+    assert_eq!(world2.query_one_mut::<&Bar>(grandchild), Ok(&Bar(4)));
+}
+
+#[test]
+fn test_load_remap() {
+    use ::nanoserde::{DeBin, SerBin};
+
+    use super::{EntityRemap, MapEntities, NoMark};
+    use crate::{
+        action::ActionBuffer,
+        component::Component,
+        entity::EntityId,
+        epoch::EpochId,
+        relation::{ChildOf, RelatesExclusive},
+        world::World,
+    };
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Bar(u32);
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    #[edict(map_entities)]
+    struct Target(Option<EntityId>);
+
+    impl MapEntities for Target {
+        fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+            self.0.map_entities(mapper);
+        }
+    }
+
+    let mut world = World::new();
+
+    let parent = world.spawn((Bar(1),));
+    let child = world.spawn((Bar(2), Target(Some(parent))));
+    world.add_relation(child, ChildOf, parent).unwrap();
+
+    type Set = (Bar, Target, Relations<ChildOf>);
+
+    let data = WorldDump::<Set, _>::new(&world, (), EpochId::start()).serialize_bin();
+
+    let mut remaps = [EntityRemap::new(), EntityRemap::new()];
+
+    for remap in &mut remaps {
+        let mut buffer = ActionBuffer::new();
+        let mut actions = buffer.encoder(&world);
+
+        WorldLoad::<Set, _>::new(&world, NoMark)
+            .load_bin_remap(&mut actions, &data, remap)
+            .unwrap();
+        buffer.execute(&mut world);
+    }
+
+    let mut loaded = Vec::new();
+    for remap in &remaps {
+        assert_eq!(remap.len(), 2);
+
+        let new_parent = remap.get(parent).unwrap();
+        let new_child = remap.get(child).unwrap();
+        assert_ne!(new_parent, parent);
+        assert_ne!(new_child, child);
+
+        assert_eq!(world.query_one_mut::<&Bar>(new_parent), Ok(&Bar(1)));
+        assert_eq!(world.query_one_mut::<&Bar>(new_child), Ok(&Bar(2)));
+        assert_eq!(
+            world.query_one_mut::<&Target>(new_child),
+            Ok(&Target(Some(new_parent)))
+        );
+        assert_eq!(
+            world.query_one_mut::<RelatesExclusive<&ChildOf>>(new_child),
+            Ok((&ChildOf, new_parent))
+        );
+
+        loaded.push(new_parent);
+        loaded.push(new_child);
+    }
+
+    loaded.sort();
+    loaded.dedup();
+    assert_eq!(loaded.len(), 4);
+
+    assert_eq!(
+        world.query_one_mut::<&Target>(child),
+        Ok(&Target(Some(parent)))
+    );
+}
+
+#[test]
+fn test_dump_tombstones() {
+    use ::nanoserde::{DeBin, SerBin};
+
+    use super::NoMark;
+    use crate::{action::ActionBuffer, component::Component, epoch::EpochId, world::World};
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Bar(u32);
+
+    let mut world = World::builder().with_tombstones().build();
+
+    let a = world.spawn((Bar(1),));
+    let b = world.spawn((Bar(2),));
+
+    let mut world2 = World::new();
+
+    let mut load = |world: &World, world2: &mut World, epoch: EpochId| {
+        let data = WorldDump::<(Bar,), _>::new(world, (), epoch).serialize_bin();
+
+        let mut buffer = ActionBuffer::new();
+        let mut actions = buffer.encoder(world2);
+        WorldLoad::<(Bar,), _>::new(world2, NoMark)
+            .load_bin(&mut actions, &data)
+            .unwrap();
+        buffer.execute(world2);
+    };
+
+    load(&world, &mut world2, EpochId::start());
+    assert!(world2.is_alive(a));
+    assert!(world2.is_alive(b));
+
+    let epoch = world.epoch();
+    world.despawn(a).unwrap();
+    assert!(world.tombstones(epoch).eq([a]));
+
+    load(&world, &mut world2, epoch);
+    assert!(!world2.is_alive(a));
+    assert_eq!(world2.query_one_mut::<&Bar>(b), Ok(&Bar(2)));
+
+    world.trim_tombstones(world.epoch());
+    assert_eq!(world.tombstones(EpochId::start()).count(), 0);
+
+    // Records with unknown kind are rejected.
+    let mut data = WorldDump::<(Bar,), _>::new(&world, (), EpochId::start()).serialize_bin();
+    data[0] = u8::MAX;
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world2);
+    assert!(WorldLoad::<(Bar,), _>::new(&world2, NoMark)
+        .load_bin(&mut actions, &data)
+        .is_err());
+}
+
+#[test]
+fn test_named_dump() {
+    use ::nanoserde::{DeBin, SerBin};
+
+    use super::named::{Preserved, Unknown};
+    use crate::{component::Component, world::World};
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Foo(u32);
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Bar(u32);
+
+    let mut world = World::new();
+    let a = world.spawn((Foo(1), Bar(2)));
+    let b = world.spawn((Bar(3),));
+
+    let mut full = NamedRegistry::new();
+    full.register_bin::<Foo>();
+    full.register_as(
+        "bar",
+        |bar: &Bar, out| bar.ser_bin(out),
+        |bytes| Bar::deserialize_bin(bytes).ok(),
+    );
+
+    let data = full.dump(&world).serialize_bin();
+
+    // Registry without `Bar` preserves it.
+    let mut partial = NamedRegistry::new();
+    partial.register_bin::<Foo>();
+
+    let mut world2 = World::new();
+    partial
+        .load(
+            &mut world2,
+            &NamedDump::deserialize_bin(&data).unwrap(),
+            Unknown::Preserve,
+        )
+        .unwrap();
+
+    assert_eq!(world2.query_one_mut::<&Foo>(a), Ok(&Foo(1)));
+    assert_eq!(
+        world2.query_one_mut::<&Bar>(a),
+        Err(crate::world::QueryOneError::NotSatisfied)
+    );
+    assert_eq!(
+        world2.query_one_mut::<&Preserved>(b).unwrap().components[0].key,
+        "bar"
+    );
+
+    // Preserved components are written back and loaded by full registry.
+    let data = partial.dump(&world2).serialize_bin();
+
+    let mut world3 = World::new();
+    full.load(
+        &mut world3,
+        &NamedDump::deserialize_bin(&data).unwrap(),
+        Unknown::Skip,
+    )
+    .unwrap();
+
+    assert_eq!(world3.query_one_mut::<&Foo>(a), Ok(&Foo(1)));
+    assert_eq!(world3.query_one_mut::<&Bar>(a), Ok(&Bar(2)));
+    assert_eq!(world3.query_one_mut::<&Bar>(b), Ok(&Bar(3)));
+}
+
+#[test]
+fn test_load_migrations() {
+    use ::nanoserde::{DeBin, DeJson, DeRon, SerBin, SerJson, SerRon};
+
+    use super::NoMark;
+    use crate::{action::ActionBuffer, component::Component, epoch::EpochId, world::World};
+
+    // Version 0 stores only `x` in meters.
+    #[derive(Component, SerBin, DeBin, SerJson, DeJson, SerRon, DeRon)]
+    struct Pos0 {
+        x: u32,
+    }
+
+    // Version 1 adds `y`.
+    #[derive(Component, SerBin, DeBin, SerJson, DeJson, SerRon, DeRon)]
+    struct Pos1 {
+        x: u32,
+        y: u32,
+    }
+
+    // Version 2 stores centimeters.
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin, SerJson, DeJson, SerRon, DeRon)]
+    struct Pos {
+        x_cm: u64,
+        y_cm: u64,
+    }
+
+    let up0 = |p: Pos0| Pos1 { x: p.x, y: 0 };
+    let up1 = |p: Pos1| Pos {
+        x_cm: u64::from(p.x) * 100,
+        y_cm: u64::from(p.y) * 100,
+    };
+
+    let mut world0 = World::new();
+    let a = world0.spawn((Pos0 { x: 1 },));
+
+    let mut builder = World::builder();
+    builder.register_component::<Pos1>().version(1);
+    let mut world1 = builder.build();
+    world1.spawn(());
+    let b = world1.spawn((Pos1 { x: 2, y: 3 },));
+
+    let mut builder = World::builder();
+    builder.register_component::<Pos>().version(2);
+    let mut world2 = builder.build();
+    world2.spawn(());
+    world2.spawn(());
+    let c = world2.spawn((Pos { x_cm: 4, y_cm: 5 },));
+
+    macro_rules! check {
+        ($serialize:ident, $load:ident) => {{
+            let dumps = [
+                WorldDump::<(Pos0,), _>::new(&world0, (), EpochId::start()).$serialize(),
+                WorldDump::<(Pos1,), _>::new(&world1, (), EpochId::start()).$serialize(),
+                WorldDump::<(Pos,), _>::new(&world2, (), EpochId::start()).$serialize(),
+            ];
+
+            let mut builder = World::builder();
+            builder
+                .register_component::<Pos>()
+                .version(2)
+                .migration_bin(0, up0)
+                .migration_bin(1, up1)
+                .migration_json(0, up0)
+                .migration_json(1, up1)
+                .migration_ron(0, up0)
+                .migration_ron(1, up1);
+            let mut world = builder.build();
+
+            let mut buffer = ActionBuffer::new();
+            for dump in &dumps {
+                let mut actions = buffer.encoder(&world);
+                WorldLoad::<(Pos,), _>::new(&world, NoMark)
+                    .$load(&mut actions, dump)
+                    .unwrap();
+                buffer.execute(&mut world);
+            }
+
+            assert_eq!(
+                world.query_one_mut::<&Pos>(a),
+                Ok(&Pos { x_cm: 100, y_cm: 0 })
+            );
+            assert_eq!(
+                world.query_one_mut::<&Pos>(b),
+                Ok(&Pos {
+                    x_cm: 200,
+                    y_cm: 300
+                })
+            );
+            assert_eq!(
+                world.query_one_mut::<&Pos>(c),
+                Ok(&Pos { x_cm: 4, y_cm: 5 })
+            );
+        }};
+    }
+
+    check!(serialize_bin, load_bin);
+    check!(serialize_json, load_json);
+    check!(serialize_ron, load_ron);
+}
+
+#[test]
+fn test_load_migration_errors() {
+    use ::nanoserde::{DeBin, SerBin};
+
+    use super::NoMark;
+    use crate::{action::ActionBuffer, component::Component, epoch::EpochId, world::World};
+
+    #[derive(Component, SerBin, DeBin)]
+    struct Pos0 {
+        x: u32,
+    }
+
+    #[derive(Component, SerBin, DeBin)]
+    struct Pos1 {
+        x: u32,
+        y: u32,
+    }
+
+    #[derive(Component, SerBin, DeBin)]
+    struct Pos {
+        x_cm: u64,
+        y_cm: u64,
+    }
+
+    let mut world0 = World::new();
+    world0.spawn((Pos0 { x: 1 },));
+    let dump0 = WorldDump::<(Pos0,), _>::new(&world0, (), EpochId::start()).serialize_bin();
+
+    let mut builder = World::builder();
+    builder.register_component::<Pos>().version(2);
+    let mut world2 = builder.build();
+    world2.spawn((Pos { x_cm: 4, y_cm: 5 },));
+    let dump2 = WorldDump::<(Pos,), _>::new(&world2, (), EpochId::start()).serialize_bin();
+
+    // Migration from version 1 is missing.
+    let mut builder = World::builder();
+    builder
+        .register_component::<Pos>()
+        .version(2)
+        .migration_bin(0, |p: Pos0| Pos1 { x: p.x, y: 0 });
+    let world = builder.build();
+
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world);
+    assert!(WorldLoad::<(Pos,), _>::new(&world, NoMark)
+        .load_bin(&mut actions, &dump0)
+        .is_err());
+
+    // Dump is newer than the world.
+    let mut builder = World::builder();
+    builder.register_component::<Pos>().version(1);
+    let world = builder.build();
+
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world);
+    assert!(WorldLoad::<(Pos,), _>::new(&world, NoMark)
+        .load_bin(&mut actions, &dump2)
+        .is_err());
+}
+
+#[test]
+fn test_named_migrations() {
+    use ::nanoserde::{DeBin, SerBin};
+
+    use super::named::Unknown;
+    use crate::{component::Component, world::World};
+
+    #[derive(Component, SerBin, DeBin)]
+    struct Health0(u8);
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Health {
+        current: u32,
+        max: u32,
+    }
+
+    // Both types are stored under the same key.
+    let mut registry = NamedRegistry::new();
+    registry.register_as(
+        "health",
+        |comp: &Health0, output: &mut Vec<u8>| comp.ser_bin(output),
+        |bytes: &[u8]| Health0::deserialize_bin(bytes).ok(),
+    );
+
+    let mut world = World::new();
+    let a = world.spawn((Health0(7),));
+    let dump = registry.dump(&world);
+
+    let mut builder = World::builder();
+    builder
+        .register_component::<Health>()
+        .version(1)
+        .migration_named(
+            0,
+            |bytes: &[u8]| Health0::deserialize_bin(bytes).ok(),
+            |old: Health0| Health {
+                current: u32::from(old.0),
+                max: 100,
+            },
+        );
+    let mut world2 = builder.build();
+
+    let mut registry = NamedRegistry::new();
+    registry.register_as(
+        "health",
+        |comp: &Health, output: &mut Vec<u8>| comp.ser_bin(output),
+        |bytes: &[u8]| Health::deserialize_bin(bytes).ok(),
+    );
+    registry.load(&mut world2, &dump, Unknown::Skip).unwrap();
+
+    let expected = Health {
+        current: 7,
+        max: 100,
+    };
+    assert_eq!(world2.query_one_mut::<&Health>(a), Ok(&expected));
+
+    // Current version is written and loaded as is.
+    let dump = registry.dump(&world2);
+    assert_eq!(dump.entities[0].components[0].version, 1);
+
+    let mut world3 = World::new();
+    registry.load(&mut world3, &dump, Unknown::Skip).unwrap();
+    assert_eq!(world3.query_one_mut::<&Health>(a), Ok(&expected));
+
+    // `register_bin` uses binary migrations.
+    let mut world = World::new();
+    let d = world.spawn((Health0(9),));
+    let mut old = NamedRegistry::new();
+    old.register_bin::<Health0>();
+    let mut dump = old.dump(&world);
+    dump.entities[0].components[0].key = Health::name().into();
+
+    let mut builder = World::builder();
+    builder
+        .register_component::<Health>()
+        .version(1)
+        .migration_bin(0, |old: Health0| Health {
+            current: u32::from(old.0),
+            max: 50,
+        });
+    let mut world4 = builder.build();
+
+    let mut registry = NamedRegistry::new();
+    registry.register_bin::<Health>();
+    registry.load(&mut world4, &dump, Unknown::Skip).unwrap();
+    assert_eq!(
+        world4.query_one_mut::<&Health>(d),
+        Ok(&Health {
+            current: 9,
+            max: 50
+        })
+    );
+}
+
+#[test]
+fn test_dump_resources() {
+    use ::nanoserde::{DeBin, SerBin};
+
+    use super::NoMark;
+    use crate::{action::ActionBuffer, component::Component, epoch::EpochId, world::World};
+
+    #[derive(Component, Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Bar(u32);
+
+    #[derive(Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Score(u32);
+
+    #[derive(Debug, PartialEq, Eq, SerBin, DeBin)]
+    struct Level(String);
+
+    let mut world = World::new();
+    let a = world.spawn((Bar(1),));
+    world.insert_resource(Score(42));
+
+    let data = WorldDump::<(Bar,), _>::new(&world, (), EpochId::start())
+        .with_resources::<(Score, Level)>()
+        .serialize_bin();
+
+    let mut world2 = World::new();
+    world2.insert_resource(Level("old".into()));
+
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world2);
+    WorldLoad::<(Bar,), _>::new(&world2, NoMark)
+        .with_resources::<(Score, Level)>()
+        .load_bin(&mut actions, &data)
+        .unwrap();
+    buffer.execute(&mut world2);
+
+    assert_eq!(world2.query_one_mut::<&Bar>(a), Ok(&Bar(1)));
+    assert_eq!(*world2.expect_resource::<Score>(), Score(42));
+    assert_eq!(*world2.expect_resource::<Level>(), Level("old".into()));
+}
+
+#[test]
+fn test_dump_text() {
+    use ::nanoserde::{DeJson, DeRon, SerJson, SerRon};
+
+    use super::NoMark;
+    use crate::{action::ActionBuffer, component::Component, epoch::EpochId, world::World};
+
+    #[derive(Component, Debug, PartialEq, Eq, SerJson, DeJson, SerRon, DeRon)]
+    struct Foo {
+        name: String,
+    }
+
+    #[derive(Component, Debug, PartialEq, Eq, SerJson, DeJson, SerRon, DeRon)]
+    struct Bar(u32);
+
+    type Set = (Foo, Bar);
+
+    let mut world = World::builder().with_tombstones().build();
+    let a = world.spawn((Foo { name: "a".into() }, Bar(1)));
+    let b = world.spawn((Bar(2),));
+    let c = world.spawn((Bar(3),));
+    world.despawn(c).unwrap();
+
+    let json = WorldDump::<Set, _>::new(&world, (), EpochId::start()).serialize_json();
+    assert!(json.contains(&format!("\"id\":{}", a.bits())));
+    assert!(json.contains(&format!("\"despawned\":{}", c.bits())));
+
+    let ron = WorldDump::<Set, _>::new(&world, (), EpochId::start()).serialize_ron();
+    assert!(ron.contains(&format!("id:{}", b.bits())));
+
+    let check = |world2: &mut World| {
+        assert_eq!(
+            world2.query_one_mut::<(&Foo, &Bar)>(a),
+            Ok((&Foo { name: "a".into() }, &Bar(1)))
+        );
+        assert_eq!(
+            world2.query_one_mut::<(Option<&Foo>, &Bar)>(b),
+            Ok((None, &Bar(2)))
+        );
+        assert!(!world2.is_alive(c));
+    };
+
+    let mut world2 = World::new();
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world2);
+    WorldLoad::<Set, _>::new(&world2, NoMark)
+        .load_json(&mut actions, &json)
+        .unwrap();
+    buffer.execute(&mut world2);
+    check(&mut world2);
+
+    let mut world3 = World::new();
+    let mut actions = buffer.encoder(&world3);
+    WorldLoad::<Set, _>::new(&world3, NoMark)
+        .load_ron(&mut actions, &ron)
+        .unwrap();
+    buffer.execute(&mut world3);
+    check(&mut world3);
 }
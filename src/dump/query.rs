@@ -10,26 +10,20 @@ use crate::{
     },
 };
 
+use super::Dumpable;
+
 /// Query result per component.
-pub enum DumpItem<'a, T> {
+pub enum DumpItem<'a, T: Dumpable> {
     /// Component is missing.
     Missing,
 
     /// Component is present and modified.
-    Modified(&'a T),
+    Modified(T::Dump<'a>),
 
     /// Component is present and unmodified.
     Unmodified,
 }
 
-impl<'a, T> Clone for DumpItem<'a, T> {
-    fn clone(&self) -> Self {
-        *self
-    }
-}
-
-impl<'a, T> Copy for DumpItem<'a, T> {}
-
 /// Query for fetching components to serialize.
 pub(super) struct DumpQuery<T> {
     after_epoch: EpochId,
@@ -48,14 +42,14 @@ impl<T> DumpQuery<T> {
 
 pub(super) struct DumpFetch<'a, T> {
     after_epoch: EpochId,
-    ptr: Option<NonNull<T>>,
+    ptr: Option<NonNull<u8>>,
     entity_epochs: NonNull<EpochId>,
     marker: PhantomData<&'a [T]>,
 }
 
 unsafe impl<'a, T> Fetch<'a> for DumpFetch<'a, T>
 where
-    T: Sync + 'a,
+    T: Dumpable,
 {
     type Item = DumpItem<'a, T>;
 
@@ -76,7 +70,7 @@ where
             Some(ptr) => {
                 let epoch = unsafe { *self.entity_epochs.as_ptr().add(idx) };
                 if epoch.after(self.after_epoch) {
-                    DumpItem::Modified(unsafe { &*ptr.as_ptr().add(idx) })
+                    DumpItem::Modified(unsafe { T::dump(ptr, idx) })
                 } else {
                     DumpItem::Unmodified
                 }
@@ -92,7 +86,7 @@ macro_rules! impl_dump_query {
     ($($a:ident)*) => {
         impl<$($a),*> IntoQuery for DumpQuery<($($a,)*)>
         where
-            $($a: Sync + 'static,)*
+            $($a: Dumpable,)*
         {
             type Query = Self;
 
@@ -105,7 +99,7 @@ macro_rules! impl_dump_query {
         #[allow(non_snake_case)]
         unsafe impl<$($a),*> Query for DumpQuery<($($a,)*)>
         where
-            $($a: Sync + 'static,)*
+            $($a: Dumpable,)*
         {
             type Item<'a> = (EntityId, ($(DumpItem<'a, $a>),*));
             type Fetch<'a> = (EntitiesFetch<'a>, ($(DumpFetch<'a, $a>),*));
@@ -113,18 +107,25 @@ macro_rules! impl_dump_query {
             #[inline]
             fn access(&self, ty: TypeId) -> Option<Access> {
                 let mut result = None;
-                $(result = merge_access(result, <&$a as PhantomQuery>::access(ty));)*
+                $(if ty == $a::component_id() {
+                    result = merge_access(result, Some(Access::Read));
+                })*
                 result
             }
 
             #[inline]
             fn visit_archetype(&self, archetype: &Archetype) -> bool {
-                false $(|| archetype.has_component(TypeId::of::<$a>()))*
+                false $(|| archetype.has_component($a::component_id()))*
+            }
+
+            #[inline]
+            fn include_disabled(&self) -> bool {
+                true
             }
 
             #[inline]
             unsafe fn access_archetype(&self, _archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
-                $(f(TypeId::of::<$a>(), Access::Read);)*
+                $(f($a::component_id(), Access::Read);)*
             }
 
             #[inline]
@@ -134,7 +135,7 @@ macro_rules! impl_dump_query {
                 epoch: EpochId,
             ) -> (EntitiesFetch<'a>, ($(DumpFetch<'a, $a>),*)) {
                 let ($($a,)*) = ($(
-                    match archetype.component(TypeId::of::<$a>()) {
+                    match archetype.component($a::component_id()) {
                         None => DumpFetch {
                             after_epoch: self.after_epoch,
                             ptr: None,
@@ -145,7 +146,7 @@ macro_rules! impl_dump_query {
                             let data = unsafe { component.data() };
                             DumpFetch {
                                 after_epoch: self.after_epoch,
-                                ptr: Some(data.ptr.cast()),
+                                ptr: Some(data.ptr),
                                 entity_epochs: unsafe { NonNull::new_unchecked(data.entity_epochs.as_ptr() as *mut EpochId) },
                                 marker: PhantomData,
                             }
@@ -157,7 +158,7 @@ macro_rules! impl_dump_query {
 
         unsafe impl<$($a),*> ImmutableQuery for DumpQuery<($($a,)*)>
         where
-            $($a: Sync + 'static,)*
+            $($a: Dumpable,)*
         {}
     };
 }
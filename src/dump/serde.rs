@@ -2,16 +2,394 @@
 //!
 //! This module provides the [`ComponentDump`](ComponentDump) trait, which can be implemented for
 
-use serde::ser::{Serialize, SerializeSeq, Serializer};
+use core::{any::Any, fmt, marker::PhantomData};
 
-use crate::query::ImmutableQuery;
+use alloc::{boxed::Box, string::String, vec::Vec};
 
-use super::{DumpSet, DumpSlot, Dumper, EntityDump, WorldDump};
+use serde::{
+    de::{
+        DeserializeSeed, Deserializer, Error as _, IgnoredAny, MapAccess, SeqAccess, Unexpected,
+        Visitor,
+    },
+    ser::{Serialize, SerializeSeq, SerializeStruct, Serializer},
+    Deserialize,
+};
+
+use crate::{
+    action::ActionEncoder,
+    component::{ComponentInfoRef, DropHook, InsertHook, SetHook},
+    query::ImmutableQuery,
+    relation::ChildOf,
+};
+
+use super::{
+    named::{NamedComponentDump, NamedDump, NamedEntityDump},
+    Decoded, DumpSet, DumpSlot, Dumpable, Dumper, EntityDump, EntityRemap, LoadEntry, LoadError,
+    LoadSet, LoadSlot, Loader, Mark, Migration, Relations, ResourceDumper, ResourceLoader,
+    ResourceSet, TombstoneDump, VersionDump, WorldDump, WorldLoad,
+};
 
 /// Wrapper for `serde::ser::SerializeSeq` that implements `Dumper`.
 pub struct SerdeDumper<'a, S>(pub &'a mut S);
 
-struct SerializeDump<T>([u64; 3], T);
+struct SerializeDump<T>(u8, [u64; 3], T);
+
+/// Record without component values.
+struct SerializeRecord(u8, [u64; 3]);
+
+impl Serialize for SerializeRecord {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(2))?;
+        seq.serialize_element(&self.0)?;
+        seq.serialize_element(&self.1)?;
+        seq.end()
+    }
+}
+
+/// Loader that reads entries from `serde::de::SeqAccess`.
+///
+/// Each entry is deserialized as a whole,
+/// then deserialized values are moved into the slots.
+/// Values dumped with older versions are upgraded while entry is deserialized.
+pub struct SerdeLoader<'de, A, T> {
+    seq: A,
+    values: Option<T>,
+    migrations: Vec<Option<Migration>>,
+    marker: PhantomData<&'de ()>,
+}
+
+impl<'de, A, T> SerdeLoader<'de, A, T> {
+    /// Returns new loader that reads entries from `seq`.
+    pub fn new(seq: A) -> Self {
+        SerdeLoader {
+            seq,
+            values: None,
+            migrations: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Seed to deserialize one entry.
+/// Yields entry and tuple of optional values.
+struct EntrySeed<'a, T> {
+    migrations: &'a [Option<Migration>],
+    marker: PhantomData<fn() -> T>,
+}
+
+/// Decoder of values dumped with older version with [`serde::Deserialize`].
+struct DecodeSerde(fn(&mut dyn erased_serde::Deserializer) -> Decoded<erased_serde::Error>);
+
+fn decode_serde<T>(de: &mut dyn erased_serde::Deserializer) -> Decoded<erased_serde::Error>
+where
+    T: for<'de> Deserialize<'de> + 'static,
+{
+    Ok(Box::new(erased_serde::deserialize::<T>(de)?))
+}
+
+/// Seed to deserialize value dumped with older version.
+struct DecodeSeed<'a>(&'a DecodeSerde);
+
+impl<'de> DeserializeSeed<'de> for DecodeSeed<'_> {
+    type Value = Box<dyn Any>;
+
+    fn deserialize<D>(self, deserializer: D) -> Decoded<D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut erased = <dyn erased_serde::Deserializer>::erase(deserializer);
+        (self.0 .0)(&mut erased).map_err(D::Error::custom)
+    }
+}
+
+impl<T, D, S, I> ComponentInfoRef<'_, T, D, S, I>
+where
+    T: 'static,
+    D: DropHook<T>,
+    S: SetHook<T>,
+    I: InsertHook<T>,
+{
+    /// Registers migration of values dumped with version `from`
+    /// with [`serde::Serialize`].
+    ///
+    /// Dumped value is decoded as `Old`
+    /// and `migration` converts it into value of version `from + 1`.
+    pub fn migration_serde<Old, New, F>(self, from: u32, migration: F) -> Self
+    where
+        Old: for<'de> Deserialize<'de> + 'static,
+        New: 'static,
+        F: Fn(Old) -> New + Send + Sync + 'static,
+    {
+        self.migration_with(from, DecodeSerde(decode_serde::<Old>), migration)
+    }
+}
+
+/// Seed to deserialize the world dump into `WorldLoad`.
+///
+/// Created with [`WorldLoad::seed`] and [`WorldLoad::seed_remap`].
+pub struct WorldLoadSeed<'a, 'b, T, M, R = ()> {
+    load: &'a WorldLoad<'a, T, M, R>,
+    actions: &'a mut ActionEncoder<'b>,
+    remap: Option<&'a mut EntityRemap>,
+}
+
+impl<'a, T, M, R> WorldLoad<'a, T, M, R> {
+    /// Returns [`DeserializeSeed`] that loads the world dump.
+    pub fn seed<'b>(
+        &'a self,
+        actions: &'a mut ActionEncoder<'b>,
+    ) -> WorldLoadSeed<'a, 'b, T, M, R> {
+        WorldLoadSeed {
+            load: self,
+            actions,
+            remap: None,
+        }
+    }
+
+    /// Returns [`DeserializeSeed`] that loads the world dump.
+    /// Entities are loaded under fresh ids recorded in `remap`.
+    pub fn seed_remap<'b>(
+        &'a self,
+        actions: &'a mut ActionEncoder<'b>,
+        remap: &'a mut EntityRemap,
+    ) -> WorldLoadSeed<'a, 'b, T, M, R> {
+        WorldLoadSeed {
+            load: self,
+            actions,
+            remap: Some(remap),
+        }
+    }
+}
+
+/// Resources are serialized as options.
+impl<T> Serialize for DumpSlot<'_, T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            DumpSlot::Skipped => serializer.serialize_none(),
+            DumpSlot::Component(value) => serializer.serialize_some(value),
+        }
+    }
+}
+
+/// Resources are deserialized from options.
+impl<'de, T> Deserialize<'de> for LoadSlot<'_, T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<T>::deserialize(deserializer)? {
+            None => Ok(LoadSlot::Skipped),
+            Some(value) => Ok(LoadSlot::Created(value)),
+        }
+    }
+}
+
+/// Non-empty resource set is serialized as the first element of the dump.
+impl<Se, R> ResourceDumper<R> for SerdeDumper<'_, Se>
+where
+    Se: SerializeSeq,
+    R: ResourceSet,
+    for<'a> R::DumpSlots<'a>: Serialize,
+{
+    type Error = Se::Error;
+
+    fn dump_resources(&mut self, _present: u64, slots: R::DumpSlots<'_>) -> Result<(), Se::Error> {
+        if R::LEN == 0 {
+            return Ok(());
+        }
+        self.0.serialize_element(&slots)
+    }
+}
+
+impl<'de, Ac, T, R> ResourceLoader<R> for SerdeLoader<'de, Ac, T>
+where
+    Ac: SeqAccess<'de>,
+    R: ResourceSet,
+    for<'a> R::LoadSlots<'a>: Deserialize<'de>,
+{
+    type Error = Ac::Error;
+
+    fn load_resources(&mut self, slots: &mut R::LoadSlots<'_>) -> Result<(), Ac::Error> {
+        if R::LEN == 0 {
+            return Ok(());
+        }
+        *slots = self
+            .seq
+            .next_element()?
+            .ok_or_else(|| Ac::Error::invalid_length(0, &"resources"))?;
+        Ok(())
+    }
+}
+
+impl<'de, R> Deserialize<'de> for Relations<R>
+where
+    R: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RelationsVisitor<R>(PhantomData<fn() -> R>);
+
+        impl<'de, R> Visitor<'de> for RelationsVisitor<R>
+        where
+            R: Deserialize<'de>,
+        {
+            type Value = Relations<R>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("sequence of relation targets")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Relations<R>, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut targets = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(target) = seq.next_element::<(u64, R)>()? {
+                    targets.push(target);
+                }
+                Ok(Relations { targets })
+            }
+        }
+
+        deserializer.deserialize_seq(RelationsVisitor(PhantomData))
+    }
+}
+
+impl<R> Serialize for Relations<R>
+where
+    R: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(&self.targets)
+    }
+}
+
+impl Serialize for ChildOf {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_unit_struct("ChildOf")
+    }
+}
+
+impl<'de> Deserialize<'de> for ChildOf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ChildOfVisitor;
+
+        impl Visitor<'_> for ChildOfVisitor {
+            type Value = ChildOf;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("unit struct ChildOf")
+            }
+
+            fn visit_unit<E>(self) -> Result<ChildOf, E> {
+                Ok(ChildOf)
+            }
+        }
+
+        deserializer.deserialize_unit_struct("ChildOf", ChildOfVisitor)
+    }
+}
+
+/// Implements `Serialize` and `Deserialize` for struct with named fields.
+/// Structs are deserialized from both sequences and maps,
+/// so that self-describing and non-self-describing formats are supported.
+macro_rules! serde_struct {
+    ($name:ident { $($field:ident),+ }) => {
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut state = serializer.serialize_struct(stringify!($name), [$(stringify!($field)),+].len())?;
+                $(state.serialize_field(stringify!($field), &self.$field)?;)+
+                state.end()
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct StructVisitor;
+
+                impl<'de> Visitor<'de> for StructVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        f.write_str(concat!("struct ", stringify!($name)))
+                    }
+
+                    #[allow(unused_assignments)]
+                    fn visit_seq<A>(self, mut seq: A) -> Result<$name, A::Error>
+                    where
+                        A: SeqAccess<'de>,
+                    {
+                        let mut len = 0;
+                        $(
+                            let $field = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(len, &self))?;
+                            len += 1;
+                        )+
+                        Ok($name { $($field),+ })
+                    }
+
+                    fn visit_map<A>(self, mut map: A) -> Result<$name, A::Error>
+                    where
+                        A: MapAccess<'de>,
+                    {
+                        $(let mut $field = None;)+
+                        while let Some(key) = map.next_key::<String>()? {
+                            match &*key {
+                                $(stringify!($field) => {
+                                    if $field.is_some() {
+                                        return Err(A::Error::duplicate_field(stringify!($field)));
+                                    }
+                                    $field = Some(map.next_value()?);
+                                })+
+                                _ => {
+                                    map.next_value::<IgnoredAny>()?;
+                                }
+                            }
+                        }
+                        $(
+                            let $field = $field.ok_or_else(|| A::Error::missing_field(stringify!($field)))?;
+                        )+
+                        Ok($name { $($field),+ })
+                    }
+                }
+
+                deserializer.deserialize_struct(stringify!($name), &[$(stringify!($field)),+], StructVisitor)
+            }
+        }
+    };
+}
+
+serde_struct!(NamedComponentDump { key, version, data });
+serde_struct!(NamedEntityDump { id, components });
+serde_struct!(NamedDump { entities });
 
 macro_rules! dumper {
     () => {};
@@ -25,9 +403,17 @@ macro_rules! dumper {
             where
                 S: Serializer,
             {
-                let mut seq = serializer.serialize_seq(None)?;
+                let ($($a,)+) = &self.2;
+                let mut len = 2;
+                $(
+                    if let DumpSlot::Component(_) = $a {
+                        len += 1;
+                    }
+                )+
+
+                let mut seq = serializer.serialize_seq(Some(len))?;
                 seq.serialize_element(&self.0)?;
-                let ($($a,)+) = &self.1;
+                seq.serialize_element(&self.1)?;
                 $(
                     match $a {
                         DumpSlot::Skipped => {}
@@ -40,31 +426,509 @@ macro_rules! dumper {
 
         impl<'a $(, $a)+, Se> Dumper<($($a,)+)> for SerdeDumper<'_, Se>
         where
-            $($a: Serialize + Sync + 'static,)+
+            $($a: Serialize + Dumpable,)+
             Se: SerializeSeq,
         {
             type Error = Se::Error;
             fn dump(&mut self, entity: EntityDump, slots: ($(DumpSlot<'_, $a>,)+)) -> Result<(), Se::Error> {
-                self.0.serialize_element(&SerializeDump(entity.0, slots))
+                let (tag, triple) = entity.to_record();
+                self.0.serialize_element(&SerializeDump(tag, triple, slots))
+            }
+
+            // Serialized as sequences like entities,
+            // so that length-prefixed formats can read them back.
+            fn tombstone(&mut self, tombstone: TombstoneDump) -> Result<(), Se::Error> {
+                let (tag, triple) = tombstone.to_record();
+                self.0.serialize_element(&SerializeRecord(tag, triple))
+            }
+
+            fn version(&mut self, version: VersionDump) -> Result<(), Se::Error> {
+                let (tag, triple) = version.to_record();
+                self.0.serialize_element(&SerializeRecord(tag, triple))
             }
         }
 
-        impl<'a $(, $a)+, Fi> Serialize for WorldDump<'a, ($($a,)+), Fi>
+        impl<'a $(, $a)+, Fi, Re> Serialize for WorldDump<'a, ($($a,)+), Fi, Re>
         where
             Fi: ImmutableQuery + Copy,
-            $($a: Serialize + Sync + 'static,)+
+            $($a: Serialize + Dumpable,)+
+            Re: ResourceSet,
+            for<'b> Re::DumpSlots<'b>: Serialize,
         {
             fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
             where
                 S: Serializer,
             {
-                let mut seq = serializer.serialize_seq(None)?;
+                // Non-self-describing formats require length upfront.
+                let len = <($($a,)+) as DumpSet>::dump_len(self.world, self.filter, self.epoch)
+                    + usize::from(Re::LEN != 0);
+
+                let mut seq = serializer.serialize_seq(Some(len))?;
                 let mut dumper = SerdeDumper(&mut seq);
+                Re::dump_resources(self.world, &mut dumper)?;
                 <($($a,)+) as DumpSet>::dump_world(self.world, self.filter, self.epoch, &mut dumper)?;
                 seq.end()
             }
         }
+
+        impl<'de $(, $a)+> DeserializeSeed<'de> for EntrySeed<'_, ($($a,)+)>
+        where
+            $($a: Deserialize<'de> + 'static,)+
+        {
+            type Value = (LoadEntry, ($(Option<$a>,)+));
+
+            fn deserialize<De>(self, deserializer: De) -> Result<Self::Value, De::Error>
+            where
+                De: Deserializer<'de>,
+            {
+                deserializer.deserialize_seq(self)
+            }
+        }
+
+        #[allow(non_snake_case)]
+        #[allow(unused_assignments)]
+        impl<'de $(, $a)+> Visitor<'de> for EntrySeed<'_, ($($a,)+)>
+        where
+            $($a: Deserialize<'de> + 'static,)+
+        {
+            type Value = (LoadEntry, ($(Option<$a>,)+));
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("entity dump")
+            }
+
+            fn visit_seq<Ac>(self, mut seq: Ac) -> Result<Self::Value, Ac::Error>
+            where
+                Ac: SeqAccess<'de>,
+            {
+                let tag: u8 = seq.next_element()?.ok_or_else(|| Ac::Error::invalid_length(0, &self))?;
+                let triple: [u64; 3] = seq.next_element()?.ok_or_else(|| Ac::Error::invalid_length(1, &self))?;
+                let entry = LoadEntry::from_record(tag, triple)
+                    .ok_or_else(|| Ac::Error::invalid_value(Unexpected::Unsigned(tag.into()), &"record kind tag"))?;
+                let modified = match &entry {
+                    LoadEntry::Entity(EntityDump([_, _, modified])) => *modified,
+                    LoadEntry::Tombstone(_) | LoadEntry::Version(_) => 0,
+                };
+
+                let mut len = 2;
+                let values = indexed_tuple!(idx => $(
+                    if modified & (1 << idx) == 0 {
+                        None
+                    } else {
+                        let migration = self.migrations.get(idx).and_then(Option::as_ref);
+                        let $a: $a = match migration.and_then(|m| Some((m, m.decoder::<DecodeSerde>()?))) {
+                            None => seq.next_element()?.ok_or_else(|| Ac::Error::invalid_length(len, &self))?,
+                            Some((migration, decode)) => {
+                                let old = seq.next_element_seed(DecodeSeed(decode))?.ok_or_else(|| Ac::Error::invalid_length(len, &self))?;
+                                migration.upgrade::<DecodeSerde, $a>(old).map_err(Ac::Error::custom)?
+                            }
+                        };
+                        len += 1;
+                        Some($a)
+                    }
+                ),+);
+                Ok((entry, values))
+            }
+        }
     };
 }
 
 for_tuple!(dumper);
+
+macro_rules! loader {
+    (,) => {};
+    ($($a:ident)+, $($v:ident)+) => {
+        #[allow(non_snake_case)]
+        impl<'de, Ac $(, $a)+> Loader<($($a,)+)> for SerdeLoader<'de, Ac, ($(Option<$a>,)+)>
+        where
+            Ac: SeqAccess<'de>,
+            $($a: Deserialize<'de> + Dumpable,)+
+        {
+            type Error = Ac::Error;
+
+            fn next(&mut self) -> Result<Option<LoadEntry>, Ac::Error> {
+                let seed = EntrySeed::<($($a,)+)> {
+                    migrations: &self.migrations,
+                    marker: PhantomData,
+                };
+                match self.seq.next_element_seed(seed)? {
+                    None => Ok(None),
+                    Some((entry, values)) => {
+                        self.values = Some(values);
+                        Ok(Some(entry))
+                    }
+                }
+            }
+
+            fn error(&self, error: LoadError) -> Ac::Error {
+                Ac::Error::custom(error)
+            }
+
+            fn migration(&mut self, slot: u32, migration: Option<&Migration>) {
+                let slot = slot as usize;
+                if self.migrations.len() <= slot {
+                    self.migrations.resize(slot + 1, None);
+                }
+                self.migrations[slot] = migration.cloned();
+            }
+
+            fn load(&mut self, slots: &mut ($(LoadSlot<'_, $a>,)+)) -> Result<(), Ac::Error> {
+                let ($($a,)+) = slots;
+                let ($($v,)+) = self.values.take().unwrap();
+                $(
+                    if let Some($v) = $v {
+                        match $a {
+                            LoadSlot::Missing | LoadSlot::Migrate(_) => *$a = LoadSlot::Created($v),
+                            LoadSlot::Existing(comp) => **comp = $v,
+                            LoadSlot::Skipped | LoadSlot::Created(_) => unreachable!(),
+                        }
+                    }
+                )+
+                Ok(())
+            }
+        }
+
+        impl<'de, Ma $(, $a)+, Re> DeserializeSeed<'de> for WorldLoadSeed<'_, '_, ($($a,)+), Ma, Re>
+        where
+            Ma: Mark,
+            $($a: Deserialize<'de> + Dumpable,)+
+            Re: ResourceSet,
+            for<'b> Re::LoadSlots<'b>: Deserialize<'de>,
+        {
+            type Value = ();
+
+            fn deserialize<De>(self, deserializer: De) -> Result<(), De::Error>
+            where
+                De: Deserializer<'de>,
+            {
+                deserializer.deserialize_seq(self)
+            }
+        }
+
+        impl<'de, Ma $(, $a)+, Re> Visitor<'de> for WorldLoadSeed<'_, '_, ($($a,)+), Ma, Re>
+        where
+            Ma: Mark,
+            $($a: Deserialize<'de> + Dumpable,)+
+            Re: ResourceSet,
+            for<'b> Re::LoadSlots<'b>: Deserialize<'de>,
+        {
+            type Value = ();
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("world dump")
+            }
+
+            fn visit_seq<Ac>(self, seq: Ac) -> Result<(), Ac::Error>
+            where
+                Ac: SeqAccess<'de>,
+            {
+                let mut loader = SerdeLoader::<Ac, ($(Option<$a>,)+)>::new(seq);
+                let load = self.load;
+                Re::load_resources(self.actions, &mut loader)?;
+                match self.remap {
+                    None => <($($a,)+) as LoadSet>::load_world(load.world, load.marker, self.actions, &mut loader),
+                    Some(remap) => <($($a,)+) as LoadSet>::load_world_remap(load.world, load.marker, self.actions, &mut loader, remap),
+                }
+            }
+        }
+
+        impl<Ma $(, $a)+, Re> WorldLoad<'_, ($($a,)+), Ma, Re>
+        where
+            Ma: Mark,
+            Re: ResourceSet,
+        {
+            /// Loads the world dump from serde deserializer.
+            pub fn load_serde<'de, D>(&self, actions: &mut ActionEncoder, deserializer: D) -> Result<(), D::Error>
+            where
+                D: Deserializer<'de>,
+                $($a: Deserialize<'de> + Dumpable,)+
+                for<'b> Re::LoadSlots<'b>: Deserialize<'de>,
+            {
+                self.seed(actions).deserialize(deserializer)
+            }
+        }
+    };
+}
+
+for_tuple_2!(loader);
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec::Vec};
+
+    use ::serde::{Deserialize, Serialize};
+    use bincode::Options;
+
+    use super::super::{
+        named::{NamedDump, NamedRegistry, Preserved, Unknown},
+        NoMark, WorldDump, WorldLoad,
+    };
+    use crate::{
+        action::{ActionBuffer, ActionEncoder},
+        component::Component,
+        epoch::EpochId,
+        world::World,
+    };
+
+    #[derive(Component, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Foo;
+
+    #[derive(Component, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Bar(u32);
+
+    #[derive(Component, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Baz(String);
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Score(u32);
+
+    type Set = (Foo, Bar, Baz);
+    type Dump<'a> = WorldDump<'a, Set, (), (Score,)>;
+    type Load<'a> = WorldLoad<'a, Set, NoMark, (Score,)>;
+
+    /// Dumps world with `serialize` and loads it back with `deserialize`.
+    /// Checks full and incremental dumps with tombstones and versioned components.
+    fn check_round_trip(
+        serialize: impl Fn(&Dump) -> Vec<u8>,
+        deserialize: impl Fn(&Load, &mut ActionEncoder, &[u8]),
+    ) {
+        let build = || {
+            let mut builder = World::builder().with_tombstones();
+            builder.register_component::<Bar>().version(1);
+            builder.register_component::<Baz>().version(3);
+            builder.build()
+        };
+
+        let mut world = build();
+        world.insert_resource(Score(42));
+
+        let foo = world.spawn((Foo,));
+        let bar = world.spawn((Bar(42),));
+        let baz = world.spawn((Baz("qwerty".into()),));
+        let foo_bar_baz = world.spawn((Foo, Bar(155), Baz("123456".into())));
+
+        // Entities without components from the set are not dumped.
+        world.spawn(());
+
+        let mut world2 = build();
+        let mut buffer = ActionBuffer::new();
+
+        let mut load = |world: &World, world2: &mut World, epoch: EpochId| {
+            let data = serialize(&WorldDump::new(world, (), epoch).with_resources());
+            let mut actions = buffer.encoder(world2);
+            deserialize(
+                &WorldLoad::new(world2, NoMark).with_resources(),
+                &mut actions,
+                &data,
+            );
+            buffer.execute(world2);
+        };
+
+        load(&world, &mut world2, EpochId::start());
+
+        assert_eq!(*world2.expect_resource::<Score>(), Score(42));
+        assert_eq!(
+            world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(foo),
+            Ok((Some(&Foo), None, None))
+        );
+        assert_eq!(
+            world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(bar),
+            Ok((None, Some(&Bar(42)), None))
+        );
+        assert_eq!(
+            world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(baz),
+            Ok((None, None, Some(&Baz("qwerty".into()))))
+        );
+        assert_eq!(
+            world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(foo_bar_baz),
+            Ok((Some(&Foo), Some(&Bar(155)), Some(&Baz("123456".into()))))
+        );
+
+        let epoch = world.epoch();
+        *world.query_one_mut::<&mut Bar>(foo_bar_baz).unwrap() = Bar(156);
+        world.despawn(baz).unwrap();
+
+        load(&world, &mut world2, epoch);
+
+        assert!(!world2.is_alive(baz));
+        assert_eq!(
+            world2.query_one_mut::<(Option<&Foo>, Option<&Bar>, Option<&Baz>)>(foo_bar_baz),
+            Ok((Some(&Foo), Some(&Bar(156)), Some(&Baz("123456".into()))))
+        );
+    }
+
+    #[test]
+    fn test_dump_json() {
+        check_round_trip(
+            |dump| serde_json::to_vec(dump).unwrap(),
+            |load, actions, data| {
+                load.load_serde(actions, &mut serde_json::Deserializer::from_slice(data))
+                    .unwrap()
+            },
+        );
+    }
+
+    #[test]
+    fn test_dump_bincode() {
+        check_round_trip(
+            |dump| bincode::serialize(dump).unwrap(),
+            |load, actions, data| {
+                // Rejects trailing bytes, so the whole dump must be read.
+                bincode::options()
+                    .with_fixint_encoding()
+                    .deserialize_seed(load.seed(actions), data)
+                    .unwrap()
+            },
+        );
+    }
+
+    #[derive(Component, Serialize, Deserialize)]
+    struct Pos0 {
+        x: u32,
+    }
+
+    #[derive(Component, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Pos {
+        x: i64,
+        y: i64,
+    }
+
+    type PosLoad<'a> = WorldLoad<'a, (Pos,), NoMark>;
+
+    /// Dumps `Pos0` with version 0 and `Pos` with version 1
+    /// and loads both into the world where `Pos0` is migrated to `Pos`.
+    fn check_migrations(
+        serialize_old: impl Fn(&WorldDump<(Pos0,), ()>) -> Vec<u8>,
+        serialize: impl Fn(&WorldDump<(Pos,), ()>) -> Vec<u8>,
+        deserialize: impl Fn(&PosLoad, &mut ActionEncoder, &[u8]),
+    ) {
+        let build = || {
+            let mut builder = World::builder();
+            builder
+                .register_component::<Pos>()
+                .version(1)
+                .migration_serde(0, |old: Pos0| Pos {
+                    x: i64::from(old.x),
+                    y: -1,
+                });
+            builder.build()
+        };
+
+        let mut world0 = World::new();
+        let a = world0.spawn((Pos0 { x: 1 },));
+        let old = serialize_old(&WorldDump::new(&world0, (), EpochId::start()));
+
+        let mut world1 = build();
+        world1.spawn(());
+        let b = world1.spawn((Pos { x: 2, y: 3 },));
+        let current = serialize(&WorldDump::new(&world1, (), EpochId::start()));
+
+        let mut world = build();
+        let mut buffer = ActionBuffer::new();
+        for data in [&old, &current] {
+            let mut actions = buffer.encoder(&world);
+            deserialize(&WorldLoad::new(&world, NoMark), &mut actions, data);
+            buffer.execute(&mut world);
+        }
+
+        assert_eq!(world.query_one_mut::<&Pos>(a), Ok(&Pos { x: 1, y: -1 }));
+        assert_eq!(world.query_one_mut::<&Pos>(b), Ok(&Pos { x: 2, y: 3 }));
+    }
+
+    #[test]
+    fn test_migrations_json() {
+        check_migrations(
+            |dump| serde_json::to_vec(dump).unwrap(),
+            |dump| serde_json::to_vec(dump).unwrap(),
+            |load, actions, data| {
+                load.load_serde(actions, &mut serde_json::Deserializer::from_slice(data))
+                    .unwrap()
+            },
+        );
+    }
+
+    #[test]
+    fn test_migrations_bincode() {
+        check_migrations(
+            |dump| bincode::serialize(dump).unwrap(),
+            |dump| bincode::serialize(dump).unwrap(),
+            |load, actions, data| {
+                bincode::options()
+                    .with_fixint_encoding()
+                    .deserialize_seed(load.seed(actions), data)
+                    .unwrap()
+            },
+        );
+    }
+
+    /// Checks that named dump round-trips through `serialize` and `deserialize`
+    /// and unknown components are preserved.
+    fn check_named_round_trip(
+        serialize: impl Fn(&NamedDump) -> Vec<u8>,
+        deserialize: impl Fn(&[u8]) -> NamedDump,
+    ) {
+        let mut world = World::new();
+        let a = world.spawn((Bar(1), Baz("a".into())));
+        let b = world.spawn((Baz("b".into()),));
+
+        let mut full = NamedRegistry::new();
+        full.register(
+            |bar: &Bar, out| out.extend(bar.0.to_le_bytes()),
+            |bytes| Some(Bar(u32::from_le_bytes(bytes.try_into().ok()?))),
+        );
+        full.register_as(
+            "baz",
+            |baz: &Baz, out| out.extend(baz.0.as_bytes()),
+            |bytes| Some(Baz(String::from_utf8(bytes.to_vec()).ok()?)),
+        );
+
+        let dump = full.dump(&world);
+        let data = serialize(&dump);
+        assert_eq!(deserialize(&data), dump);
+
+        // Registry without `Baz` preserves it.
+        let mut partial = NamedRegistry::new();
+        partial.register(
+            |bar: &Bar, out| out.extend(bar.0.to_le_bytes()),
+            |bytes| Some(Bar(u32::from_le_bytes(bytes.try_into().ok()?))),
+        );
+
+        let mut world2 = World::new();
+        partial
+            .load(&mut world2, &deserialize(&data), Unknown::Preserve)
+            .unwrap();
+        assert_eq!(world2.query_one_mut::<&Bar>(a), Ok(&Bar(1)));
+        assert_eq!(
+            world2.query_one_mut::<&Preserved>(b).unwrap().components[0].key,
+            "baz"
+        );
+
+        // Preserved components are written back and loaded by full registry.
+        let data = serialize(&partial.dump(&world2));
+
+        let mut world3 = World::new();
+        full.load(&mut world3, &deserialize(&data), Unknown::Skip)
+            .unwrap();
+        assert_eq!(
+            world3.query_one_mut::<(&Bar, &Baz)>(a),
+            Ok((&Bar(1), &Baz("a".into())))
+        );
+        assert_eq!(world3.query_one_mut::<&Baz>(b), Ok(&Baz("b".into())));
+    }
+
+    #[test]
+    fn test_named_dump_json() {
+        check_named_round_trip(
+            |dump| serde_json::to_vec(dump).unwrap(),
+            |data| serde_json::from_slice(data).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_named_dump_bincode() {
+        check_named_round_trip(
+            |dump| bincode::serialize(dump).unwrap(),
+            |data| bincode::deserialize(data).unwrap(),
+        );
+    }
+}
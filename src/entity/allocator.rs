@@ -160,6 +160,18 @@ impl IdAllocator {
         None
     }
 
+    /// Returns current and pre-allocated ranges.
+    pub fn ranges(&self) -> (IdRange, IdRange) {
+        (self.current, self.next)
+    }
+
+    /// Replaces current and pre-allocated ranges.
+    /// Range allocator is not affected.
+    pub fn set_ranges(&mut self, current: IdRange, next: IdRange) {
+        self.current = current;
+        self.next = next;
+    }
+
     /// Calls provided closure with reserved IDs.
     /// `count` must be larger than all `idx` values passed to `reserve` that
     /// returned `Some`
@@ -8,11 +8,12 @@ use hashbrown::{hash_map::Entry, HashMap};
 use crate::world::NoSuchEntity;
 
 use super::{
-    allocator::{IdAllocator, IdRangeAllocator},
+    allocator::{IdAllocator, IdRange, IdRangeAllocator},
     EntityId,
 };
 
 /// Stores entity information in the World
+#[derive(Clone)]
 struct EntityData {
     /// Archetype index.
     archetype: u32,
@@ -44,6 +45,13 @@ impl fmt::Debug for EntitySet {
     }
 }
 
+/// Copy of entity locations and id allocator state.
+pub(crate) struct EntitySetSnapshot {
+    map: HashMap<u64, EntityData>,
+    current: IdRange,
+    next: IdRange,
+}
+
 impl EntitySet {
     pub fn new() -> Self {
         EntitySet {
@@ -146,7 +154,7 @@ impl EntitySet {
                 let bits = id.bits();
                 let reserved = self.reserve_counter.load(Ordering::Acquire);
                 let Some(idx) = self.id_allocator.reserved(bits) else {
-                    return None
+                    return None;
                 };
                 if idx >= reserved {
                     return None;
@@ -161,4 +169,30 @@ impl EntitySet {
     pub fn reserve_space(&mut self, additional: usize) {
         self.map.reserve(additional);
     }
+
+    /// Copies entity locations and id allocator state.
+    /// Reserved ids that are not yet spawned are not captured.
+    pub fn snapshot(&self) -> EntitySetSnapshot {
+        let (current, next) = self.id_allocator.ranges();
+        EntitySetSnapshot {
+            map: self.map.clone(),
+            current,
+            next,
+        }
+    }
+
+    /// Restores entity locations and id allocator state.
+    /// Reserved ids that are not yet spawned are discarded.
+    pub fn restore(&mut self, snapshot: &EntitySetSnapshot) {
+        self.map.clone_from(&snapshot.map);
+        self.id_allocator
+            .set_ranges(snapshot.current, snapshot.next);
+        *self.reserve_counter.get_mut() = 0;
+    }
+
+    /// Removes all entities.
+    /// Id allocator state is not affected.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
 }
@@ -3,7 +3,7 @@
 //! Strong, weak and raw ids.
 
 pub use self::allocator::{IdRange, IdRangeAllocator, OneRangeAllocator};
-pub(crate) use self::entities::EntitySet;
+pub(crate) use self::entities::{EntitySet, EntitySetSnapshot};
 pub use self::id::EntityId;
 
 mod allocator;
@@ -53,6 +53,12 @@ impl EpochCounter {
         *value += 1;
         EpochId { value: *value }
     }
+
+    /// Resets counter to specified epoch.
+    /// Used to rewind world to snapshot.
+    pub(crate) fn reset_mut(&mut self, epoch: EpochId) {
+        *self.value.get_mut() = epoch.value;
+    }
 }
 
 /// Epoch identifier.
@@ -105,7 +105,7 @@ macro_rules! for_tuple {
     };
 }
 
-#[cfg(feature = "alkahest")]
+#[cfg(any(feature = "alkahest", feature = "serde"))]
 macro_rules! for_tuple_2 {
     ($macro:ident) => {
         for_tuple_2!($macro for
@@ -367,6 +367,7 @@ pub mod executor;
 pub mod prelude;
 pub mod query;
 pub mod relation;
+pub mod scene;
 pub mod system;
 pub mod world;
 
@@ -6,9 +6,15 @@ pub use crate::{
     component::Component,
     entity::EntityId,
     query::{Alt, Entities, Modified, PhantomQuery, Query, QueryIter},
-    relation::{ChildOf, Related, Relates, RelatesExclusive, RelatesTo, Relation},
+    relation::{
+        ChildOf, Related, RelatedDyn, Relates, RelatesDyn, RelatesExclusive, RelatesJoin,
+        RelatesTo, Relation, Up,
+    },
     scheduler::Scheduler,
     system::{IntoSystem, Res, ResMut, ResMutNoSend, ResNoSync, State, System},
     task::{task_system, task_world, Task},
-    world::{EntityError, MissingComponents, NoSuchEntity, QueryOneError, QueryRef, World},
+    world::{
+        AddRelationError, EntityError, MissingComponents, NoSuchEntity, QueryOneError, QueryRef,
+        World,
+    },
 };
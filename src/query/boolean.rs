@@ -256,6 +256,12 @@ macro_rules! impl_boolean {
                 $($a.access_archetype(archetype, f);)+
             }
 
+            #[inline(always)]
+            fn include_disabled(&self) -> bool {
+                let ($($a,)+) = &self.tuple;
+                $( $a.include_disabled() )||+
+            }
+
             #[inline(always)]
             fn visit_archetype(&self, archetype: &Archetype) -> bool {
                 let ($($a,)+) = &self.tuple;
@@ -0,0 +1,116 @@
+use core::any::TypeId;
+
+use crate::{
+    archetype::Archetype,
+    entity::EntityId,
+    epoch::EpochId,
+    system::{QueryArg, QueryArgCache, QueryArgGet},
+    world::World,
+};
+
+use super::{Access, DefaultQuery, ImmutableQuery, IntoQuery, Query, UnitFetch};
+
+/// Filter that makes query visit disabled entities.
+///
+/// Entities with [`Disabled`] component are skipped by queries
+/// unless this filter is added.
+///
+/// # Example
+///
+/// ```
+/// # use edict::{query::IncludeDisabled, world::World, ExampleComponent};
+/// let mut world = World::new();
+/// let entity = world.spawn((ExampleComponent,));
+/// world.set_enabled(entity, false).unwrap();
+///
+/// assert_eq!(world.query::<&ExampleComponent>().iter().count(), 0);
+/// assert_eq!(world.query::<(&ExampleComponent, IncludeDisabled)>().iter().count(), 1);
+/// ```
+///
+/// [`Disabled`]: crate::world::Disabled
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IncludeDisabled;
+
+impl IntoQuery for IncludeDisabled {
+    type Query = Self;
+
+    #[inline]
+    fn into_query(self) -> Self {
+        self
+    }
+}
+
+impl DefaultQuery for IncludeDisabled {
+    #[inline]
+    fn default_query() -> Self {
+        IncludeDisabled
+    }
+}
+
+unsafe impl Query for IncludeDisabled {
+    type Item<'a> = ();
+    type Fetch<'a> = UnitFetch;
+
+    #[inline]
+    fn access(&self, _ty: TypeId) -> Option<Access> {
+        None
+    }
+
+    #[inline]
+    fn visit_archetype(&self, _archetype: &Archetype) -> bool {
+        true
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, _f: &dyn Fn(TypeId, Access)) {}
+
+    #[inline]
+    unsafe fn fetch(&mut self, _archetype: &Archetype, _epoch: EpochId) -> UnitFetch {
+        UnitFetch::new()
+    }
+
+    #[inline]
+    fn reserved_entity_item<'a>(&self, _id: EntityId) -> Option<()>
+    where
+        (): 'a,
+    {
+        Some(())
+    }
+
+    #[inline]
+    fn include_disabled(&self) -> bool {
+        true
+    }
+}
+
+unsafe impl ImmutableQuery for IncludeDisabled {}
+
+impl<'a> QueryArgGet<'a> for IncludeDisabled {
+    type Arg = IncludeDisabled;
+    type Query = IncludeDisabled;
+
+    #[inline]
+    fn get(&mut self, _world: &World) -> IncludeDisabled {
+        IncludeDisabled
+    }
+}
+
+impl QueryArgCache for IncludeDisabled {
+    fn new() -> Self {
+        IncludeDisabled
+    }
+
+    #[inline]
+    fn visit_archetype(&self, _archetype: &Archetype) -> bool {
+        true
+    }
+
+    #[inline]
+    fn access_component(&self, _id: TypeId) -> Option<Access> {
+        None
+    }
+}
+
+impl QueryArg for IncludeDisabled {
+    type Cache = IncludeDisabled;
+}
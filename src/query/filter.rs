@@ -1,6 +1,6 @@
 use core::{any::TypeId, marker::PhantomData};
 
-use crate::{archetype::Archetype, epoch::EpochId};
+use crate::{archetype::Archetype, entity::EntityId, epoch::EpochId, world::Disabled};
 
 use super::{
     fetch::UnitFetch, merge_access, Access, Fetch, ImmutablePhantomQuery, ImmutableQuery,
@@ -104,6 +104,8 @@ where
 
 /// Combines query and filter.
 /// Skips using both and yields using query.
+///
+/// Skips disabled entities unless query or filter includes them.
 #[derive(Clone, Copy, Debug)]
 pub struct FilteredQuery<F, Q> {
     pub(crate) filter: F,
@@ -141,7 +143,9 @@ where
 
     #[inline]
     fn visit_archetype(&self, archetype: &Archetype) -> bool {
-        self.filter.visit_archetype(archetype) && self.query.visit_archetype(archetype)
+        (self.include_disabled() || !archetype.has_component(TypeId::of::<Disabled>()))
+            && self.filter.visit_archetype(archetype)
+            && self.query.visit_archetype(archetype)
     }
 
     #[inline]
@@ -158,6 +162,17 @@ where
             query: self.query.fetch(archetype, index),
         }
     }
+
+    #[inline]
+    fn reserved_entity_item<'a>(&self, id: EntityId) -> Option<Q::Item<'a>> {
+        self.filter.reserved_entity_item(id)?;
+        self.query.reserved_entity_item(id)
+    }
+
+    #[inline]
+    fn include_disabled(&self) -> bool {
+        self.filter.include_disabled() || self.query.include_disabled()
+    }
 }
 
 unsafe impl<F, Q> ImmutableQuery for FilteredQuery<F, Q>
@@ -254,6 +269,8 @@ where
 
 phantom_newtype! {
     /// [`Filter`] that allows only archetypes with specified component.
+    ///
+    /// `With<Disabled>` includes disabled entities.
     pub struct With<T>
 }
 
@@ -291,6 +308,11 @@ where
     unsafe fn fetch(_: &Archetype, _: EpochId) -> UnitFetch {
         UnitFetch::new()
     }
+
+    #[inline]
+    fn include_disabled() -> bool {
+        TypeId::of::<T>() == TypeId::of::<Disabled>()
+    }
 }
 
 unsafe impl<T> ImmutablePhantomQuery for With<T> where T: 'static {}
@@ -8,7 +8,7 @@
 
 use core::any::TypeId;
 
-use crate::{archetype::Archetype, entity::EntityId, epoch::EpochId};
+use crate::{archetype::Archetype, entity::EntityId, epoch::EpochId, world::Disabled};
 
 pub use self::{
     alt::{Alt, FetchAlt},
@@ -22,6 +22,7 @@ pub use self::{
         FetchBorrowOneWrite, QueryBorrowAll, QueryBorrowAny, QueryBorrowOne,
     },
     copied::{copied, Copied, FetchCopied},
+    disabled::IncludeDisabled,
     entities::{Entities, EntitiesFetch, EntitiesQuery},
     fetch::{Fetch, UnitFetch, VerifyFetch},
     filter::{FilteredFetch, FilteredQuery, Not, With, Without},
@@ -30,6 +31,7 @@ pub use self::{
         Modified, ModifiedFetchAlt, ModifiedFetchCopied, ModifiedFetchRead, ModifiedFetchWith,
         ModifiedFetchWrite,
     },
+    option::FetchOption,
     phantom::{ImmutablePhantomQuery, PhantomQuery},
     read::{read, FetchRead, Read},
     with_epoch::{EpochOf, FetchEpoch},
@@ -41,6 +43,7 @@ mod any_of;
 mod boolean;
 mod borrow;
 mod copied;
+mod disabled;
 mod entities;
 mod fetch;
 mod filter;
@@ -125,6 +128,18 @@ pub unsafe trait Query: IntoQuery<Query = Self> {
         drop(id);
         None
     }
+
+    /// Returns `true` if query visits disabled entities.
+    ///
+    /// Entities with [`Disabled`] component are skipped
+    /// unless any part of the query returns `true`.
+    ///
+    /// [`Disabled`]: crate::world::Disabled
+    #[must_use]
+    #[inline]
+    fn include_disabled(&self) -> bool {
+        false
+    }
 }
 
 /// Wraps mutable reference to query and implement query for it.
@@ -183,6 +198,10 @@ where
     unsafe fn fetch<'a>(&mut self, archetype: &'a Archetype, epoch: EpochId) -> Self::Fetch<'a> {
         self.query.fetch(archetype, epoch)
     }
+
+    fn include_disabled(&self) -> bool {
+        self.query.include_disabled()
+    }
 }
 
 unsafe impl<T> ImmutableQuery for MutQuery<'_, T> where T: ImmutableQuery {}
@@ -209,6 +228,14 @@ pub const fn merge_access(lhs: Option<Access>, rhs: Option<Access>) -> Option<Ac
     }
 }
 
+/// Checks if query visits archetype.
+/// Archetypes of disabled entities are skipped unless query includes them.
+#[inline]
+pub(crate) fn visit_enabled<Q: Query>(query: &Q, archetype: &Archetype) -> bool {
+    (query.include_disabled() || !archetype.has_component(TypeId::of::<Disabled>()))
+        && query.visit_archetype(archetype)
+}
+
 /// Helps to assert that type implements [`Query`] in compile time.
 const fn assert_query<Q: Query>() {}
 
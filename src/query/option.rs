@@ -1,6 +1,6 @@
 use core::any::TypeId;
 
-use crate::{archetype::Archetype, epoch::EpochId};
+use crate::{archetype::Archetype, entity::EntityId, epoch::EpochId};
 
 use super::{Access, Fetch, ImmutablePhantomQuery, PhantomQuery};
 
@@ -52,12 +52,75 @@ where
     }
 }
 
+/// [`Fetch`] type for the `Option<T>` query.
+///
+/// Yields `None` for items skipped by the inner fetch
+/// instead of skipping them.
+pub struct FetchOption<T> {
+    fetch: Option<T>,
+    visit_chunk: bool,
+    visit_item: bool,
+}
+
+unsafe impl<'a, T> Fetch<'a> for FetchOption<T>
+where
+    T: Fetch<'a>,
+{
+    type Item = Option<T::Item>;
+
+    /// Returns `Fetch` value that must not be used.
+    fn dangling() -> Self {
+        FetchOption {
+            fetch: None,
+            visit_chunk: false,
+            visit_item: false,
+        }
+    }
+
+    /// Checks if chunk with specified index must be visited or skipped.
+    #[inline]
+    unsafe fn visit_chunk(&mut self, chunk_idx: usize) -> bool {
+        if let Some(fetch) = &mut self.fetch {
+            self.visit_chunk = fetch.visit_chunk(chunk_idx);
+        }
+        true
+    }
+
+    /// Notifies this fetch that it visits a chunk.
+    #[inline]
+    unsafe fn touch_chunk(&mut self, chunk_idx: usize) {
+        if let Some(fetch) = &mut self.fetch {
+            if self.visit_chunk {
+                fetch.touch_chunk(chunk_idx);
+            }
+        }
+    }
+
+    /// Checks if item with specified index must be visited or skipped.
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        self.visit_item = match &mut self.fetch {
+            Some(fetch) if self.visit_chunk => fetch.visit_item(idx),
+            _ => false,
+        };
+        true
+    }
+
+    /// Returns fetched item at specified index.
+    unsafe fn get_item(&mut self, idx: usize) -> Option<T::Item> {
+        match &mut self.fetch {
+            Some(fetch) if self.visit_item => Some(fetch.get_item(idx)),
+            _ => None,
+        }
+    }
+}
+
 unsafe impl<T> PhantomQuery for Option<T>
 where
     T: PhantomQuery,
 {
     type Item<'a> = Option<T::Item<'a>>;
-    type Fetch<'a> = Option<T::Fetch<'a>>;
+    type Fetch<'a> = FetchOption<T::Fetch<'a>>;
 
     #[inline]
     fn access(ty: TypeId) -> Option<Access> {
@@ -77,13 +140,29 @@ where
     }
 
     #[inline]
-    unsafe fn fetch<'a>(archetype: &'a Archetype, epoch: EpochId) -> Option<T::Fetch<'a>> {
-        if !T::visit_archetype(archetype) {
-            None
-        } else {
+    unsafe fn fetch<'a>(archetype: &'a Archetype, epoch: EpochId) -> FetchOption<T::Fetch<'a>> {
+        let fetch = if T::visit_archetype(archetype) {
             Some(T::fetch(archetype, epoch))
+        } else {
+            None
+        };
+
+        FetchOption {
+            fetch,
+            visit_chunk: false,
+            visit_item: false,
         }
     }
+
+    #[inline]
+    fn reserved_entity_item<'a>(id: EntityId) -> Option<Option<T::Item<'a>>> {
+        Some(T::reserved_entity_item(id))
+    }
+
+    #[inline]
+    fn include_disabled() -> bool {
+        T::include_disabled()
+    }
 }
 
 unsafe impl<T> ImmutablePhantomQuery for Option<T> where T: ImmutablePhantomQuery {}
@@ -59,6 +59,13 @@ pub unsafe trait PhantomQuery: IntoQuery<Query = PhantomData<fn() -> Self>> {
         drop(id);
         None
     }
+
+    /// Returns `true` if query visits disabled entities.
+    #[must_use]
+    #[inline]
+    fn include_disabled() -> bool {
+        false
+    }
 }
 
 impl<Q> IntoQuery for PhantomData<fn() -> Q>
@@ -126,6 +133,11 @@ where
     fn reserved_entity_item<'a>(&self, id: EntityId) -> Option<Self::Item<'a>> {
         <Q as PhantomQuery>::reserved_entity_item(id)
     }
+
+    #[inline]
+    fn include_disabled(&self) -> bool {
+        <Q as PhantomQuery>::include_disabled()
+    }
 }
 
 /// Phantom counterpart of [`ImmutableQuery`] type alias.
@@ -1,6 +1,6 @@
 use core::{any::TypeId, marker::PhantomData, ptr::NonNull};
 
-use crate::{archetype::Archetype, epoch::EpochId};
+use crate::{archetype::Archetype, epoch::EpochId, world::Instance};
 
 use super::{assert_immutable_query, phantom::PhantomQuery, Access, Fetch, ImmutablePhantomQuery};
 
@@ -8,12 +8,15 @@ use super::{assert_immutable_query, phantom::PhantomQuery, Access, Fetch, Immuta
 
 pub struct FetchRead<'a, T> {
     ptr: NonNull<T>,
+
+    /// Instances column if archetype has no `T` and values are inherited from prefabs.
+    instances: Option<NonNull<Instance>>,
     marker: PhantomData<&'a [T]>,
 }
 
 unsafe impl<'a, T> Fetch<'a> for FetchRead<'a, T>
 where
-    T: Sync + 'a,
+    T: Sync + 'static,
 {
     type Item = &'a T;
 
@@ -21,13 +24,35 @@ where
     fn dangling() -> Self {
         FetchRead {
             ptr: NonNull::dangling(),
+            instances: None,
             marker: PhantomData,
         }
     }
 
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        match self.instances {
+            None => true,
+            Some(instances) => {
+                let instance = &*instances.as_ptr().add(idx);
+                instance.data().get_ptr(TypeId::of::<T>()).is_some()
+            }
+        }
+    }
+
     #[inline]
     unsafe fn get_item(&mut self, idx: usize) -> &'a T {
-        &*self.ptr.as_ptr().add(idx)
+        match self.instances {
+            None => &*self.ptr.as_ptr().add(idx),
+            Some(instances) => {
+                let instance = &*instances.as_ptr().add(idx);
+                let ptr = instance
+                    .data()
+                    .get_ptr(TypeId::of::<T>())
+                    .unwrap_unchecked();
+                &*ptr.cast::<T>().as_ptr()
+            }
+        }
     }
 }
 
@@ -40,7 +65,7 @@ where
 
     #[inline]
     fn access(ty: TypeId) -> Option<Access> {
-        if ty == TypeId::of::<T>() {
+        if ty == TypeId::of::<T>() || ty == TypeId::of::<Instance>() {
             Some(Access::Read)
         } else {
             None
@@ -50,23 +75,41 @@ where
     #[inline]
     fn visit_archetype(archetype: &Archetype) -> bool {
         archetype.has_component(TypeId::of::<T>())
+            || archetype.has_component(TypeId::of::<Instance>())
     }
 
     #[inline]
-    unsafe fn access_archetype(_archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
-        f(TypeId::of::<T>(), Access::Read)
+    unsafe fn access_archetype(archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        if archetype.has_component(TypeId::of::<T>()) {
+            f(TypeId::of::<T>(), Access::Read)
+        } else {
+            f(TypeId::of::<Instance>(), Access::Read)
+        }
     }
 
     #[inline]
     unsafe fn fetch<'a>(archetype: &'a Archetype, _epoch: EpochId) -> FetchRead<'a, T> {
-        let component = archetype.component(TypeId::of::<T>()).unwrap_unchecked();
-        debug_assert_eq!(component.id(), TypeId::of::<T>());
-
-        let data = component.data();
-
-        FetchRead {
-            ptr: data.ptr.cast(),
-            marker: PhantomData,
+        match archetype.component(TypeId::of::<T>()) {
+            Some(component) => {
+                debug_assert_eq!(component.id(), TypeId::of::<T>());
+
+                FetchRead {
+                    ptr: component.data().ptr.cast(),
+                    instances: None,
+                    marker: PhantomData,
+                }
+            }
+            None => {
+                let component = archetype
+                    .component(TypeId::of::<Instance>())
+                    .unwrap_unchecked();
+
+                FetchRead {
+                    ptr: NonNull::dangling(),
+                    instances: Some(component.data().ptr.cast()),
+                    marker: PhantomData,
+                }
+            }
         }
     }
 }
@@ -77,7 +120,8 @@ unsafe impl<T> ImmutablePhantomQuery for &T where T: Sync + 'static {}
 pub type Read<T> = PhantomData<fn() -> &'static T>;
 
 /// Returns query that yields reference to specified component
-/// for each entity that has that component.
+/// for each entity that has that component
+/// or inherits it from a prefab.
 ///
 /// Skips entities that don't have the component.
 pub fn read<T>() -> Read<T>
@@ -53,6 +53,10 @@ macro_rules! impl_fetch {
         }
 
         unsafe impl ImmutableQuery for () {}
+
+        impl DefaultQuery for () {
+            fn default_query() {}
+        }
     };
 
     ($($a:ident)+) => {
@@ -133,6 +137,12 @@ macro_rules! impl_fetch {
                 $( let $a = $a.reserved_entity_item(id)?; )+
                 Some(($($a),+))
             }
+
+            #[inline]
+            fn include_disabled(&self) -> bool {
+                let ($($a,)+) = self;
+                $( <$a as Query>::include_disabled($a) )||+
+            }
         }
 
         unsafe impl<$($a),+> ImmutableQuery for ($($a,)+) where $($a: ImmutableQuery,)+ {}
@@ -3,6 +3,8 @@ use super::Relation;
 /// Child -> Parent relation.
 /// Children can have only one parent. So this relation is exclusive.
 /// Children should be despawned when parent is despawned. So this relation is owned.
+/// Entity cannot be its own ancestor. So this relation is acyclic.
+/// Siblings order matters for scene graphs and UI trees. So this relation is ordered.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ChildOf;
 
@@ -10,4 +12,6 @@ impl Relation for ChildOf {
     const EXCLUSIVE: bool = true;
     const OWNED: bool = true;
     const SYMMETRIC: bool = false;
+    const ACYCLIC: bool = true;
+    const ORDERED: bool = true;
 }
@@ -0,0 +1,211 @@
+//! Relations with kind defined at runtime.
+//!
+//! Kind of dynamic relation is an [`EntityId`].
+//! It may be an entity that describes the relation
+//! or just an id allocated to distinguish one kind from another.
+
+use core::{any::TypeId, ptr::NonNull};
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    action::ActionEncoder,
+    component::{Component, ComponentInfo, ComponentRegistry, RelationClone},
+    entity::EntityId,
+    query::IncludeDisabled,
+};
+
+/// Pair of relation kind and entity on the other side of the relation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct DynPair {
+    pub kind: EntityId,
+    pub id: EntityId,
+}
+
+/// Removes pair from the vector, returns `true` if pair was found.
+fn remove_pair(pairs: &mut Vec<DynPair>, pair: DynPair) -> bool {
+    match pairs.iter().position(|p| *p == pair) {
+        None => false,
+        Some(idx) => {
+            pairs.swap_remove(idx);
+            true
+        }
+    }
+}
+
+/// Registers components of dynamic relations
+/// with their behavior for entity cloning.
+pub(crate) fn register_relation_dyn(registry: &mut ComponentRegistry) {
+    registry.get_or_register_with(TypeId::of::<DynOriginComponent>(), || {
+        ComponentInfo::of::<DynOriginComponent>()
+            .with_relation_clone(RelationClone::Origin(clone_origins))
+    });
+
+    registry.get_or_register_with(TypeId::of::<DynTargetComponent>(), || {
+        ComponentInfo::of::<DynTargetComponent>().with_relation_clone(RelationClone::Target)
+    });
+}
+
+/// Encodes actions to add dynamic relations of the origin component to the clone.
+/// Both kinds and targets are mapped.
+unsafe fn clone_origins(
+    ptr: NonNull<u8>,
+    id: EntityId,
+    map_target: &mut dyn FnMut(EntityId) -> Option<EntityId>,
+    encoder: &mut ActionEncoder,
+) {
+    let component = unsafe { ptr.cast::<DynOriginComponent>().as_ref() };
+    for &DynPair { kind, id: target } in &component.targets {
+        if let (Some(kind), Some(target)) = (map_target(kind), map_target(target)) {
+            encoder.add_relation_dyn(id, kind, target);
+        }
+    }
+}
+
+/// Component that is added to origin entity of dynamic relations.
+/// Stores kinds and targets.
+#[derive(Clone)]
+pub(crate) struct DynOriginComponent {
+    pub(crate) targets: Vec<DynPair>,
+}
+
+impl DynOriginComponent {
+    #[must_use]
+    pub(crate) fn new(kind: EntityId, target: EntityId) -> Self {
+        DynOriginComponent {
+            targets: vec![DynPair { kind, id: target }],
+        }
+    }
+
+    pub(crate) fn add(&mut self, kind: EntityId, target: EntityId) {
+        let pair = DynPair { kind, id: target };
+        if !self.targets.contains(&pair) {
+            self.targets.push(pair);
+        }
+    }
+
+    /// Removes relation from origin entity.
+    /// Returns `false` if relation is not found.
+    pub(crate) fn remove(
+        &mut self,
+        id: EntityId,
+        kind: EntityId,
+        target: EntityId,
+        mut encoder: ActionEncoder,
+    ) -> bool {
+        if !remove_pair(&mut self.targets, DynPair { kind, id: target }) {
+            return false;
+        }
+        if self.targets.is_empty() {
+            encoder.drop::<Self>(id);
+        }
+        true
+    }
+}
+
+impl Component for DynOriginComponent {
+    #[inline]
+    fn on_drop(&mut self, id: EntityId, mut encoder: ActionEncoder) {
+        for &DynPair { kind, id: target } in &self.targets {
+            encoder.closure_with_encoder(move |world, encoder| unsafe {
+                if let Ok((target_component, _)) =
+                    world.query_one_unchecked::<(&mut DynTargetComponent, IncludeDisabled)>(target)
+                {
+                    target_component.remove(target, kind, id, encoder);
+                }
+            });
+        }
+    }
+
+    #[inline]
+    fn on_replace(&mut self, _value: &Self, _id: EntityId, _encoder: ActionEncoder) -> bool {
+        unimplemented!("This method is not intended to be called");
+    }
+
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        for pair in &mut self.targets {
+            pair.kind = mapper(pair.kind);
+            pair.id = mapper(pair.id);
+        }
+    }
+
+    #[inline]
+    fn clone_fn() -> Option<fn(&Self) -> Self> {
+        Some(Self::clone)
+    }
+}
+
+/// Component that is added to target entity of dynamic relations.
+/// Stores kinds and origins.
+#[derive(Clone)]
+pub(crate) struct DynTargetComponent {
+    pub(crate) origins: Vec<DynPair>,
+}
+
+impl DynTargetComponent {
+    #[must_use]
+    pub(crate) fn new(kind: EntityId, origin: EntityId) -> Self {
+        DynTargetComponent {
+            origins: vec![DynPair { kind, id: origin }],
+        }
+    }
+
+    pub(crate) fn add(&mut self, kind: EntityId, origin: EntityId) {
+        let pair = DynPair { kind, id: origin };
+        if !self.origins.contains(&pair) {
+            self.origins.push(pair);
+        }
+    }
+
+    /// Removes relation from target entity.
+    /// Returns `false` if relation is not found.
+    pub(crate) fn remove(
+        &mut self,
+        id: EntityId,
+        kind: EntityId,
+        origin: EntityId,
+        mut encoder: ActionEncoder,
+    ) -> bool {
+        if !remove_pair(&mut self.origins, DynPair { kind, id: origin }) {
+            return false;
+        }
+        if self.origins.is_empty() {
+            encoder.drop::<Self>(id);
+        }
+        true
+    }
+}
+
+impl Component for DynTargetComponent {
+    #[inline]
+    fn on_drop(&mut self, id: EntityId, mut encoder: ActionEncoder) {
+        for &DynPair { kind, id: origin } in &self.origins {
+            encoder.closure_with_encoder(move |world, encoder| unsafe {
+                if let Ok((origin_component, _)) =
+                    world.query_one_unchecked::<(&mut DynOriginComponent, IncludeDisabled)>(origin)
+                {
+                    origin_component.remove(origin, kind, id, encoder);
+                }
+            });
+        }
+    }
+
+    #[inline]
+    fn on_replace(&mut self, _value: &Self, _id: EntityId, _encoder: ActionEncoder) -> bool {
+        unimplemented!("This method is not intended to be called");
+    }
+
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        for pair in &mut self.origins {
+            pair.kind = mapper(pair.kind);
+            pair.id = mapper(pair.id);
+        }
+    }
+
+    #[inline]
+    fn clone_fn() -> Option<fn(&Self) -> Self> {
+        Some(Self::clone)
+    }
+}
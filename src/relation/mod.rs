@@ -6,15 +6,16 @@
 //!
 //! [`Component`]: ../component/trait.Component.html
 
-use core::{marker::PhantomData, mem::ManuallyDrop};
+use core::{any::TypeId, marker::PhantomData, mem::ManuallyDrop, ptr::NonNull};
 
 use alloc::{vec, vec::Vec};
 
 use crate::{
     action::ActionEncoder,
     borrow_dyn_trait,
-    component::{Component, ComponentBorrow},
+    component::{Component, ComponentBorrow, ComponentInfo, ComponentRegistry, RelationClone},
     entity::EntityId,
+    query::IncludeDisabled,
 };
 
 pub use edict_proc::Relation;
@@ -22,15 +23,18 @@ pub use edict_proc::Relation;
 pub use self::{
     child_of::ChildOf,
     query::{
-        related, related_by, relates, relates_to, FetchFilterRelatedBy, FetchRelated,
-        FetchRelatesExclusiveRead, FetchRelatesExclusiveWrite, FetchRelatesRead,
-        FetchRelatesToRead, FetchRelatesToWrite, FetchRelatesWrite, FilterFetchRelationTo,
-        FilterRelated, FilterRelatedBy, FilterRelates, FilterRelatesTo, Related, Relates,
-        RelatesExclusive, RelatesReadIter, RelatesTo, RelatesWriteIter,
+        related, related_by, related_dyn, relates, relates_dyn, relates_to, DynRelationIter,
+        FetchFilterRelatedBy, FetchRelated, FetchRelatedDyn, FetchRelatesDyn,
+        FetchRelatesExclusiveRead, FetchRelatesExclusiveWrite, FetchRelatesJoinRead,
+        FetchRelatesRead, FetchRelatesToRead, FetchRelatesToWrite, FetchRelatesWrite, FetchUp,
+        FilterFetchRelationTo, FilterRelated, FilterRelatedBy, FilterRelates, FilterRelatesTo,
+        Related, RelatedDyn, Relates, RelatesDyn, RelatesExclusive, RelatesJoin, RelatesJoinIter,
+        RelatesReadIter, RelatesTo, RelatesWriteIter, Up,
     },
 };
 
 mod child_of;
+pub(crate) mod dynamic;
 mod query;
 
 /// Trait that must be implemented for relations.
@@ -46,6 +50,25 @@ pub trait Relation: Send + Sync + Copy + 'static {
     /// This means that when last target is dropped, entity is also dropped, not just relation.
     const OWNED: bool = false;
 
+    /// If `true` then relation graph is kept free of cycles.
+    /// Adding relation that would close a cycle fails with [`AddRelationError::Cycle`].
+    /// This includes relation from entity to itself.
+    /// Has no effect for symmetric relations.
+    ///
+    /// [`AddRelationError::Cycle`]: edict::world::AddRelationError::Cycle
+    const ACYCLIC: bool = false;
+
+    /// If `true` then order of relations is stable.
+    /// Removing relation does not reorder remaining relations on origin and target.
+    /// Target keeps its origins in the order relations were added,
+    /// which can be changed with [`World::add_relation_at`] and [`World::move_relation`].
+    ///
+    /// Removal of ordered relation takes linear time.
+    ///
+    /// [`World::add_relation_at`]: edict::world::World::add_relation_at
+    /// [`World::move_relation`]: edict::world::World::move_relation
+    const ORDERED: bool = false;
+
     /// Returns name of the relation type.
     #[inline]
     #[must_use]
@@ -98,6 +121,51 @@ pub trait Relation: Send + Sync + Copy + 'static {
     }
 }
 
+/// Registers components of relation `R`
+/// with their behavior for entity cloning.
+pub(crate) fn register_relation<R>(registry: &mut ComponentRegistry)
+where
+    R: Relation,
+{
+    registry.get_or_register_with(TypeId::of::<OriginComponent<R>>(), || {
+        ComponentInfo::of::<OriginComponent<R>>()
+            .with_relation_clone(RelationClone::Origin(clone_origins::<R>))
+    });
+
+    if !R::SYMMETRIC {
+        registry.get_or_register_with(TypeId::of::<TargetComponent<R>>(), || {
+            ComponentInfo::of::<TargetComponent<R>>().with_relation_clone(RelationClone::Target)
+        });
+    }
+}
+
+/// Encodes actions to add relations of the origin component to the clone.
+unsafe fn clone_origins<R>(
+    ptr: NonNull<u8>,
+    id: EntityId,
+    map_target: &mut dyn FnMut(EntityId) -> Option<EntityId>,
+    encoder: &mut ActionEncoder,
+) where
+    R: Relation,
+{
+    let component = unsafe { ptr.cast::<OriginComponent<R>>().as_ref() };
+    for origin in component.origins() {
+        if let Some(target) = map_target(origin.target) {
+            encoder.add_relation(id, origin.relation, target);
+        }
+    }
+}
+
+/// Removes element at `idx` preserving order if relation is ordered.
+fn remove_at<R: Relation, T>(vec: &mut Vec<T>, idx: usize) -> T {
+    if R::ORDERED {
+        vec.remove(idx)
+    } else {
+        vec.swap_remove(idx)
+    }
+}
+
+#[derive(Clone, Copy)]
 pub(crate) struct Origin<R> {
     pub target: EntityId,
     pub relation: R,
@@ -120,6 +188,22 @@ where
     }
 }
 
+impl<R> Clone for OriginComponent<R>
+where
+    R: Relation,
+{
+    fn clone(&self) -> Self {
+        match R::EXCLUSIVE {
+            false => OriginComponent {
+                non_exclusive: unsafe { self.non_exclusive.clone() },
+            },
+            true => OriginComponent {
+                exclusive: unsafe { self.exclusive },
+            },
+        }
+    }
+}
+
 impl<R> OriginComponent<R>
 where
     R: Relation,
@@ -136,21 +220,32 @@ where
         }
     }
 
-    pub fn add(&mut self, id: EntityId, target: EntityId, relation: R, encoder: ActionEncoder) {
+    /// Adds relation to the target or replaces existing one.
+    /// Returns `false` if relation with the target is replaced.
+    pub fn add(
+        &mut self,
+        id: EntityId,
+        target: EntityId,
+        relation: R,
+        encoder: ActionEncoder,
+    ) -> bool {
         match R::EXCLUSIVE {
             false => {
                 let origins = unsafe { &mut *self.non_exclusive };
                 for idx in 0..origins.len() {
                     if origins[idx].target == target {
                         Self::set_one(&mut origins[idx], Origin { target, relation }, id, encoder);
-                        return;
+                        return false;
                     }
                 }
                 origins.push(Origin { target, relation });
+                true
             }
             true => {
                 let old_origin = unsafe { &mut *self.exclusive };
+                let new_target = old_origin.target != target;
                 Self::set_one(old_origin, Origin { target, relation }, id, encoder);
+                new_target
             }
         }
     }
@@ -166,7 +261,7 @@ where
                 let origins = unsafe { &mut *self.non_exclusive };
                 for idx in 0..origins.len() {
                     if origins[idx].target == target {
-                        let origin = origins.swap_remove(idx);
+                        let origin = remove_at::<R, _>(origins, idx);
                         if origins.is_empty() {
                             encoder.drop::<Self>(id);
                         }
@@ -221,7 +316,7 @@ where
                 origins[idx]
                     .relation
                     .on_drop(id, target, encoder.reborrow());
-                origins.swap_remove(idx);
+                remove_at::<R, _>(origins, idx);
                 break;
             }
         }
@@ -283,8 +378,10 @@ where
                 } else {
                     let target = origin.target;
                     encoder.closure_with_encoder(move |world, encoder| {
-                        if let Ok(mut target_component) = world.query_one::<&mut Self>(target) {
-                            if let Some(target_component) = target_component.get() {
+                        if let Ok(mut target_component) =
+                            world.query_one::<(&mut Self, IncludeDisabled)>(target)
+                        {
+                            if let Some((target_component, _)) = target_component.get() {
                                 target_component.on_non_exclusive_target_drop(target, id, encoder);
                             }
                         }
@@ -294,9 +391,10 @@ where
         } else {
             let target = origin.target;
             encoder.closure_with_encoder(move |world, encoder| {
-                if let Ok(mut target_component) = world.query_one::<&mut TargetComponent<R>>(target)
+                if let Ok(mut target_component) =
+                    world.query_one::<(&mut TargetComponent<R>, IncludeDisabled)>(target)
                 {
-                    if let Some(target_component) = target_component.get() {
+                    if let Some((target_component, _)) = target_component.get() {
                         target_component.on_origin_drop(id, target, encoder);
                     }
                 }
@@ -333,6 +431,18 @@ where
         }
         output
     }
+
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        for origin in self.origins_mut() {
+            origin.target = mapper(origin.target);
+        }
+    }
+
+    #[inline]
+    fn clone_fn() -> Option<fn(&Self) -> Self> {
+        Some(Self::clone)
+    }
 }
 
 /// Component that is added to target entity of the non-symmetric relation.
@@ -341,6 +451,15 @@ pub(crate) struct TargetComponent<R> {
     relation: PhantomData<fn() -> R>,
 }
 
+impl<R> Clone for TargetComponent<R> {
+    fn clone(&self) -> Self {
+        TargetComponent {
+            origins: self.origins.clone(),
+            relation: PhantomData,
+        }
+    }
+}
+
 impl<R> TargetComponent<R>
 where
     R: Relation,
@@ -360,13 +479,32 @@ where
         self.origins.push(id);
     }
 
+    pub(crate) fn has_origin(&self, id: EntityId) -> bool {
+        self.origins.contains(&id)
+    }
+
+    /// Moves origin to specified position.
+    /// Index is clamped to the number of origins.
+    /// Returns `false` if origin is not found.
+    pub(crate) fn move_origin(&mut self, id: EntityId, index: usize) -> bool {
+        match self.origins.iter().position(|&origin| origin == id) {
+            None => false,
+            Some(idx) => {
+                let origin = self.origins.remove(idx);
+                let index = index.min(self.origins.len());
+                self.origins.insert(index, origin);
+                true
+            }
+        }
+    }
+
     /// Called when relation is removed from origin entity.
     /// Or origin entity is dropped.
     fn on_origin_drop(&mut self, id: EntityId, target: EntityId, mut encoder: ActionEncoder) {
         for idx in 0..self.origins.len() {
             if self.origins[idx] == id {
                 R::on_target_drop(id, target, encoder.reborrow());
-                self.origins.swap_remove(idx);
+                remove_at::<R, _>(&mut self.origins, idx);
                 break;
             }
         }
@@ -393,7 +531,8 @@ where
                 }
             } else {
                 encoder.closure_with_encoder(move |world, encoder| unsafe {
-                    if let Ok(origin) = world.query_one_unchecked::<&mut OriginComponent<R>>(entity)
+                    if let Ok((origin, _)) = world
+                        .query_one_unchecked::<(&mut OriginComponent<R>, IncludeDisabled)>(entity)
                     {
                         origin.on_non_exclusive_target_drop(entity, target, encoder);
                     }
@@ -414,6 +553,18 @@ where
         borrow_dyn_trait!(Self as RelationTarget => output);
         output
     }
+
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        for origin in &mut self.origins {
+            *origin = mapper(*origin);
+        }
+    }
+
+    #[inline]
+    fn clone_fn() -> Option<fn(&Self) -> Self> {
+        Some(Self::clone)
+    }
 }
 
 #[doc(hidden)]
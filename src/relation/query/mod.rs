@@ -14,6 +14,10 @@
 //! [`RelatesExclusive`] - matches relation origins and fetches exclusive relation instance and target.
 //! [`RelatesTo`] - matches relation origin with specified target and fetches relation instance.
 //! [`Related`] - matches relation targets and fetches slice of origins.
+//! [`RelatesDyn`] - matches origins of dynamic relation of specified kind and fetches targets.
+//! [`RelatedDyn`] - matches targets of dynamic relation of specified kind and fetches origins.
+//! [`RelatesJoin`] - matches relation origins and fetches relation instances joined with target query items.
+//! [`Up`] - matches relation origins and fetches component of the nearest ancestor that has it.
 //!
 //! # Filters
 //!
@@ -32,9 +36,13 @@ mod filter_related_by;
 mod filter_relates;
 mod filter_relates_to;
 mod related;
+mod related_dyn;
 mod relates;
+mod relates_dyn;
 mod relates_exclusive;
+mod relates_join;
 mod relates_to;
+mod up;
 
 pub use self::{
     filter_related::{related, FilterRelated},
@@ -42,7 +50,11 @@ pub use self::{
     filter_relates::{relates, FilterRelates},
     filter_relates_to::{relates_to, FilterFetchRelationTo, FilterRelatesTo},
     related::{FetchRelated, Related},
+    related_dyn::{related_dyn, FetchRelatedDyn, RelatedDyn},
     relates::{FetchRelatesRead, FetchRelatesWrite, Relates, RelatesReadIter, RelatesWriteIter},
+    relates_dyn::{relates_dyn, DynRelationIter, FetchRelatesDyn, RelatesDyn},
     relates_exclusive::{FetchRelatesExclusiveRead, FetchRelatesExclusiveWrite, RelatesExclusive},
+    relates_join::{FetchRelatesJoinRead, RelatesJoin, RelatesJoinIter},
     relates_to::{FetchRelatesToRead, FetchRelatesToWrite, RelatesTo},
+    up::{FetchUp, Up},
 };
@@ -0,0 +1,117 @@
+use core::{any::TypeId, marker::PhantomData, ptr::NonNull};
+
+use crate::{
+    archetype::Archetype,
+    entity::EntityId,
+    epoch::EpochId,
+    query::{Access, Fetch, ImmutableQuery, IntoQuery, Query},
+    relation::{dynamic::DynTargetComponent, DynRelationIter},
+};
+
+/// Query for targets of dynamic relation of specified kind.
+///
+/// Yields iterator of origins.
+#[derive(Clone, Copy, Debug)]
+pub struct RelatedDyn {
+    kind: EntityId,
+}
+
+impl RelatedDyn {
+    /// Returns dynamic relation query bound to one specific kind.
+    pub fn new(kind: EntityId) -> Self {
+        RelatedDyn { kind }
+    }
+}
+
+/// Returns a query for targets of dynamic relation of specified kind.
+pub fn related_dyn(kind: EntityId) -> RelatedDyn {
+    RelatedDyn::new(kind)
+}
+
+/// Fetch for the [`RelatedDyn`] query.
+pub struct FetchRelatedDyn<'a> {
+    kind: EntityId,
+    ptr: NonNull<DynTargetComponent>,
+    marker: PhantomData<&'a DynTargetComponent>,
+}
+
+unsafe impl<'a> Fetch<'a> for FetchRelatedDyn<'a> {
+    type Item = DynRelationIter<'a>;
+
+    #[inline]
+    fn dangling() -> Self {
+        FetchRelatedDyn {
+            kind: EntityId::dangling(),
+            ptr: NonNull::dangling(),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        let component = unsafe { &*self.ptr.as_ptr().add(idx) };
+        component.origins.iter().any(|pair| pair.kind == self.kind)
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> DynRelationIter<'a> {
+        let component = unsafe { &*self.ptr.as_ptr().add(idx) };
+        DynRelationIter::new(self.kind, &component.origins)
+    }
+}
+
+impl IntoQuery for RelatedDyn {
+    type Query = Self;
+
+    fn into_query(self) -> Self::Query {
+        self
+    }
+}
+
+unsafe impl Query for RelatedDyn {
+    type Item<'a> = DynRelationIter<'a>;
+    type Fetch<'a> = FetchRelatedDyn<'a>;
+
+    #[inline]
+    fn access(&self, ty: TypeId) -> Option<Access> {
+        if ty == TypeId::of::<DynTargetComponent>() {
+            Some(Access::Read)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.has_component(TypeId::of::<DynTargetComponent>())
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        f(TypeId::of::<DynTargetComponent>(), Access::Read)
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(
+        &mut self,
+        archetype: &'a Archetype,
+        _epoch: EpochId,
+    ) -> FetchRelatedDyn<'a> {
+        let component = unsafe {
+            archetype
+                .component(TypeId::of::<DynTargetComponent>())
+                .unwrap_unchecked()
+        };
+        debug_assert_eq!(component.id(), TypeId::of::<DynTargetComponent>());
+
+        let data = unsafe { component.data() };
+
+        FetchRelatedDyn {
+            kind: self.kind,
+            ptr: data.ptr.cast(),
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl ImmutableQuery for RelatedDyn {}
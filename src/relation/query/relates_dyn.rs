@@ -0,0 +1,148 @@
+use core::{any::TypeId, marker::PhantomData, ptr::NonNull};
+
+use crate::{
+    archetype::Archetype,
+    entity::EntityId,
+    epoch::EpochId,
+    query::{Access, Fetch, ImmutableQuery, IntoQuery, Query},
+    relation::dynamic::{DynOriginComponent, DynPair},
+};
+
+/// Query for origins of dynamic relation of specified kind.
+///
+/// Yields iterator of targets.
+#[derive(Clone, Copy, Debug)]
+pub struct RelatesDyn {
+    kind: EntityId,
+}
+
+impl RelatesDyn {
+    /// Returns dynamic relation query bound to one specific kind.
+    pub fn new(kind: EntityId) -> Self {
+        RelatesDyn { kind }
+    }
+}
+
+/// Returns a query for origins of dynamic relation of specified kind.
+pub fn relates_dyn(kind: EntityId) -> RelatesDyn {
+    RelatesDyn::new(kind)
+}
+
+/// Iterator over entities on the other side of dynamic relations of one kind.
+#[derive(Clone)]
+pub struct DynRelationIter<'a> {
+    kind: EntityId,
+    iter: core::slice::Iter<'a, DynPair>,
+}
+
+impl<'a> DynRelationIter<'a> {
+    pub(crate) fn new(kind: EntityId, pairs: &'a [DynPair]) -> Self {
+        DynRelationIter {
+            kind,
+            iter: pairs.iter(),
+        }
+    }
+}
+
+impl Iterator for DynRelationIter<'_> {
+    type Item = EntityId;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<EntityId> {
+        let kind = self.kind;
+        self.iter.find(|pair| pair.kind == kind).map(|pair| pair.id)
+    }
+}
+
+/// Fetch for the [`RelatesDyn`] query.
+pub struct FetchRelatesDyn<'a> {
+    kind: EntityId,
+    ptr: NonNull<DynOriginComponent>,
+    marker: PhantomData<&'a DynOriginComponent>,
+}
+
+unsafe impl<'a> Fetch<'a> for FetchRelatesDyn<'a> {
+    type Item = DynRelationIter<'a>;
+
+    #[inline]
+    fn dangling() -> Self {
+        FetchRelatesDyn {
+            kind: EntityId::dangling(),
+            ptr: NonNull::dangling(),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        let component = unsafe { &*self.ptr.as_ptr().add(idx) };
+        component.targets.iter().any(|pair| pair.kind == self.kind)
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> DynRelationIter<'a> {
+        let component = unsafe { &*self.ptr.as_ptr().add(idx) };
+        DynRelationIter::new(self.kind, &component.targets)
+    }
+}
+
+impl IntoQuery for RelatesDyn {
+    type Query = Self;
+
+    fn into_query(self) -> Self::Query {
+        self
+    }
+}
+
+unsafe impl Query for RelatesDyn {
+    type Item<'a> = DynRelationIter<'a>;
+    type Fetch<'a> = FetchRelatesDyn<'a>;
+
+    #[inline]
+    fn access(&self, ty: TypeId) -> Option<Access> {
+        if ty == TypeId::of::<DynOriginComponent>() {
+            Some(Access::Read)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.has_component(TypeId::of::<DynOriginComponent>())
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        f(TypeId::of::<DynOriginComponent>(), Access::Read)
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(
+        &mut self,
+        archetype: &'a Archetype,
+        _epoch: EpochId,
+    ) -> FetchRelatesDyn<'a> {
+        let component = unsafe {
+            archetype
+                .component(TypeId::of::<DynOriginComponent>())
+                .unwrap_unchecked()
+        };
+        debug_assert_eq!(component.id(), TypeId::of::<DynOriginComponent>());
+
+        let data = unsafe { component.data() };
+
+        FetchRelatesDyn {
+            kind: self.kind,
+            ptr: data.ptr.cast(),
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl ImmutableQuery for RelatesDyn {}
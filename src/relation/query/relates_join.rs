@@ -0,0 +1,273 @@
+use core::{any::TypeId, marker::PhantomData, ptr::NonNull};
+
+use crate::{
+    archetype::{chunk_idx, Archetype},
+    entity::{EntityId, EntitySet},
+    epoch::EpochId,
+    query::{merge_access, Access, Fetch, ImmutableQuery, IntoQuery, Query},
+    relation::{Origin, OriginComponent, Relation},
+    world::{acquire_archetypes, release_archetypes, World},
+};
+
+/// Query for origins of relation joined with a query on relation targets.
+///
+/// Yields iterator of pairs - relation instance and target query item.
+/// Targets that do not satisfy target query are skipped.
+///
+/// Target query must be immutable and [`Copy`].
+/// Archetypes visited by target query are borrowed when this query is created
+/// and stay borrowed until it is dropped.
+/// Therefore target query must not conflict with the rest of the query.
+///
+/// Because of this [`RelatesJoin`] is not [`Clone`] and can't be used with [`QueryRef::iter`].
+/// Use [`QueryRef::iter_mut`], [`QueryRef::for_each`] or [`QueryRef::fold`] instead.
+///
+/// [`QueryRef::iter`]: edict::world::QueryRef::iter
+/// [`QueryRef::iter_mut`]: edict::world::QueryRef::iter_mut
+/// [`QueryRef::for_each`]: edict::world::QueryRef::for_each
+/// [`QueryRef::fold`]: edict::world::QueryRef::fold
+pub struct RelatesJoin<'w, R, Q: Query> {
+    query: Q,
+    archetypes: &'w [Archetype],
+    entities: &'w EntitySet,
+    phantom: PhantomData<R>,
+}
+
+impl<'w, R, Q> RelatesJoin<'w, R, Q>
+where
+    Q: Query,
+{
+    /// Returns relation query joined with specified query on relation targets.
+    pub fn new(world: &'w World, query: Q) -> Self {
+        Self::from_parts(world.archetypes(), world.entity_set(), query)
+    }
+
+    pub(crate) fn from_parts(
+        archetypes: &'w [Archetype],
+        entities: &'w EntitySet,
+        query: Q,
+    ) -> Self {
+        acquire_archetypes(archetypes, &query);
+
+        RelatesJoin {
+            query,
+            archetypes,
+            entities,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<R, Q> Drop for RelatesJoin<'_, R, Q>
+where
+    Q: Query,
+{
+    fn drop(&mut self) {
+        // Borrowed in `from_parts`.
+        release_archetypes(self.archetypes, &self.query);
+    }
+}
+
+/// Iterator over relations of a given type on one entity
+/// joined with target query items.
+pub struct RelatesJoinIter<'a, R, Q> {
+    iter: core::slice::Iter<'a, Origin<R>>,
+    query: Q,
+    epoch: EpochId,
+    archetypes: &'a [Archetype],
+    entities: &'a EntitySet,
+}
+
+impl<'a, R, Q> Iterator for RelatesJoinIter<'a, R, Q>
+where
+    Q: Query,
+{
+    type Item = (&'a R, Q::Item<'a>);
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a R, Q::Item<'a>)> {
+        loop {
+            let origin = self.iter.next()?;
+            let item = unsafe {
+                fetch_target(
+                    &mut self.query,
+                    self.archetypes,
+                    self.entities,
+                    self.epoch,
+                    origin.target,
+                )
+            };
+            if let Some(item) = item {
+                return Some((&origin.relation, item));
+            }
+        }
+    }
+}
+
+/// Fetches target query item for one entity.
+///
+/// # Safety
+///
+/// Archetypes must be borrowed for the target query.
+unsafe fn fetch_target<'a, Q>(
+    query: &mut Q,
+    archetypes: &'a [Archetype],
+    entities: &EntitySet,
+    epoch: EpochId,
+    id: EntityId,
+) -> Option<Q::Item<'a>>
+where
+    Q: Query,
+{
+    let (archetype_idx, idx) = entities.get_location(id)?;
+
+    if archetype_idx == u32::MAX {
+        return query.reserved_entity_item(id);
+    }
+
+    let archetype = &archetypes[archetype_idx as usize];
+
+    if !query.visit_archetype(archetype) {
+        return None;
+    }
+
+    let mut fetch = unsafe { query.fetch(archetype, epoch) };
+
+    if !unsafe { fetch.visit_chunk(chunk_idx(idx as usize)) } {
+        return None;
+    }
+
+    if !unsafe { fetch.visit_item(idx as usize) } {
+        return None;
+    }
+
+    unsafe { fetch.touch_chunk(chunk_idx(idx as usize)) };
+
+    Some(unsafe { fetch.get_item(idx as usize) })
+}
+
+/// Fetch for the [`RelatesJoin<&R, Q>`] query.
+pub struct FetchRelatesJoinRead<'a, R: Relation, Q> {
+    query: Option<Q>,
+    epoch: EpochId,
+    archetypes: NonNull<[Archetype]>,
+    entities: NonNull<EntitySet>,
+    ptr: NonNull<OriginComponent<R>>,
+    marker: PhantomData<&'a OriginComponent<R>>,
+}
+
+unsafe impl<'a, R, Q> Fetch<'a> for FetchRelatesJoinRead<'a, R, Q>
+where
+    R: Relation + Sync,
+    Q: ImmutableQuery + Copy + 'static,
+{
+    type Item = RelatesJoinIter<'a, R, Q>;
+
+    #[inline]
+    fn dangling() -> Self {
+        FetchRelatesJoinRead {
+            query: None,
+            epoch: EpochId::start(),
+            archetypes: NonNull::from(&[][..]),
+            entities: NonNull::dangling(),
+            ptr: NonNull::dangling(),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> RelatesJoinIter<'a, R, Q> {
+        let origin_component = unsafe { &*self.ptr.as_ptr().add(idx) };
+
+        RelatesJoinIter {
+            iter: origin_component.origins().iter(),
+            query: unsafe { self.query.unwrap_unchecked() },
+            epoch: self.epoch,
+            archetypes: unsafe { &*self.archetypes.as_ptr() },
+            entities: unsafe { &*self.entities.as_ptr() },
+        }
+    }
+}
+
+impl<'w, R, Q> IntoQuery for RelatesJoin<'w, &R, Q>
+where
+    R: Relation + Sync,
+    Q: ImmutableQuery + Copy + 'static,
+{
+    type Query = Self;
+
+    #[inline]
+    fn into_query(self) -> Self {
+        self
+    }
+}
+
+unsafe impl<'w, R, Q> Query for RelatesJoin<'w, &R, Q>
+where
+    R: Relation + Sync,
+    Q: ImmutableQuery + Copy + 'static,
+{
+    type Item<'a> = RelatesJoinIter<'a, R, Q>;
+    type Fetch<'a> = FetchRelatesJoinRead<'a, R, Q>;
+
+    #[inline]
+    fn access(&self, ty: TypeId) -> Option<Access> {
+        let origin = if ty == TypeId::of::<OriginComponent<R>>() {
+            Some(Access::Read)
+        } else {
+            None
+        };
+        merge_access(origin, self.query.access(ty))
+    }
+
+    #[inline]
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.has_component(TypeId::of::<OriginComponent<R>>())
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        f(TypeId::of::<OriginComponent<R>>(), Access::Read);
+        if self.query.visit_archetype(archetype) {
+            unsafe { self.query.access_archetype(archetype, f) }
+        }
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(
+        &mut self,
+        archetype: &'a Archetype,
+        epoch: EpochId,
+    ) -> FetchRelatesJoinRead<'a, R, Q> {
+        let component = unsafe {
+            archetype
+                .component(TypeId::of::<OriginComponent<R>>())
+                .unwrap_unchecked()
+        };
+
+        debug_assert_eq!(component.id(), TypeId::of::<OriginComponent<R>>());
+
+        let data = unsafe { component.data() };
+
+        FetchRelatesJoinRead {
+            query: Some(self.query),
+            epoch,
+            archetypes: NonNull::from(self.archetypes),
+            entities: NonNull::from(self.entities),
+            ptr: data.ptr.cast(),
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl<'w, R, Q> ImmutableQuery for RelatesJoin<'w, &R, Q>
+where
+    R: Relation + Sync,
+    Q: ImmutableQuery + Copy + 'static,
+{
+}
@@ -0,0 +1,263 @@
+use core::{any::TypeId, marker::PhantomData, ptr::NonNull};
+
+use crate::{
+    archetype::Archetype,
+    entity::{EntityId, EntitySet},
+    epoch::EpochId,
+    query::{Access, Fetch, ImmutableQuery, IntoQuery, Query},
+    relation::{OriginComponent, Relation},
+    world::{acquire_archetypes, release_archetypes, World},
+};
+
+/// Query that traverses exclusive relation "up" from origin to target
+/// and fetches component of the nearest ancestor that has it.
+///
+/// Entity itself is not checked, only its targets, targets of targets and so on.
+/// Entities that have no ancestor with the component are skipped.
+///
+/// Relation must be exclusive, like [`ChildOf`].
+/// This is checked at compile time.
+///
+/// Archetypes with the component and relation are borrowed when this query is created
+/// and stay borrowed until it is dropped.
+/// Therefore the rest of the query must not access the component or relation mutably.
+///
+/// Because of this [`Up`] is not [`Clone`] and can't be used with [`QueryRef::iter`].
+/// Use [`QueryRef::iter_mut`], [`QueryRef::for_each`] or [`QueryRef::fold`] instead.
+///
+/// [`ChildOf`]: edict::relation::ChildOf
+/// [`QueryRef::iter`]: edict::world::QueryRef::iter
+/// [`QueryRef::iter_mut`]: edict::world::QueryRef::iter_mut
+/// [`QueryRef::for_each`]: edict::world::QueryRef::for_each
+/// [`QueryRef::fold`]: edict::world::QueryRef::fold
+pub struct Up<'w, R: Relation + Sync, T: Sync + 'static> {
+    archetypes: &'w [Archetype],
+    entities: &'w EntitySet,
+    max_depth: usize,
+    phantom: PhantomData<fn() -> (R, T)>,
+}
+
+/// Query used to borrow archetypes visited by [`Up`] traversal.
+type UpBorrow<R, T> = (
+    PhantomData<fn() -> Option<&'static T>>,
+    PhantomData<fn() -> Option<&'static OriginComponent<R>>>,
+);
+
+impl<'w, R, T> Up<'w, R, T>
+where
+    R: Relation + Sync,
+    T: Sync + 'static,
+{
+    /// Returns query that fetches component from the nearest ancestor.
+    pub fn new(world: &'w World) -> Self {
+        Self::from_parts(world.archetypes(), world.entity_set())
+    }
+
+    pub(crate) fn from_parts(archetypes: &'w [Archetype], entities: &'w EntitySet) -> Self {
+        const {
+            assert!(R::EXCLUSIVE, "Up can be used only with EXCLUSIVE relations");
+        }
+
+        acquire_archetypes(archetypes, &UpBorrow::<R, T>::default());
+
+        Up {
+            archetypes,
+            entities,
+            // Bounds traversal if relation has a cycle.
+            max_depth: archetypes.iter().map(Archetype::len).sum(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<R, T> Drop for Up<'_, R, T>
+where
+    R: Relation + Sync,
+    T: Sync + 'static,
+{
+    fn drop(&mut self) {
+        // Borrowed in `from_parts`.
+        release_archetypes(self.archetypes, &UpBorrow::<R, T>::default());
+    }
+}
+
+/// Fetch for the [`Up<R, T>`] query.
+pub struct FetchUp<'a, R: Relation, T> {
+    archetypes: NonNull<[Archetype]>,
+    entities: NonNull<EntitySet>,
+    ptr: NonNull<OriginComponent<R>>,
+    max_depth: usize,
+    found: Option<(usize, NonNull<T>)>,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, R, T> FetchUp<'a, R, T>
+where
+    R: Relation,
+    T: 'static,
+{
+    /// Finds component on the nearest ancestor of the entity at `idx`.
+    unsafe fn find(&self, idx: usize) -> Option<NonNull<T>> {
+        let archetypes = unsafe { &*self.archetypes.as_ptr() };
+        let entities = unsafe { &*self.entities.as_ptr() };
+
+        let origin_component = unsafe { &*self.ptr.as_ptr().add(idx) };
+        let mut target = origin_component.origins()[0].target;
+
+        for _ in 0..self.max_depth {
+            match unsafe { step::<R, T>(archetypes, entities, target)? } {
+                Ok(next) => target = next,
+                Err(ptr) => return Some(ptr),
+            }
+        }
+        None
+    }
+}
+
+/// Looks at the entity `id`.
+/// Returns `Err` with component pointer if entity has the component,
+/// `Ok` with next target if it doesn't but has relation,
+/// and `None` otherwise.
+unsafe fn step<R, T>(
+    archetypes: &[Archetype],
+    entities: &EntitySet,
+    id: EntityId,
+) -> Option<Result<EntityId, NonNull<T>>>
+where
+    R: Relation,
+    T: 'static,
+{
+    let (archetype_idx, idx) = entities.get_location(id)?;
+    if archetype_idx == u32::MAX {
+        return None;
+    }
+
+    let archetype = &archetypes[archetype_idx as usize];
+
+    if let Some(component) = archetype.component(TypeId::of::<T>()) {
+        let data = unsafe { component.data() };
+        let ptr = unsafe { data.ptr.cast::<T>().as_ptr().add(idx as usize) };
+        return Some(Err(unsafe { NonNull::new_unchecked(ptr) }));
+    }
+
+    let component = archetype.component(TypeId::of::<OriginComponent<R>>())?;
+    let data = unsafe { component.data() };
+    let origin_component = unsafe {
+        &*data
+            .ptr
+            .cast::<OriginComponent<R>>()
+            .as_ptr()
+            .add(idx as usize)
+    };
+    Some(Ok(origin_component.origins()[0].target))
+}
+
+unsafe impl<'a, R, T> Fetch<'a> for FetchUp<'a, R, T>
+where
+    R: Relation + Sync,
+    T: Sync + 'static,
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn dangling() -> Self {
+        FetchUp {
+            archetypes: NonNull::from(&[][..]),
+            entities: NonNull::dangling(),
+            ptr: NonNull::dangling(),
+            max_depth: 0,
+            found: None,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        match unsafe { self.find(idx) } {
+            None => false,
+            Some(ptr) => {
+                self.found = Some((idx, ptr));
+                true
+            }
+        }
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> &'a T {
+        let ptr = match self.found {
+            Some((found_idx, ptr)) if found_idx == idx => ptr,
+            _ => unsafe { self.find(idx).unwrap_unchecked() },
+        };
+        unsafe { &*ptr.as_ptr() }
+    }
+}
+
+impl<'w, R, T> IntoQuery for Up<'w, R, T>
+where
+    R: Relation + Sync,
+    T: Sync + 'static,
+{
+    type Query = Self;
+
+    #[inline]
+    fn into_query(self) -> Self {
+        self
+    }
+}
+
+unsafe impl<'w, R, T> Query for Up<'w, R, T>
+where
+    R: Relation + Sync,
+    T: Sync + 'static,
+{
+    type Item<'a> = &'a T;
+    type Fetch<'a> = FetchUp<'a, R, T>;
+
+    #[inline]
+    fn access(&self, ty: TypeId) -> Option<Access> {
+        if ty == TypeId::of::<OriginComponent<R>>() || ty == TypeId::of::<T>() {
+            Some(Access::Read)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.has_component(TypeId::of::<OriginComponent<R>>())
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        f(TypeId::of::<OriginComponent<R>>(), Access::Read)
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(&mut self, archetype: &'a Archetype, _epoch: EpochId) -> FetchUp<'a, R, T> {
+        let component = unsafe {
+            archetype
+                .component(TypeId::of::<OriginComponent<R>>())
+                .unwrap_unchecked()
+        };
+
+        debug_assert_eq!(component.id(), TypeId::of::<OriginComponent<R>>());
+
+        let data = unsafe { component.data() };
+
+        FetchUp {
+            archetypes: NonNull::from(self.archetypes),
+            entities: NonNull::from(self.entities),
+            ptr: data.ptr.cast(),
+            max_depth: self.max_depth,
+            found: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl<'w, R, T> ImmutableQuery for Up<'w, R, T>
+where
+    R: Relation + Sync,
+    T: Sync + 'static,
+{
+}
@@ -187,6 +187,17 @@ impl Res {
         Some(r)
     }
 
+    /// Moves all resources from `other` container into this one.
+    /// Resources present in both containers are replaced if `replace` is `true`
+    /// and dropped from `other` otherwise.
+    pub fn append(&mut self, other: Res, replace: bool) {
+        for (id, resource) in other.resources {
+            if replace || !self.resources.contains_key(&id) {
+                self.resources.insert(id, resource);
+            }
+        }
+    }
+
     /// Reset all possible leaks on resources.
     /// Mutable reference guarantees that no borrows are active.
     pub fn undo_leak(&mut self) {
@@ -0,0 +1,410 @@
+//! Data-driven entity templates.
+//!
+//! [`Scene`] describes entities in a simple line-based text format
+//! that can be authored without recompiling.
+//!
+//! ```text
+//! # Comments start with '#'.
+//! entity level
+//!     Level = 1
+//!
+//! entity player
+//!     Health = 100
+//!     Player
+//!     ChildOf -> level
+//!
+//! entity
+//!     Health = 20
+//!     Likes = 5 -> player
+//! ```
+//!
+//! Each entity starts with `entity` keyword followed by an optional local name.
+//! Following lines list components as `Key = value` or just `Key` for components without value.
+//! Lines with `->` add relations to other entities of the scene by local name,
+//! either as `Key -> target` or `Key = value -> target`.
+//!
+//! Keys and value parsers are registered in [`SceneRegistry`].
+//! [`SceneRegistry::instantiate`] spawns scene entities under fresh ids
+//! and returns map from local names to spawned entities.
+
+use core::{fmt, str::FromStr};
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use hashbrown::HashMap;
+
+use crate::{
+    action::ActionEncoder, bundle::EntityBuilder, component::Component, entity::EntityId,
+    relation::Relation,
+};
+
+/// Component of a scene entity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SceneComponent {
+    /// Key of the component in [`SceneRegistry`].
+    pub key: String,
+
+    /// Text of the value. Empty for components without value.
+    pub value: String,
+}
+
+/// Relation of a scene entity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SceneRelation {
+    /// Key of the relation in [`SceneRegistry`].
+    pub key: String,
+
+    /// Text of the value. Empty for relations without value.
+    pub value: String,
+
+    /// Local name of the target entity.
+    pub target: String,
+}
+
+/// Entity of a scene.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SceneEntity {
+    /// Local name of the entity.
+    pub name: Option<String>,
+
+    /// Components of the entity.
+    pub components: Vec<SceneComponent>,
+
+    /// Relations of the entity.
+    pub relations: Vec<SceneRelation>,
+}
+
+/// Parsed scene.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Scene {
+    /// Entities of the scene.
+    pub entities: Vec<SceneEntity>,
+}
+
+/// Error that may occur when scene is parsed or instantiated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SceneError {
+    /// Line of scene text is malformed.
+    Syntax {
+        /// Line number starting from 1.
+        line: usize,
+
+        /// Description of the problem.
+        message: &'static str,
+    },
+
+    /// Two entities of the scene have the same local name.
+    DuplicateName {
+        /// Local name.
+        name: String,
+    },
+
+    /// Component key is not registered.
+    UnknownComponent {
+        /// Key of the component.
+        key: String,
+    },
+
+    /// Relation key is not registered.
+    UnknownRelation {
+        /// Key of the relation.
+        key: String,
+    },
+
+    /// Relation target is not an entity of the scene.
+    UnknownEntity {
+        /// Local name of the target.
+        name: String,
+    },
+
+    /// Value failed to parse.
+    InvalidValue {
+        /// Key of the component or relation.
+        key: String,
+    },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { line, message } => write!(f, "Line {}: {}", line, message),
+            Self::DuplicateName { name } => write!(f, "Duplicate entity name `{}`", name),
+            Self::UnknownComponent { key } => write!(f, "Unknown component `{}`", key),
+            Self::UnknownRelation { key } => write!(f, "Unknown relation `{}`", key),
+            Self::UnknownEntity { name } => write!(f, "Unknown entity `{}`", name),
+            Self::InvalidValue { key } => write!(f, "Invalid value of `{}`", key),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SceneError {}
+
+impl Scene {
+    /// Parses scene from text.
+    pub fn parse(text: &str) -> Result<Self, SceneError> {
+        let mut entities: Vec<SceneEntity> = Vec::new();
+        let mut names = HashMap::new();
+
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let syntax = |message| SceneError::Syntax {
+                line: idx + 1,
+                message,
+            };
+
+            if let Some(rest) = line.strip_prefix("entity") {
+                if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+                    return Err(syntax("Unexpected text after `entity`"));
+                }
+                let name = rest.trim();
+                let name = if name.is_empty() {
+                    None
+                } else {
+                    if name.contains(char::is_whitespace) {
+                        return Err(syntax("Entity name must not contain whitespace"));
+                    }
+                    if names.insert(String::from(name), entities.len()).is_some() {
+                        return Err(SceneError::DuplicateName {
+                            name: String::from(name),
+                        });
+                    }
+                    Some(String::from(name))
+                };
+                entities.push(SceneEntity {
+                    name,
+                    ..SceneEntity::default()
+                });
+                continue;
+            }
+
+            let Some(entity) = entities.last_mut() else {
+                return Err(syntax("Component outside of entity"));
+            };
+
+            let (lhs, target) = match line.rsplit_once("->") {
+                None => (line, None),
+                Some((lhs, target)) => {
+                    let target = target.trim();
+                    if target.is_empty() {
+                        return Err(syntax("Missing relation target"));
+                    }
+                    (lhs, Some(String::from(target)))
+                }
+            };
+
+            let (key, value) = match lhs.split_once('=') {
+                None => (lhs.trim(), ""),
+                Some((key, value)) => (key.trim(), value.trim()),
+            };
+            if key.is_empty() {
+                return Err(syntax("Missing key"));
+            }
+
+            let key = String::from(key);
+            let value = String::from(value);
+            match target {
+                None => entity.components.push(SceneComponent { key, value }),
+                Some(target) => entity.relations.push(SceneRelation { key, value, target }),
+            }
+        }
+
+        Ok(Scene { entities })
+    }
+}
+
+impl FromStr for Scene {
+    type Err = SceneError;
+
+    #[inline]
+    fn from_str(text: &str) -> Result<Self, SceneError> {
+        Scene::parse(text)
+    }
+}
+
+type ComponentFn = Box<dyn Fn(&str, &mut EntityBuilder) -> Option<()> + Send + Sync>;
+type RelationFn = Box<dyn Fn(&str) -> Option<AddRelationFn> + Send + Sync>;
+type AddRelationFn = Box<dyn FnOnce(&mut ActionEncoder, EntityId, EntityId)>;
+
+/// Registry of components and relations that can be used in scenes.
+#[derive(Default)]
+pub struct SceneRegistry {
+    components: HashMap<String, ComponentFn>,
+    relations: HashMap<String, RelationFn>,
+}
+
+impl SceneRegistry {
+    /// Returns new empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        SceneRegistry {
+            components: HashMap::new(),
+            relations: HashMap::new(),
+        }
+    }
+
+    /// Registers component type keyed by [`Component::name`].
+    ///
+    /// `parse` reads component from value text
+    /// and returns `None` if text is invalid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if key is already registered.
+    pub fn register<T, P>(&mut self, parse: P) -> &mut Self
+    where
+        T: Component + Send,
+        P: Fn(&str) -> Option<T> + Send + Sync + 'static,
+    {
+        self.register_as(T::name(), parse)
+    }
+
+    /// Registers component type with specified key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if key is already registered.
+    pub fn register_as<T, P>(&mut self, key: impl Into<String>, parse: P) -> &mut Self
+    where
+        T: Component + Send,
+        P: Fn(&str) -> Option<T> + Send + Sync + 'static,
+    {
+        let key = key.into();
+        assert!(
+            !self.components.contains_key(&key),
+            "Component key `{}` is already registered",
+            key
+        );
+
+        self.components.insert(
+            key,
+            Box::new(move |value, builder| {
+                builder.add(parse(value)?);
+                Some(())
+            }),
+        );
+        self
+    }
+
+    /// Registers relation type keyed by [`Relation::name`].
+    ///
+    /// `parse` reads relation from value text
+    /// and returns `None` if text is invalid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if key is already registered.
+    pub fn register_relation<R, P>(&mut self, parse: P) -> &mut Self
+    where
+        R: Relation,
+        P: Fn(&str) -> Option<R> + Send + Sync + 'static,
+    {
+        self.register_relation_as(R::name(), parse)
+    }
+
+    /// Registers relation type with specified key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if key is already registered.
+    pub fn register_relation_as<R, P>(&mut self, key: impl Into<String>, parse: P) -> &mut Self
+    where
+        R: Relation,
+        P: Fn(&str) -> Option<R> + Send + Sync + 'static,
+    {
+        let key = key.into();
+        assert!(
+            !self.relations.contains_key(&key),
+            "Relation key `{}` is already registered",
+            key
+        );
+
+        self.relations.insert(
+            key,
+            Box::new(move |value| {
+                let relation = parse(value)?;
+                Some(Box::new(
+                    move |actions: &mut ActionEncoder, origin, target| {
+                        actions.add_relation(origin, relation, target);
+                    },
+                ))
+            }),
+        );
+        self
+    }
+
+    /// Encodes actions to spawn scene entities under fresh ids.
+    ///
+    /// Returns map from local names to spawned entities.
+    /// Nothing is encoded if scene refers to unknown keys or names
+    /// or contains invalid values.
+    pub fn instantiate(
+        &self,
+        scene: &Scene,
+        actions: &mut ActionEncoder,
+    ) -> Result<HashMap<String, EntityId>, SceneError> {
+        let mut indices = HashMap::new();
+        for (idx, entity) in scene.entities.iter().enumerate() {
+            if let Some(name) = &entity.name {
+                if indices.insert(name.as_str(), idx).is_some() {
+                    return Err(SceneError::DuplicateName { name: name.clone() });
+                }
+            }
+        }
+
+        let mut builders = Vec::with_capacity(scene.entities.len());
+        let mut relations = Vec::new();
+
+        for (idx, entity) in scene.entities.iter().enumerate() {
+            let mut builder = EntityBuilder::new();
+            for comp in &entity.components {
+                let parse =
+                    self.components
+                        .get(&comp.key)
+                        .ok_or_else(|| SceneError::UnknownComponent {
+                            key: comp.key.clone(),
+                        })?;
+                parse(&comp.value, &mut builder).ok_or_else(|| SceneError::InvalidValue {
+                    key: comp.key.clone(),
+                })?;
+            }
+            builders.push(builder);
+
+            for rel in &entity.relations {
+                let parse =
+                    self.relations
+                        .get(&rel.key)
+                        .ok_or_else(|| SceneError::UnknownRelation {
+                            key: rel.key.clone(),
+                        })?;
+                let target =
+                    *indices
+                        .get(rel.target.as_str())
+                        .ok_or_else(|| SceneError::UnknownEntity {
+                            name: rel.target.clone(),
+                        })?;
+                let add = parse(&rel.value).ok_or_else(|| SceneError::InvalidValue {
+                    key: rel.key.clone(),
+                })?;
+                relations.push((idx, add, target));
+            }
+        }
+
+        let ids: Vec<EntityId> = builders
+            .into_iter()
+            .map(|builder| actions.spawn(builder))
+            .collect();
+
+        for (origin, add, target) in relations {
+            add(actions, ids[origin], ids[target]);
+        }
+
+        Ok(indices
+            .into_iter()
+            .map(|(name, idx)| (String::from(name), ids[idx]))
+            .collect())
+    }
+}
@@ -16,7 +16,7 @@ use crate::{
     entity::EntityId,
     epoch::EpochId,
     system::State,
-    world::World,
+    world::{Disabled, World},
 };
 
 mod tls;
@@ -207,6 +207,7 @@ pub fn task_system(world: &mut World, mut state: State<TaskSystemState>) {
 
     for archetype in world.archetypes() {
         let Some(indices) = archetype.borrow_mut_indices(TypeId::of::<dyn AnyTask>()) else { continue };
+        let disabled = archetype.has_component(TypeId::of::<Disabled>());
         for &(tid, idx) in indices {
             let component = unsafe { archetype.component(tid).unwrap_unchecked() };
             let data = unsafe { component.data_mut() };
@@ -229,6 +230,12 @@ pub fn task_system(world: &mut World, mut state: State<TaskSystemState>) {
                     continue;
                 }
 
+                if disabled {
+                    // Poll when entity is enabled.
+                    state.queue.lock().push((archetype.entities()[idx], tid));
+                    continue;
+                }
+
                 let ptr = unsafe {
                     NonNull::new_unchecked(data.ptr.as_ptr().add(component.layout().size() * idx))
                 };
@@ -253,6 +260,11 @@ pub fn task_system(world: &mut World, mut state: State<TaskSystemState>) {
     for (id, tid) in state.wakes.drain(..) {
         let Some((arch, idx)) = world.entity_set().get_location(id) else {continue;};
         let arch = &world.archetypes()[arch as usize];
+        if arch.has_component(TypeId::of::<Disabled>()) {
+            // Poll when entity is enabled.
+            state.queue.lock().push((id, tid));
+            continue;
+        }
         let Some(component) = arch.component(tid) else {continue;};
         let borrow = unsafe {
             component
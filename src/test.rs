@@ -1,8 +1,9 @@
 use crate::{
+    bundle::EntityBuilder,
     component::Component,
     query::{Entities, ImmutableQuery, Not, With, Without},
-    relation::{ChildOf, Relation, RelationOrigin, RelationTarget},
-    world::{QueryOneError, World},
+    relation::{ChildOf, Related, Relation, RelationOrigin, RelationTarget, TargetComponent},
+    world::{AddRelationError, EntityError, QueryOneError, World},
 };
 
 use alloc::{vec, vec::Vec};
@@ -33,6 +34,25 @@ fn world_spawn() {
     );
 }
 
+/// Tests that entity builder grows its buffer and spawns all components.
+#[test]
+fn world_spawn_builder() {
+    let mut world = World::new();
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Big([u64; 32]);
+    impl Component for Big {}
+
+    let mut builder = EntityBuilder::new();
+    builder.add(U32(42)).add(Str("qwe")).add(Big([7; 32]));
+
+    let e = world.spawn(builder);
+    assert_eq!(
+        world.query_one_mut::<(&U32, &Str, &Big)>(e),
+        Ok((&U32(42), &Str("qwe"), &Big([7; 32])))
+    );
+}
+
 /// Tests that entity does not have a component that wasn't in spawn bundle
 /// but has it after component is inserted
 #[test]
@@ -55,6 +75,31 @@ fn world_insert() {
     );
 }
 
+/// Tests that replaced component value is dropped exactly once.
+#[test]
+fn world_insert_replace_drop() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Counted;
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    impl Component for Counted {}
+
+    let mut world = World::new();
+
+    let e = world.spawn((Counted,));
+    assert_eq!(world.insert(e, Counted), Ok(()));
+    assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+
+    assert_eq!(world.despawn(e), Ok(()));
+    assert_eq!(DROPS.load(Ordering::Relaxed), 2);
+}
+
 /// Tests that entity does not have a component that was removed.
 #[test]
 fn world_remove() {
@@ -681,6 +726,249 @@ fn test_symmetric_exclusive_relation() {
     }
 }
 
+#[test]
+fn test_acyclic_relation() {
+    let mut world = World::new();
+
+    #[derive(Clone, Copy)]
+    struct A;
+
+    impl Relation for A {
+        const ACYCLIC: bool = true;
+    }
+
+    let a = world.spawn(());
+    let b = world.spawn(());
+    let c = world.spawn(());
+
+    assert_eq!(world.add_relation(a, A, a), Err(AddRelationError::Cycle));
+
+    world.add_relation(a, A, b).unwrap();
+    world.add_relation(b, A, c).unwrap();
+    world.add_relation(a, A, c).unwrap();
+
+    assert_eq!(world.add_relation(c, A, a), Err(AddRelationError::Cycle));
+    assert_eq!(world.add_relation(b, A, a), Err(AddRelationError::Cycle));
+
+    world.remove_relation::<A>(b, c).unwrap();
+    world.add_relation(c, A, b).unwrap();
+
+    let p = world.spawn(());
+    let q = world.spawn(());
+    world.add_relation(q, ChildOf, p).unwrap();
+    assert_eq!(
+        world.add_relation(p, ChildOf, q),
+        Err(AddRelationError::Cycle)
+    );
+    assert!(world.is_alive(p));
+    assert!(world.is_alive(q));
+}
+
+#[test]
+fn test_relates_join() {
+    let mut world = World::new();
+
+    #[derive(Clone, Copy)]
+    struct Likes;
+
+    impl Relation for Likes {}
+
+    let parent = world.spawn((U32(1),));
+    let child_a = world.spawn((Str("a"),));
+    let child_b = world.spawn((Str("b"),));
+    let orphan = world.spawn((Str("c"),));
+
+    world.add_relation(child_a, ChildOf, parent).unwrap();
+    world.add_relation(child_b, ChildOf, parent).unwrap();
+
+    let mut joined = Vec::new();
+    world
+        .query::<&Str>()
+        .relates_join::<&ChildOf, (Entities, &U32)>()
+        .for_each(|(name, parents)| {
+            for (ChildOf, (p, value)) in parents {
+                joined.push((name.0, p, value.0));
+            }
+        });
+    joined.sort();
+    assert_eq!(joined, vec![("a", parent, 1), ("b", parent, 1)]);
+
+    world.add_relation(orphan, Likes, parent).unwrap();
+    world.add_relation(orphan, Likes, child_a).unwrap();
+
+    let mut query = world.query::<Entities>().relates_join::<&Likes, &U32>();
+    let items = query
+        .iter_mut()
+        .map(|(e, likes)| (e, likes.map(|(_, value)| value.0).collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    assert_eq!(items, vec![(orphan, vec![1])]);
+    drop(query);
+
+    // Target archetypes are released with the query.
+    world.query::<&mut U32>().for_each(|value| value.0 += 1);
+}
+
+#[test]
+fn test_up() {
+    let mut world = World::new();
+
+    let root = world.spawn((U32(1),));
+    let mid = world.spawn((Str("mid"),));
+    let leaf = world.spawn((Str("leaf"),));
+    let sub = world.spawn((Str("sub"), U32(2)));
+    let sub_leaf = world.spawn((Str("sub_leaf"),));
+    let lonely = world.spawn((Str("lonely"),));
+    let lonely_child = world.spawn((Str("lonely_child"),));
+
+    world.add_relation(mid, ChildOf, root).unwrap();
+    world.add_relation(leaf, ChildOf, mid).unwrap();
+    world.add_relation(sub, ChildOf, root).unwrap();
+    world.add_relation(sub_leaf, ChildOf, sub).unwrap();
+    world.add_relation(lonely_child, ChildOf, lonely).unwrap();
+
+    let mut items = Vec::new();
+    world
+        .query::<&Str>()
+        .up::<ChildOf, U32>()
+        .for_each(|(name, value)| items.push((name.0, value.0)));
+    items.sort();
+
+    assert_eq!(
+        items,
+        vec![("leaf", 1), ("mid", 1), ("sub", 1), ("sub_leaf", 2)]
+    );
+}
+
+#[test]
+fn test_ordered_children() {
+    let mut world = World::new();
+
+    let parent = world.spawn(());
+    let a = world.spawn(());
+    let b = world.spawn(());
+    let c = world.spawn(());
+    let d = world.spawn(());
+
+    world.add_relation(a, ChildOf, parent).unwrap();
+    world.add_relation(b, ChildOf, parent).unwrap();
+    world.add_relation(c, ChildOf, parent).unwrap();
+
+    let children = |world: &World| {
+        world
+            .query_one::<Related<ChildOf>>(parent)
+            .unwrap()
+            .get()
+            .unwrap()
+            .to_vec()
+    };
+
+    assert_eq!(children(&world), vec![a, b, c]);
+
+    world.insert_child_at(d, parent, 1).unwrap();
+    assert_eq!(children(&world), vec![a, d, b, c]);
+
+    world.move_child(c, 0).unwrap();
+    assert_eq!(children(&world), vec![c, a, d, b]);
+
+    world.move_child(a, 100).unwrap();
+    assert_eq!(children(&world), vec![c, d, b, a]);
+
+    // Reordering marks target component modified.
+    let epoch = world.epoch();
+    world.move_child(b, 0).unwrap();
+    assert_eq!(
+        world
+            .query::<Entities>()
+            .modified::<&TargetComponent<ChildOf>>(epoch)
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>(),
+        vec![parent]
+    );
+    assert_eq!(children(&world), vec![b, c, d, a]);
+    world.move_child(b, 2).unwrap();
+
+    // Re-adding relation keeps the position.
+    world.add_relation(d, ChildOf, parent).unwrap();
+    assert_eq!(children(&world), vec![c, d, b, a]);
+
+    world.despawn(c).unwrap();
+    assert_eq!(children(&world), vec![d, b, a]);
+
+    assert_eq!(
+        world.move_child(parent, 0),
+        Err(EntityError::MissingComponents)
+    );
+}
+
+#[test]
+fn test_dyn_relation() {
+    let mut world = World::new();
+
+    let likes = world.spawn(());
+    let fears = world.spawn(());
+
+    let a = world.spawn(());
+    let b = world.spawn(());
+    let c = world.spawn(());
+
+    world.add_relation_dyn(a, likes, b).unwrap();
+    world.add_relation_dyn(a, likes, c).unwrap();
+    world.add_relation_dyn(b, likes, c).unwrap();
+    world.add_relation_dyn(a, fears, c).unwrap();
+
+    let relates = |world: &World, kind| {
+        let mut items = world
+            .query::<Entities>()
+            .relates_dyn(kind)
+            .iter()
+            .map(|(e, targets)| {
+                let mut targets = targets.collect::<Vec<_>>();
+                targets.sort();
+                (e, targets)
+            })
+            .collect::<Vec<_>>();
+        items.sort();
+        items
+    };
+
+    let related = |world: &World, kind| {
+        let mut items = world
+            .query::<Entities>()
+            .related_dyn(kind)
+            .iter()
+            .map(|(e, origins)| {
+                let mut origins = origins.collect::<Vec<_>>();
+                origins.sort();
+                (e, origins)
+            })
+            .collect::<Vec<_>>();
+        items.sort();
+        items
+    };
+
+    assert_eq!(relates(&world, likes), vec![(a, vec![b, c]), (b, vec![c])]);
+    assert_eq!(relates(&world, fears), vec![(a, vec![c])]);
+    assert_eq!(related(&world, likes), vec![(b, vec![a]), (c, vec![a, b])]);
+
+    world.remove_relation_dyn(a, likes, b).unwrap();
+    assert_eq!(
+        world.remove_relation_dyn(a, likes, b),
+        Err(EntityError::MissingComponents)
+    );
+    assert_eq!(relates(&world, likes), vec![(a, vec![c]), (b, vec![c])]);
+    assert_eq!(related(&world, likes), vec![(c, vec![a, b])]);
+
+    world.despawn(c).unwrap();
+    assert_eq!(relates(&world, likes), vec![]);
+    assert_eq!(relates(&world, fears), vec![]);
+    assert_eq!(related(&world, likes), vec![]);
+
+    world.add_relation_dyn(a, likes, b).unwrap();
+    world.despawn(a).unwrap();
+    assert_eq!(related(&world, likes), vec![]);
+}
+
 #[test]
 fn test_filters() {
     use crate::query::IntoQuery;
@@ -748,3 +1036,906 @@ fn add_relation() {
     world.insert(origin, Foo).unwrap();
     world.add_relation(origin, ChildOf, target).unwrap();
 }
+
+#[test]
+fn scene_instantiate() {
+    use crate::{
+        action::ActionBuffer,
+        scene::{Scene, SceneError, SceneRegistry},
+    };
+
+    let scene = Scene::parse(
+        "
+        # Parent with a child.
+        entity parent
+            U32 = 1
+            Bool = true
+
+        entity child
+            U32 = 2
+            ChildOf -> parent
+
+        entity
+            Bool
+        ",
+    )
+    .unwrap();
+
+    let mut registry = SceneRegistry::new();
+    registry
+        .register_as("U32", |value: &str| value.parse().ok().map(U32))
+        .register_as("Bool", |value: &str| match value {
+            "" => Some(Bool(false)),
+            value => value.parse().ok().map(Bool),
+        })
+        .register_relation_as("ChildOf", |_: &str| Some(ChildOf));
+
+    let mut world = World::new();
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world);
+    let names = registry.instantiate(&scene, &mut actions).unwrap();
+    buffer.execute(&mut world);
+
+    assert_eq!(names.len(), 2);
+    let parent = names["parent"];
+    let child = names["child"];
+
+    assert_eq!(
+        world.query_one_mut::<(&U32, &Bool)>(parent),
+        Ok((&U32(1), &Bool(true)))
+    );
+    assert_eq!(world.query_one_mut::<&U32>(child), Ok(&U32(2)));
+    assert_eq!(
+        world.query_one_mut::<Related<ChildOf>>(parent),
+        Ok(&[child][..])
+    );
+    assert_eq!(world.query::<&Bool>().iter().count(), 2);
+
+    let scene = Scene::parse("entity\n  ChildOf -> nobody").unwrap();
+    let mut actions = buffer.encoder(&world);
+    assert_eq!(
+        registry.instantiate(&scene, &mut actions),
+        Err(SceneError::UnknownEntity {
+            name: "nobody".into()
+        })
+    );
+
+    assert_eq!(
+        Scene::parse("U32 = 1"),
+        Err(SceneError::Syntax {
+            line: 1,
+            message: "Component outside of entity"
+        })
+    );
+}
+
+#[test]
+fn world_snapshot_restore() {
+    use crate::world::{SnapshotRing, WorldBuilder};
+
+    let mut builder = WorldBuilder::new().with_snapshot_resource::<u64>();
+    builder.register_component::<U32>().clone_fn(U32::clone);
+    builder.register_component::<Str>().clone_fn(Str::clone);
+    let mut world = builder.build();
+
+    world.insert_resource(1u64);
+    let parent = world.spawn((U32(1), Str("parent")));
+    let child = world.spawn((U32(2),));
+    world.add_relation(child, ChildOf, parent).unwrap();
+
+    let mut ring = SnapshotRing::new(2);
+    ring.capture(0, &world);
+
+    *world.get_resource_mut::<u64>().unwrap() = 2;
+    world.despawn(parent).unwrap();
+    ring.capture(1, &world);
+    assert_eq!(ring.latest().map(|(tick, _)| tick), Some(1));
+
+    // Archetypes created after snapshot may have non-cloneable components.
+    let spawned = world.spawn((U32(3), Bool(true)));
+
+    assert!(world.is_alive(spawned));
+    assert!(!world.is_alive(child));
+
+    // Actions recorded before rollback are discarded.
+    world
+        .action_sender()
+        .closure(|world| world.insert_resource(3u64));
+
+    assert!(ring.rollback(0, &mut world));
+    assert_eq!(ring.len(), 1);
+
+    world.execute_received_actions();
+
+    assert_eq!(*world.expect_resource::<u64>(), 1);
+    assert!(!world.is_alive(spawned));
+    assert_eq!(
+        world.query_one_mut::<(&U32, &Str)>(parent),
+        Ok((&U32(1), &Str("parent")))
+    );
+    assert_eq!(world.query_one_mut::<&U32>(child), Ok(&U32(2)));
+    assert_eq!(
+        world.query_one_mut::<Related<ChildOf>>(parent),
+        Ok(&[child][..])
+    );
+    assert_eq!(world.query::<&U32>().iter().count(), 2);
+
+    // Id allocator is rewound too.
+    assert_eq!(world.spawn((U32(3),)), spawned);
+
+    // Relation hooks still work after restore.
+    world.despawn(parent).unwrap();
+    assert!(!world.is_alive(child));
+}
+
+#[test]
+fn clone_entity() {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    use crate::{action::ActionBuffer, world::CloneError};
+
+    #[derive(Clone, Debug, PartialEq, Component)]
+    #[edict(clone)]
+    struct Health(u32);
+
+    let mut world = World::new();
+    let parent = world.spawn(());
+    let template = world.spawn((Health(10), Bool(true)));
+    world.add_relation(template, ChildOf, parent).unwrap();
+    let child = world.spawn(());
+    world.add_relation(child, ChildOf, template).unwrap();
+
+    let clone = match world.clone_entity(template) {
+        Err(CloneError::NotCloneable { id, components }) => {
+            assert_eq!(components, [Bool::name()]);
+            id
+        }
+        result => panic!("Unexpected result {:?}", result),
+    };
+
+    assert_eq!(world.query_one_mut::<&Health>(clone), Ok(&Health(10)));
+    assert_eq!(world.has_component::<Bool>(clone), Ok(false));
+
+    // Clone is origin of the same relations but not a target.
+    assert_eq!(
+        world.query_one_mut::<Related<ChildOf>>(parent),
+        Ok(&[template, clone][..])
+    );
+    assert_eq!(
+        world.query_one_mut::<Related<ChildOf>>(template),
+        Ok(&[child][..])
+    );
+    assert!(world.query_one_mut::<Related<ChildOf>>(clone).is_err());
+
+    world.remove::<Bool>(template).unwrap();
+
+    let reported = Arc::new(AtomicBool::new(false));
+
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world);
+    let clone = actions.clone_entity(template);
+    actions.despawn(child);
+    let missing = actions.clone_entity_with_report(child, {
+        let reported = reported.clone();
+        move |err| {
+            assert_eq!(err, CloneError::NoSuchEntity);
+            reported.store(true, Ordering::Relaxed);
+        }
+    });
+    buffer.execute(&mut world);
+
+    assert_eq!(world.query_one_mut::<&Health>(clone), Ok(&Health(10)));
+    assert!(!world.is_alive(missing));
+    assert!(reported.load(Ordering::Relaxed));
+    assert_eq!(world.clone_entity(child), Err(CloneError::NoSuchEntity));
+
+    world.despawn(parent).unwrap();
+    assert!(!world.is_alive(clone));
+}
+
+#[test]
+fn prefab_instances() {
+    use crate::world::Instance;
+
+    let mut world = World::new();
+    let prefab = world.spawn_prefab((U32(1), Str("prop")));
+    let part = world.spawn_prefab((U32(2),));
+    world.add_relation(part, ChildOf, prefab).unwrap();
+    let plain = world.spawn((U32(3),));
+    world.add_relation(plain, ChildOf, prefab).unwrap();
+
+    // Prefab components are not visible to queries on prefab.
+    assert!(world.query_one_mut::<&U32>(prefab).is_err());
+
+    let a = world.instantiate(prefab).unwrap();
+    let b = world.instantiate(prefab).unwrap();
+
+    assert_eq!(world.query_one_mut::<&U32>(a), Ok(&U32(1)));
+    assert_eq!(world.query_one_mut::<&Str>(b), Ok(&Str("prop")));
+    assert_eq!(world.has_component::<U32>(a), Ok(false));
+    assert_eq!(
+        world.query_one_mut::<&Instance>(a).unwrap().prefab(),
+        prefab
+    );
+
+    // Only prefab children are instantiated.
+    let children = world.query_one_mut::<Related<ChildOf>>(a).unwrap().to_vec();
+    assert_eq!(children.len(), 1);
+    assert_eq!(world.query_one_mut::<&U32>(children[0]), Ok(&U32(2)));
+    assert!(world.query_one_mut::<&Str>(children[0]).is_err());
+    assert_eq!(world.query_one_mut::<Option<&Str>>(children[0]), Ok(None));
+
+    // Inherited values are not writable without override.
+    assert!(world.query_one_mut::<&mut U32>(a).is_err());
+
+    // Override only affects one instance.
+    world.override_component::<U32>(a).unwrap();
+    world.query_one_mut::<&mut U32>(a).unwrap().0 = 10;
+    world.insert(b, Str("own")).unwrap();
+
+    assert_eq!(world.query_one_mut::<&U32>(a), Ok(&U32(10)));
+    assert_eq!(world.query_one_mut::<&U32>(b), Ok(&U32(1)));
+    assert_eq!(world.query_one_mut::<&Str>(b), Ok(&Str("own")));
+
+    let mut values = world
+        .query::<(Entities, &U32, Option<&Str>)>()
+        .iter()
+        .map(|(e, u, s)| (e, u.0, s.copied()))
+        .collect::<Vec<_>>();
+    values.sort_by_key(|(e, u, _)| (*u, *e));
+    let b_child = world.query_one_mut::<Related<ChildOf>>(b).unwrap()[0];
+    assert_eq!(
+        values,
+        [
+            (b, 1, Some(Str("own"))),
+            (children[0], 2, None),
+            (b_child, 2, None),
+            (plain, 3, None),
+            (a, 10, Some(Str("prop"))),
+        ]
+    );
+
+    // Dropping override brings inherited value back.
+    world.drop::<Str>(b).unwrap();
+    assert_eq!(world.query_one_mut::<&Str>(b), Ok(&Str("prop")));
+
+    assert_eq!(
+        world.override_component::<Str>(children[0]),
+        Err(EntityError::MissingComponents)
+    );
+    assert_eq!(
+        world.instantiate(plain),
+        Err(EntityError::MissingComponents)
+    );
+
+    // Instances outlive the prefab.
+    world.despawn(prefab).unwrap();
+    assert_eq!(world.query_one_mut::<&Str>(a), Ok(&Str("prop")));
+}
+
+#[test]
+fn disabled_entities() {
+    use crate::{
+        action::ActionBuffer,
+        query::IncludeDisabled,
+        world::{Disabled, NoSuchEntity},
+    };
+
+    let mut world = World::new();
+    let a = world.spawn((U32(1),));
+    let b = world.spawn((U32(2),));
+    world.add_relation(b, ChildOf, a).unwrap();
+
+    world.set_enabled(b, false).unwrap();
+    assert_eq!(world.is_enabled(b), Ok(false));
+    assert_eq!(world.has_component::<U32>(b), Ok(true));
+
+    assert_eq!(world.query::<Entities>().iter().collect::<Vec<_>>(), [a]);
+    assert_eq!(
+        world.query_one_mut::<&U32>(b),
+        Err(QueryOneError::NotSatisfied)
+    );
+    assert_eq!(
+        world.query_one_mut::<(&U32, IncludeDisabled)>(b),
+        Ok((&U32(2), ()))
+    );
+    assert_eq!(
+        world
+            .query::<Entities>()
+            .with::<Disabled>()
+            .iter()
+            .collect::<Vec<_>>(),
+        [b]
+    );
+
+    let mut all = world
+        .query::<&U32>()
+        .include_disabled()
+        .iter()
+        .map(|u| u.0)
+        .collect::<Vec<_>>();
+    all.sort_unstable();
+    assert_eq!(all, [1, 2]);
+
+    // Relations are kept.
+    assert_eq!(world.query_one_mut::<Related<ChildOf>>(a), Ok(&[b][..]));
+
+    let mut buffer = ActionBuffer::new();
+    let mut actions = buffer.encoder(&world);
+    actions.set_enabled(a, false);
+    actions.set_enabled(b, true);
+    buffer.execute(&mut world);
+
+    assert_eq!(world.is_enabled(a), Ok(false));
+    assert_eq!(world.query_one_mut::<&U32>(b), Ok(&U32(2)));
+
+    // Owned relation still despawns disabled children.
+    world.set_enabled(b, false).unwrap();
+    world.despawn(a).unwrap();
+    assert!(!world.is_alive(b));
+    assert_eq!(world.set_enabled(b, true), Err(NoSuchEntity));
+}
+
+#[test]
+fn transfer_entities() {
+    use crate::{entity::EntityId, world::NoSuchEntity};
+
+    struct Link(EntityId);
+    impl Component for Link {
+        fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+            self.0 = mapper(self.0);
+        }
+    }
+
+    let mut side = World::new();
+    let mut main = World::new();
+    let existing = main.spawn((U32(0),));
+
+    let a = side.spawn((U32(1), Bool(true)));
+    let b = side.spawn((U32(2), Link(a)));
+    let c = side.spawn((U32(3),));
+    let d = side.spawn((U32(4), Link(c)));
+    let gone = side.spawn(());
+    side.despawn(gone).unwrap();
+    side.add_relation(b, ChildOf, a).unwrap();
+    side.add_relation(c, ChildOf, a).unwrap();
+
+    assert_eq!(side.transfer_batch([a, gone], &mut main), Err(NoSuchEntity));
+    assert!(side.is_alive(a));
+
+    let ids = side.transfer_batch([a, b, d], &mut main).unwrap();
+    let [a2, b2, d2] = ids[..] else {
+        panic!("Unexpected number of ids");
+    };
+
+    assert!(!side.is_alive(a));
+    assert!(!side.is_alive(b));
+    assert!(!side.is_alive(d));
+
+    // Child left behind is despawned with its owner.
+    assert!(!side.is_alive(c));
+
+    assert_eq!(main.query_one_mut::<&U32>(existing), Ok(&U32(0)));
+    assert_eq!(main.query_one_mut::<&U32>(a2), Ok(&U32(1)));
+    assert_eq!(main.query_one_mut::<&Bool>(a2), Ok(&Bool(true)));
+    assert_eq!(main.query_one_mut::<&Link>(b2).unwrap().0, a2);
+    assert_eq!(main.query_one_mut::<&Link>(d2).unwrap().0, c);
+    assert_eq!(main.query_one_mut::<Related<ChildOf>>(a2), Ok(&[b2][..]));
+
+    let e = main.transfer(a2, &mut side).unwrap();
+    assert_eq!(side.query_one_mut::<&U32>(e), Ok(&U32(1)));
+    assert!(!main.is_alive(b2));
+}
+
+#[test]
+fn transfer_notifies_worlds() {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{
+        entity::EntityId,
+        world::{Name, ObserverEvent, RejectedName},
+    };
+
+    struct Link(EntityId);
+    impl Component for Link {
+        fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+            self.0 = mapper(self.0);
+        }
+    }
+
+    let mut side = World::new();
+    let mut main = World::builder().with_unique_names().build();
+    let existing = main.spawn((Name::new("a"),));
+
+    let removed = Arc::new(AtomicUsize::new(0));
+    let inserted = Arc::new(AtomicUsize::new(0));
+
+    let counter = removed.clone();
+    side.observe::<U32, _, _>(ObserverEvent::Remove, (), move |_, _, _| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+    let counter = inserted.clone();
+    main.observe::<U32, _, _>(ObserverEvent::Insert, (), move |_, _, _| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+
+    // Archetypes of `a` and `c` are moved as a whole, `b` is moved alone.
+    let a = side.spawn((U32(1), Name::new("a")));
+    let b = side.spawn((U32(2), Name::new("b"), Bool(true)));
+    let c = side.spawn((U32(3), Link(a)));
+    let kept = side.spawn((U32(4), Name::new("kept"), Bool(false)));
+
+    let ids = side.transfer_batch([a, b, c], &mut main).unwrap();
+    let [a2, b2, c2] = ids[..] else {
+        panic!("Unexpected number of ids");
+    };
+
+    // Source forgets moved entities.
+    assert_eq!(removed.load(Ordering::Relaxed), 3);
+    assert_eq!(side.find_by_name("a"), None);
+    assert_eq!(side.find_by_name("b"), None);
+    assert_eq!(side.find_by_name("kept"), Some(kept));
+    assert_eq!(side.query::<&U32>().iter().count(), 1);
+
+    // Destination runs insert hooks and observers.
+    assert_eq!(inserted.load(Ordering::Relaxed), 3);
+    assert_eq!(main.find_by_name("a"), Some(existing));
+    assert_eq!(main.find_by_name("b"), Some(b2));
+    assert_eq!(
+        main.query_one_mut::<&RejectedName>(a2).unwrap().holder(),
+        existing
+    );
+
+    assert_eq!(main.query_one_mut::<&U32>(a2), Ok(&U32(1)));
+    assert_eq!(main.query_one_mut::<&Bool>(b2), Ok(&Bool(true)));
+    assert_eq!(main.query_one_mut::<&U32>(c2), Ok(&U32(3)));
+    assert_eq!(main.query_one_mut::<&Link>(c2).unwrap().0, a2);
+}
+
+#[test]
+fn world_append() {
+    use crate::{entity::EntityId, world::ResourceConflict};
+
+    struct Link(EntityId);
+    impl Component for Link {
+        fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+            self.0 = mapper(self.0);
+        }
+    }
+
+    let mut world = World::new();
+    let x = world.spawn((U32(0),));
+    world.insert_resource(Str("world"));
+
+    let mut level = World::new();
+    let a = level.spawn((U32(1), Bool(true)));
+    let b = level.spawn((U32(2), Link(a)));
+    let c = level.spawn(());
+    level.add_relation(b, ChildOf, a).unwrap();
+    level.set_enabled(c, false).unwrap();
+    level.insert_resource(Str("level"));
+    level.insert_resource(1u32);
+
+    // Ids collide.
+    assert_eq!(x, a);
+
+    let ids = world.append(level, ResourceConflict::Keep);
+    assert_eq!(ids.len(), 3);
+    let (a2, b2, c2) = (ids[&a], ids[&b], ids[&c]);
+    assert_ne!(a2, x);
+
+    assert_eq!(world.query_one_mut::<&U32>(x), Ok(&U32(0)));
+    assert_eq!(world.query_one_mut::<&U32>(a2), Ok(&U32(1)));
+    assert_eq!(world.query_one_mut::<&Bool>(a2), Ok(&Bool(true)));
+    assert_eq!(world.query_one_mut::<&Link>(b2).unwrap().0, a2);
+    assert_eq!(world.query_one_mut::<Related<ChildOf>>(a2), Ok(&[b2][..]));
+    assert_eq!(world.is_enabled(c2), Ok(false));
+
+    assert_eq!(*world.expect_resource::<Str>(), Str("world"));
+    assert_eq!(*world.expect_resource::<u32>(), 1);
+
+    // Relations of appended entities are fully functional.
+    world.despawn(a2).unwrap();
+    assert!(!world.is_alive(b2));
+
+    let d = world.spawn((U32(3),));
+    assert!(!ids.values().any(|&id| id == d));
+}
+
+#[test]
+fn value_index() {
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    struct Team(u32);
+    impl Component for Team {
+        fn clone_fn() -> Option<fn(&Self) -> Self> {
+            Some(Self::clone)
+        }
+    }
+
+    let mut world = World::new();
+    let a = world.spawn((Team(1),));
+    world.add_index::<Team>();
+
+    let b = world.spawn((Team(1),));
+    let c = world.spawn((Team(2),));
+
+    let mut ones = world.index::<Team>().get(&Team(1)).to_vec();
+    ones.sort_unstable();
+    assert_eq!(ones, [a, b]);
+    assert_eq!(world.index::<Team>().get_one(&Team(2)), Some(c));
+    assert!(!world.index::<Team>().contains(&Team(3)));
+
+    let snapshot = world.snapshot();
+
+    // Replace.
+    world.insert(a, Team(2)).unwrap();
+    // Modification through query.
+    world.query_one_mut::<&mut Team>(b).unwrap().0 = 3;
+    // Drop and despawn.
+    world.drop::<Team>(c).unwrap();
+    let d = world.spawn((Team(2),));
+    world.despawn(d).unwrap();
+
+    assert!(world.index::<Team>().get(&Team(1)).is_empty());
+    assert_eq!(world.index::<Team>().get(&Team(2)), [a]);
+    assert_eq!(world.index::<Team>().get(&Team(3)), [b]);
+
+    // Modified entity moves to another archetype before the next access.
+    world.query_one_mut::<&mut Team>(b).unwrap().0 = 4;
+    world.insert(b, U32(0)).unwrap();
+    assert!(world.index::<Team>().get(&Team(3)).is_empty());
+    assert_eq!(world.index::<Team>().get(&Team(4)), [b]);
+
+    // Disabled entities are found.
+    world.set_enabled(b, false).unwrap();
+    assert_eq!(world.index::<Team>().get(&Team(4)), [b]);
+    world.set_enabled(b, true).unwrap();
+
+    // Modifications under mutable borrow are picked up after it ends.
+    let mut query = world.query_one::<&mut Team>(a).unwrap();
+    query.get().unwrap().0 = 12;
+    assert!(world.index::<Team>().get(&Team(12)).is_empty());
+    drop(query);
+    assert_eq!(world.index::<Team>().get(&Team(12)), [a]);
+
+    world.restore(&snapshot);
+    let mut ones = world.index::<Team>().get(&Team(1)).to_vec();
+    ones.sort_unstable();
+    assert_eq!(ones, [a, b]);
+    assert_eq!(world.index::<Team>().get(&Team(2)), [c]);
+
+    // Despawned entities leave no entries behind.
+    world.despawn(a).unwrap();
+    world.despawn(b).unwrap();
+    world.despawn(c).unwrap();
+    assert!(!world.index::<Team>().contains(&Team(1)));
+    assert!(!world.index::<Team>().contains(&Team(2)));
+}
+
+#[test]
+fn entity_names() {
+    use crate::{
+        entity::EntityId,
+        world::{Name, NameError, RejectedName},
+    };
+
+    let mut world = World::new();
+    let a = world.spawn((Name::new("a"),));
+    let b = world.spawn(());
+    world.set_name(b, "a").unwrap();
+
+    let mut all = world.find_all_by_name("a");
+    all.sort_unstable();
+    assert_eq!(all, [a, b]);
+
+    world.despawn(a).unwrap();
+    assert_eq!(world.find_by_name("a"), Some(b));
+
+    world.set_name(b, "b").unwrap();
+    assert_eq!(world.find_by_name("a"), None);
+    assert_eq!(world.find_by_name("b"), Some(b));
+
+    world.drop::<Name>(b).unwrap();
+    assert_eq!(world.find_by_name("b"), None);
+    assert_eq!(world.entity_display(b).to_string(), b.to_string());
+
+    let mut world = World::builder().with_unique_names().build();
+    let a = world.spawn(());
+    let b = world.spawn(());
+    let gone = world.spawn(());
+    world.despawn(gone).unwrap();
+    world.set_name(a, "a").unwrap();
+    world.set_name(a, "a").unwrap();
+    assert_eq!(world.set_name(b, "a"), Err(NameError::Taken(a)));
+    assert_eq!(world.set_name(gone, "c"), Err(NameError::NoSuchEntity));
+
+    world.despawn(a).unwrap();
+    world.set_name(b, "a").unwrap();
+    assert_eq!(world.find_by_name("a"), Some(b));
+    assert_eq!(
+        alloc::format!("{:?}", world.entity_display(b)),
+        alloc::format!("EntityId {{ value: {}, name: \"a\" }}", b.bits())
+    );
+
+    // Direct insertion of a taken name is rejected.
+    let c = world.spawn((Name::new("a"),));
+    let d = world.spawn((Name::new("d"),));
+    world.insert(d, Name::new("a")).unwrap();
+    assert_eq!(world.has_component::<Name>(c), Ok(false));
+    assert_eq!(world.has_component::<Name>(d), Ok(false));
+    assert_eq!(world.find_all_by_name("a"), [b]);
+
+    let rejected = world.query_one_mut::<&RejectedName>(d).unwrap();
+    assert_eq!(rejected.name().as_str(), "a");
+    assert_eq!(rejected.holder(), b);
+
+    // First entity in a batch keeps duplicate name.
+    let batch: Vec<EntityId> = world
+        .spawn_batch([(Name::new("e"),), (Name::new("e"),), (Name::new("e"),)])
+        .collect();
+    assert_eq!(world.find_all_by_name("e"), [batch[0]]);
+    for &id in &batch[1..] {
+        assert_eq!(
+            world.query_one_mut::<&RejectedName>(id).unwrap().holder(),
+            batch[0]
+        );
+    }
+
+    let e = world.clone_entity(b).unwrap();
+    assert_eq!(world.has_component::<Name>(e), Ok(false));
+
+    // Index is locked for each lookup under shared borrows.
+    let world = &world;
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..100 {
+                    assert_eq!(world.find_by_name("a"), Some(b));
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn insert_hook() {
+    use crate::{
+        action::{ActionBuffer, ActionEncoder},
+        entity::EntityId,
+    };
+
+    #[derive(Component)]
+    #[edict(on_insert = register_body)]
+    struct Body;
+
+    struct Registered;
+    impl Component for Registered {}
+
+    fn register_body(_: &mut Body, id: EntityId, mut encoder: ActionEncoder) {
+        encoder.insert(id, Registered);
+    }
+
+    let mut builder = World::builder();
+    builder
+        .register_external::<u32>()
+        .on_insert_fn(|_: &mut u32, id, mut encoder| encoder.insert(id, Registered));
+    let mut world = builder.build();
+
+    let a = world.spawn((Body,));
+    let b = world.spawn(());
+    world.insert(b, Body).unwrap();
+    let c = world.spawn((U32(1),));
+    world.insert_bundle(c, (Body, U32(2))).unwrap();
+    let batch: Vec<EntityId> = world.spawn_batch([(Body,), (Body,)]).collect();
+    let d = world.spawn_external((1u32,));
+
+    for id in [a, b, c, d].into_iter().chain(batch) {
+        assert_eq!(world.has_component::<Registered>(id), Ok(true));
+    }
+    let plain = world.spawn((U32(0),));
+    assert_eq!(world.has_component::<Registered>(plain), Ok(false));
+
+    // Replacing existing component does not execute insert hook.
+    world.drop::<Registered>(a).unwrap();
+    world.insert(a, Body).unwrap();
+    world.insert_bundle(a, (Body, U32(3))).unwrap();
+    assert_eq!(world.has_component::<Registered>(a), Ok(false));
+
+    let mut buffer = ActionBuffer::new();
+    let mut encoder = buffer.encoder(&world);
+    let e = encoder.spawn((Body,));
+    buffer.execute(&mut world);
+    assert_eq!(world.has_component::<Registered>(e), Ok(true));
+}
+
+#[test]
+fn required_components() {
+    use crate::action::ActionBuffer;
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Component)]
+    struct Position(u32);
+
+    #[derive(Component)]
+    #[edict(require(Position))]
+    struct Velocity;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Component)]
+    struct Mass(u32);
+
+    let mut builder = World::builder();
+    builder
+        .register_component::<Velocity>()
+        .require_with(|| Mass(1));
+    let mut world = builder.build();
+
+    let a = world.spawn((Velocity,));
+    assert_eq!(world.query_one_mut::<&Position>(a), Ok(&Position(0)));
+    assert_eq!(world.query_one_mut::<&Mass>(a), Ok(&Mass(1)));
+
+    // Present components are kept.
+    let b = world.spawn((Velocity, Position(5)));
+    assert_eq!(world.query_one_mut::<&Position>(b), Ok(&Position(5)));
+
+    let c = world.spawn((Mass(3),));
+    world.insert(c, Velocity).unwrap();
+    assert_eq!(world.query_one_mut::<&Position>(c), Ok(&Position(0)));
+    assert_eq!(world.query_one_mut::<&Mass>(c), Ok(&Mass(3)));
+
+    let d = world.spawn(());
+    world.insert_bundle(d, (Velocity, U32(0))).unwrap();
+    assert_eq!(world.has_component::<Position>(d), Ok(true));
+
+    let mut buffer = ActionBuffer::new();
+    let mut encoder = buffer.encoder(&world);
+    let e = encoder.spawn((Velocity,));
+    buffer.execute(&mut world);
+    assert_eq!(world.has_component::<Position>(e), Ok(true));
+    assert_eq!(world.has_component::<Mass>(e), Ok(true));
+}
+
+#[test]
+fn observers() {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{action::ActionBuffer, world::ObserverEvent};
+
+    #[derive(Component)]
+    struct Alive;
+
+    #[derive(Component)]
+    struct Removed;
+
+    let mut world = World::new();
+
+    let inserted = Arc::new(AtomicUsize::new(0));
+    let replaced = Arc::new(AtomicUsize::new(0));
+    let removed = Arc::new(AtomicUsize::new(0));
+
+    let counter = inserted.clone();
+    let on_insert = world.observe::<U32, _, _>(ObserverEvent::Insert, (), move |_, _, _| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+
+    let counter = replaced.clone();
+    world.observe::<U32, _, _>(
+        ObserverEvent::Replace,
+        With::<Str>::query(),
+        move |_, _, _| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        },
+    );
+
+    let counter = removed.clone();
+    world.observe::<U32, _, _>(
+        ObserverEvent::Remove,
+        With::<Alive>::query(),
+        move |id, _, mut encoder| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            encoder.insert(id, Removed);
+        },
+    );
+
+    let a = world.spawn((U32(0), Str("a"), Alive));
+    let b = world.spawn((U32(0),));
+    assert_eq!(inserted.load(Ordering::Relaxed), 2);
+
+    // Observer sees the world after the operation.
+    world.insert(a, U32(1)).unwrap();
+    world.insert(b, U32(1)).unwrap();
+    assert_eq!(replaced.load(Ordering::Relaxed), 1);
+
+    world.drop::<U32>(a).unwrap();
+    assert_eq!(world.has_component::<Removed>(a), Ok(true));
+
+    assert_eq!(world.remove::<U32>(b), Ok(U32(1)));
+    assert_eq!(world.has_component::<Removed>(b), Ok(false));
+    assert_eq!(removed.load(Ordering::Relaxed), 1);
+
+    assert!(world.detach_observer(on_insert));
+    assert!(!world.detach_observer(on_insert));
+    world.insert(a, U32(2)).unwrap();
+    assert_eq!(inserted.load(Ordering::Relaxed), 2);
+
+    // Filter is checked before entity is despawned.
+    let alive = world.spawn((U32(0), Alive));
+    let other = world.spawn((U32(0),));
+    world.despawn(other).unwrap();
+    assert_eq!(removed.load(Ordering::Relaxed), 1);
+    world.despawn(alive).unwrap();
+    assert_eq!(removed.load(Ordering::Relaxed), 2);
+
+    // Observers can be attached and detached with encoder.
+    let mut buffer = ActionBuffer::new();
+    let mut encoder = buffer.encoder(&world);
+    let counter = inserted.clone();
+    let observer = encoder.observe::<Bool, _, _>(ObserverEvent::Insert, (), move |_, _, _| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+    encoder.spawn((Bool(true),));
+    buffer.execute(&mut world);
+    assert_eq!(inserted.load(Ordering::Relaxed), 3);
+
+    let mut encoder = buffer.encoder(&world);
+    encoder.detach_observer(observer);
+    encoder.spawn((Bool(false),));
+    buffer.execute(&mut world);
+    assert_eq!(inserted.load(Ordering::Relaxed), 3);
+
+    #[derive(Clone, Copy)]
+    struct Likes;
+    impl Relation for Likes {}
+
+    let related = Arc::new(AtomicUsize::new(0));
+    let unrelated = Arc::new(AtomicUsize::new(0));
+
+    let counter = related.clone();
+    world.observe_relation::<Likes, _, _>(ObserverEvent::Insert, (), move |_, _, _| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+
+    let counter = unrelated.clone();
+    world.observe_relation::<Likes, _, _>(ObserverEvent::Remove, (), move |_, _, _| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+
+    let c = world.spawn(());
+    world.add_relation(a, Likes, b).unwrap();
+    world.add_relation(a, Likes, c).unwrap();
+    assert_eq!(related.load(Ordering::Relaxed), 1);
+
+    world.remove_relation::<Likes>(a, b).unwrap();
+    assert_eq!(unrelated.load(Ordering::Relaxed), 0);
+    world.remove_relation::<Likes>(a, c).unwrap();
+    assert_eq!(unrelated.load(Ordering::Relaxed), 1);
+
+    // Observers belong to the world even if component info is shared.
+    let mut other = World::new();
+    let moved = world.spawn((U32(0),));
+    let moved = world.transfer(moved, &mut other).unwrap();
+
+    let other_inserted = Arc::new(AtomicUsize::new(0));
+    let counter = other_inserted.clone();
+    let observer = other.observe::<U32, _, _>(ObserverEvent::Insert, (), move |_, _, _| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+
+    let counter = inserted.clone();
+    world.observe::<U32, _, _>(ObserverEvent::Insert, (), move |_, _, _| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+
+    other.spawn((U32(0),));
+    assert_eq!(other_inserted.load(Ordering::Relaxed), 1);
+    assert_eq!(inserted.load(Ordering::Relaxed), 3);
+
+    assert!(!world.detach_observer(observer));
+    assert!(other.detach_observer(observer));
+    world.spawn((U32(0),));
+    assert_eq!(inserted.load(Ordering::Relaxed), 4);
+    other.drop::<U32>(moved).unwrap();
+    other.insert(moved, U32(1)).unwrap();
+    assert_eq!(other_inserted.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,136 @@
+//! Merging whole worlds.
+
+use alloc::vec::Vec;
+use core::ptr::NonNull;
+
+use hashbrown::HashMap;
+
+use crate::{archetype::Archetype, entity::EntityId};
+
+use super::World;
+
+/// Defines which resource is kept by [`World::append`]
+/// when both worlds contain resource of the same type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceConflict {
+    /// Resource of this world is kept.
+    /// Resource of appended world is dropped.
+    Keep,
+
+    /// Resource of this world is replaced with resource of appended world.
+    Replace,
+}
+
+impl World {
+    /// Moves all entities and resources of `other` world into this world.
+    ///
+    /// Components are moved column by column without cloning.
+    /// Entities get new ids allocated by this world, so ids never collide.
+    /// Returns map from ids in `other` world to ids in this world.
+    /// Ids stored in components and relations are rewritten with [`Component::map_entities`].
+    ///
+    /// Resources present in both worlds are merged according to `conflict`.
+    ///
+    /// Unlike replaying entities through [`ActionSender`],
+    /// this does not invoke any hooks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{relation::{ChildOf, Related}, world::{ResourceConflict, World}, ExampleComponent};
+    /// let mut level = World::new();
+    /// let parent = level.spawn((ExampleComponent,));
+    /// let child = level.spawn(());
+    /// level.add_relation(child, ChildOf, parent).unwrap();
+    /// level.insert_resource(42u32);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((ExampleComponent,));
+    /// world.insert_resource(1u32);
+    ///
+    /// let ids = world.append(level, ResourceConflict::Replace);
+    ///
+    /// assert_eq!(world.query::<&ExampleComponent>().iter().count(), 2);
+    /// assert_eq!(
+    ///     world.query_one_mut::<Related<ChildOf>>(ids[&parent]).unwrap(),
+    ///     &[ids[&child]],
+    /// );
+    /// assert_eq!(*world.expect_resource::<u32>(), 42);
+    /// ```
+    ///
+    /// [`Component::map_entities`]: crate::component::Component::map_entities
+    /// [`ActionSender`]: crate::action::ActionSender
+    pub fn append(
+        &mut self,
+        mut other: World,
+        conflict: ResourceConflict,
+    ) -> HashMap<EntityId, EntityId> {
+        self.maintenance();
+        other.execute_received_actions();
+        other.maintenance();
+
+        let mut mapping = HashMap::new();
+        for archetype in other.archetypes.iter() {
+            for &id in archetype.entities() {
+                mapping.insert(id, self.entities.alloc_mut());
+            }
+        }
+
+        let epoch = self.epoch.next_mut();
+
+        for src in other.archetypes.iter_mut() {
+            if src.is_empty() {
+                continue;
+            }
+
+            let archetype_idx = self.matching_archetype(src);
+            let ids: Vec<EntityId> = src.entities().iter().map(|id| mapping[id]).collect();
+
+            let dst = &mut self.archetypes[archetype_idx as usize];
+            let start = dst.append(src, &ids, epoch);
+
+            for (idx, &id) in ids.iter().enumerate() {
+                self.entities.spawn_at(id);
+                self.entities
+                    .set_location(id, archetype_idx, start + idx as u32);
+            }
+
+            for info in dst.infos() {
+                let component = dst.component(info.id()).unwrap();
+                let size = info.layout().size();
+                let ptr = unsafe { component.data_mut().ptr };
+
+                for idx in start as usize..start as usize + ids.len() {
+                    unsafe {
+                        let ptr = NonNull::new_unchecked(ptr.as_ptr().add(idx * size));
+                        info.map_entities(ptr, &mut |id| mapping.get(&id).copied().unwrap_or(id));
+                    }
+                }
+            }
+        }
+
+        self.res
+            .append(other.res, conflict == ResourceConflict::Replace);
+
+        mapping
+    }
+
+    /// Returns index of archetype with the same set of components
+    /// as archetype of another world.
+    /// Registers components and adds archetype if there is none.
+    pub(super) fn matching_archetype(&mut self, src: &Archetype) -> u32 {
+        match self.archetypes.iter().position(|a| a.matches(src.ids())) {
+            Some(idx) => idx as u32,
+            None => {
+                for info in src.infos() {
+                    self.registry.get_or_register_raw(info.clone());
+                }
+
+                let registry = &self.registry;
+                self.archetypes.add_with(|_| {
+                    Archetype::new(src.ids().map(|id| registry.get_info(id).unwrap()))
+                })
+            }
+        }
+    }
+}
@@ -1,14 +1,22 @@
+use alloc::vec::Vec;
+use core::any::TypeId;
+
+use hashbrown::HashMap;
+
 use crate::{
     action::{ActionBuffer, ActionChannel},
     component::{
         Component, ComponentInfo, ComponentInfoRef, ComponentRegistry, ExternalDropHook,
-        ExternalSetHook,
+        ExternalInsertHook, ExternalSetHook,
     },
     entity::{EntitySet, IdRangeAllocator},
+    hash::NoOpHasherBuilder,
     res::Res,
 };
 
-use super::{ArchetypeSet, Edges, EpochCounter, World};
+use super::{
+    observer::Observers, snapshot::ResourceClone, ArchetypeSet, Edges, EpochCounter, Name, World,
+};
 
 /// Builder for [`World`] value.
 ///
@@ -18,6 +26,9 @@ use super::{ArchetypeSet, Edges, EpochCounter, World};
 pub struct WorldBuilder {
     registry: ComponentRegistry,
     range_alloc: Option<Box<dyn IdRangeAllocator>>,
+    tombstones: bool,
+    unique_names: bool,
+    resource_clones: Vec<ResourceClone>,
 }
 
 impl WorldBuilder {
@@ -27,6 +38,9 @@ impl WorldBuilder {
         WorldBuilder {
             registry: ComponentRegistry::new(),
             range_alloc: None,
+            tombstones: false,
+            unique_names: false,
+            resource_clones: Vec::new(),
         }
     }
 
@@ -38,7 +52,7 @@ impl WorldBuilder {
             Some(range_alloc) => EntitySet::with_allocator(range_alloc),
         };
 
-        World {
+        let mut world = World {
             epoch: EpochCounter::new(),
             entities,
             archetypes: ArchetypeSet::new(),
@@ -47,7 +61,19 @@ impl WorldBuilder {
             registry: self.registry,
             action_buffer: Some(ActionBuffer::new()),
             action_channel: ActionChannel::new(),
-        }
+            tombstones: if self.tombstones {
+                Some(Vec::new())
+            } else {
+                None
+            },
+            resource_clones: self.resource_clones,
+            indices: HashMap::with_hasher(NoOpHasherBuilder),
+            unique_names: self.unique_names,
+            observers: Observers::new(),
+        };
+
+        world.add_index::<Name>();
+        world
     }
 
     /// Registers new component type and allows modifying it.
@@ -66,7 +92,7 @@ impl WorldBuilder {
     /// Registers new component type and allows modifying it.
     pub fn register_external<T>(
         &mut self,
-    ) -> ComponentInfoRef<'_, T, ExternalDropHook, ExternalSetHook>
+    ) -> ComponentInfoRef<'_, T, ExternalDropHook, ExternalSetHook, ExternalInsertHook>
     where
         T: 'static,
     {
@@ -84,4 +110,42 @@ impl WorldBuilder {
         self.range_alloc = Some(range_alloc);
         self
     }
+
+    /// Enables recording of despawn tombstones.
+    ///
+    /// [`World`] will remember ids of despawned entities with epochs of despawning.
+    /// Incremental dumps use them to delete entities despawned after dumped epoch.
+    /// Recorded tombstones are kept until [`World::trim_tombstones`] is called.
+    pub fn with_tombstones(mut self) -> Self {
+        self.tombstones = true;
+        self
+    }
+
+    /// Makes entity names unique.
+    ///
+    /// [`World::set_name`] will reject names that are already used by other entities.
+    pub fn with_unique_names(mut self) -> Self {
+        self.unique_names = true;
+        self
+    }
+
+    /// Registers resource type to be captured in [`WorldSnapshot`]s.
+    ///
+    /// [`World::restore`] replaces registered resources with captured clones.
+    /// Resources that are not registered are left untouched.
+    ///
+    /// [`WorldSnapshot`]: super::WorldSnapshot
+    pub fn with_snapshot_resource<T>(mut self) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        if !self
+            .resource_clones
+            .iter()
+            .any(|r| r.id() == TypeId::of::<T>())
+        {
+            self.resource_clones.push(ResourceClone::of::<T>());
+        }
+        self
+    }
 }
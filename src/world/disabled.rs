@@ -0,0 +1,89 @@
+//! Disabled entities.
+
+use core::any::TypeId;
+
+use crate::{action::ActionBuffer, component::Component, entity::EntityId};
+
+use super::{EntityError, NoSuchEntity, World};
+
+/// Marker component of disabled entities.
+///
+/// Queries skip entities with this component unless they include
+/// [`IncludeDisabled`] or `With<Disabled>`.
+///
+/// [`IncludeDisabled`]: crate::query::IncludeDisabled
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Disabled;
+
+impl Component for Disabled {
+    #[inline]
+    fn clone_fn() -> Option<fn(&Self) -> Self> {
+        Some(Self::clone)
+    }
+}
+
+impl World {
+    /// Enables or disables entity.
+    ///
+    /// Disabled entity keeps all its components and relations,
+    /// but queries skip it unless they include [`IncludeDisabled`].
+    /// Tasks of disabled entity are not polled by [`task_system`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let entity = world.spawn((ExampleComponent,));
+    ///
+    /// world.set_enabled(entity, false).unwrap();
+    /// assert!(world.query_one_mut::<&ExampleComponent>(entity).is_err());
+    ///
+    /// world.set_enabled(entity, true).unwrap();
+    /// assert!(world.query_one_mut::<&ExampleComponent>(entity).is_ok());
+    /// ```
+    ///
+    /// [`IncludeDisabled`]: crate::query::IncludeDisabled
+    /// [`task_system`]: crate::task::task_system
+    #[inline]
+    pub fn set_enabled(&mut self, id: EntityId, enabled: bool) -> Result<(), NoSuchEntity> {
+        if self.is_enabled(id)? == enabled {
+            return Ok(());
+        }
+
+        if enabled {
+            match self.drop::<Disabled>(id) {
+                Err(EntityError::NoSuchEntity) => Err(NoSuchEntity),
+                _ => Ok(()),
+            }
+        } else {
+            self.insert(id, Disabled)
+        }
+    }
+
+    pub(crate) fn set_enabled_with_buffer(
+        &mut self,
+        id: EntityId,
+        enabled: bool,
+        buffer: &mut ActionBuffer,
+    ) -> Result<(), NoSuchEntity> {
+        if self.is_enabled(id)? == enabled {
+            return Ok(());
+        }
+
+        if enabled {
+            match self.drop_erased_with_buffer(id, TypeId::of::<Disabled>(), buffer) {
+                Err(EntityError::NoSuchEntity) => Err(NoSuchEntity),
+                _ => Ok(()),
+            }
+        } else {
+            self.insert_with_buffer(id, Disabled, buffer)
+        }
+    }
+
+    /// Returns `true` if entity is not disabled.
+    #[inline]
+    pub fn is_enabled(&self, id: EntityId) -> Result<bool, NoSuchEntity> {
+        Ok(!self.has_component::<Disabled>(id)?)
+    }
+}
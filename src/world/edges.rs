@@ -188,7 +188,10 @@ impl Edges {
             cold();
             match archetypes.iter().position(|a| {
                 bundle.with_ids(|ids| {
-                    let ids = archetypes[src as usize].ids().chain(ids.iter().copied());
+                    let ids = archetypes[src as usize]
+                        .ids()
+                        .filter(|aid| ids.iter().all(|id| *id != *aid))
+                        .chain(ids.iter().copied());
                     a.matches(ids)
                 })
             }) {
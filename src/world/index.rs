@@ -0,0 +1,303 @@
+//! Hash indices on component values.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    any::{type_name, Any, TypeId},
+    borrow::Borrow,
+    hash::Hash,
+};
+
+use hashbrown::HashMap;
+use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
+
+use crate::{
+    archetype::{chunk_idx, Archetype, ArchetypeComponent, CHUNK_LEN_USIZE},
+    component::Component,
+    entity::EntityId,
+    epoch::EpochId,
+    query::Access,
+};
+
+use super::World;
+
+/// Type-erased index stored in the world.
+pub(crate) trait AnyIndex: Send + Sync + 'static {
+    /// Updates entry of the entity at `idx` in the archetype
+    /// after its component is inserted or replaced.
+    ///
+    /// # Safety
+    ///
+    /// Archetype must contain indexed component
+    /// and it must not be borrowed mutably.
+    unsafe fn insert(&mut self, archetype: &Archetype, idx: u32);
+
+    /// Removes entry of the entity before its component is removed.
+    fn remove(&mut self, id: EntityId);
+
+    /// Forgets all entries and indexes all components in archetypes.
+    fn rebuild(&mut self, archetypes: &[Archetype]);
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// Index from component values to entities.
+struct ValueIndex<T> {
+    /// Epoch of the component in each archetype
+    /// up to which modifications through queries are synchronized.
+    ///
+    /// Writes always bump epoch of the component in archetype,
+    /// so modifications after synchronization have later epochs.
+    synced: Vec<EpochId>,
+
+    /// Indexed value of each entity and its position in `entities`.
+    values: HashMap<EntityId, (T, usize)>,
+
+    /// Entities with each value.
+    entities: HashMap<T, Vec<EntityId>>,
+}
+
+impl<T> AnyIndex for ValueIndex<T>
+where
+    T: Component + Hash + Eq + Clone + Send + Sync,
+{
+    unsafe fn insert(&mut self, archetype: &Archetype, idx: u32) {
+        let component = unsafe { archetype.component(TypeId::of::<T>()).unwrap_unchecked() };
+        let value = unsafe { &*component.data().ptr.cast::<T>().as_ptr().add(idx as usize) };
+        self.update(archetype.entities()[idx as usize], value);
+    }
+
+    fn remove(&mut self, id: EntityId) {
+        let Some((value, pos)) = self.values.remove(&id) else {
+            return;
+        };
+
+        let ids = self.entities.get_mut(&value).unwrap();
+        ids.swap_remove(pos);
+        if let Some(&moved) = ids.get(pos) {
+            self.values.get_mut(&moved).unwrap().1 = pos;
+        }
+        if ids.is_empty() {
+            self.entities.remove(&value);
+        }
+    }
+
+    fn rebuild(&mut self, archetypes: &[Archetype]) {
+        self.synced.clear();
+        self.values.clear();
+        self.entities.clear();
+        self.sync(archetypes);
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Releases shared borrow of the component on drop.
+struct ReleaseRead<'a>(&'a ArchetypeComponent);
+
+impl Drop for ReleaseRead<'_> {
+    fn drop(&mut self) {
+        unsafe { self.0.release(Access::Read) }
+    }
+}
+
+impl<T> ValueIndex<T>
+where
+    T: Component + Hash + Eq + Clone + Send + Sync,
+{
+    fn new() -> Self {
+        ValueIndex {
+            synced: Vec::new(),
+            values: HashMap::new(),
+            entities: HashMap::new(),
+        }
+    }
+
+    /// Sets indexed value of the entity.
+    fn update(&mut self, id: EntityId, value: &T) {
+        match self.values.get(&id) {
+            Some((old, _)) if old == value => return,
+            Some(_) => self.remove(id),
+            None => {}
+        }
+
+        let ids = self.entities.entry(value.clone()).or_default();
+        self.values.insert(id, (value.clone(), ids.len()));
+        ids.push(id);
+    }
+
+    /// Updates entries of entities which components were modified since last sync.
+    ///
+    /// Archetypes where component is borrowed mutably are skipped
+    /// and synchronized on later access.
+    fn sync(&mut self, archetypes: &[Archetype]) {
+        self.synced.resize(archetypes.len(), EpochId::start());
+
+        for (archetype_idx, archetype) in archetypes.iter().enumerate() {
+            let Some(component) = archetype.component(TypeId::of::<T>()) else {
+                continue;
+            };
+
+            if !unsafe { component.borrow(Access::Read) } {
+                continue;
+            }
+            let _release = ReleaseRead(component);
+
+            let synced = self.synced[archetype_idx];
+            let data = unsafe { component.data() };
+            if !data.epoch.after(synced) {
+                continue;
+            }
+
+            let ptr = data.ptr.cast::<T>();
+            let len = archetype.len();
+            let mut start = 0;
+
+            while start < len {
+                let chunk_idx = chunk_idx(start);
+                let end = len.min(start + CHUNK_LEN_USIZE);
+
+                if data.chunk_epochs[chunk_idx].after(synced) {
+                    for idx in start..end {
+                        if data.entity_epochs[idx].after(synced) {
+                            let value = unsafe { &*ptr.as_ptr().add(idx) };
+                            self.update(archetype.entities()[idx], value);
+                        }
+                    }
+                }
+
+                start = end;
+            }
+
+            self.synced[archetype_idx] = data.epoch;
+        }
+    }
+}
+
+/// Access to hash index of component values.
+/// Produced by [`World::index`].
+///
+/// Index is locked while this value is alive.
+pub struct Index<'a, T> {
+    index: MappedMutexGuard<'a, ValueIndex<T>>,
+}
+
+impl<T> Index<'_, T>
+where
+    T: Component + Hash + Eq + Clone + Sync,
+{
+    /// Returns entities with component equal to specified value.
+    #[inline]
+    pub fn get<Q>(&self, value: &Q) -> &[EntityId]
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.entities.get(value).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns an entity with component equal to specified value.
+    /// Returns `None` if there is no such entity.
+    #[inline]
+    pub fn get_one<Q>(&self, value: &Q) -> Option<EntityId>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(value).first().copied()
+    }
+
+    /// Returns `true` if there is an entity with component equal to specified value.
+    #[inline]
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.entities.contains_key(value)
+    }
+}
+
+impl World {
+    /// Adds hash index on values of component `T`.
+    /// Does nothing if index is already added.
+    ///
+    /// Index is updated when components are inserted, replaced and removed,
+    /// including despawning of entities.
+    /// Modifications through [`Write<T>`] queries are picked up
+    /// by [`World::index`] using epochs of the component,
+    /// visiting only chunks and entities modified since previous access.
+    /// Own components of disabled entities are indexed too,
+    /// while values inherited from prefabs are not.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Clone, Component, PartialEq, Eq, Hash)]
+    /// struct PlayerId(u32);
+    ///
+    /// let mut world = World::new();
+    /// world.add_index::<PlayerId>();
+    ///
+    /// let player = world.spawn((PlayerId(7),));
+    /// assert_eq!(world.index::<PlayerId>().get_one(&PlayerId(7)), Some(player));
+    ///
+    /// world.query_one_mut::<&mut PlayerId>(player).unwrap().0 = 8;
+    /// assert!(world.index::<PlayerId>().get(&PlayerId(7)).is_empty());
+    /// assert_eq!(world.index::<PlayerId>().get(&PlayerId(8)), [player]);
+    /// ```
+    ///
+    /// [`Write<T>`]: crate::query::Write
+    pub fn add_index<T>(&mut self)
+    where
+        T: Component + Hash + Eq + Clone + Send + Sync,
+    {
+        if self.indices.contains_key(&TypeId::of::<T>()) {
+            return;
+        }
+
+        let mut index = ValueIndex::<T>::new();
+        index.rebuild(&self.archetypes);
+        self.indices
+            .insert(TypeId::of::<T>(), Mutex::new(Box::new(index)));
+    }
+
+    /// Returns hash index on values of component `T`.
+    ///
+    /// Checks epoch of `T` in each archetype that has it
+    /// to pick up modifications since previous access.
+    /// Archetypes where `T` is borrowed mutably at the moment
+    /// are skipped and picked up by a later access.
+    ///
+    /// Index is locked until returned value is dropped.
+    /// Accessing the same index again meanwhile blocks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if index is not added with [`World::add_index`].
+    #[track_caller]
+    pub fn index<T>(&self) -> Index<'_, T>
+    where
+        T: Component + Hash + Eq + Clone + Send + Sync,
+    {
+        let Some(index) = self.indices.get(&TypeId::of::<T>()) else {
+            panic!("Index of `{}` is not added", type_name::<T>());
+        };
+
+        let mut index = MutexGuard::map(index.lock(), |index| {
+            index.as_any_mut().downcast_mut::<ValueIndex<T>>().unwrap()
+        });
+        index.sync(&self.archetypes);
+
+        Index { index }
+    }
+
+    /// Rebuilds all indices from components in the world.
+    pub(crate) fn rebuild_indices(&mut self) {
+        for index in self.indices.values_mut() {
+            index.get_mut().rebuild(&self.archetypes);
+        }
+    }
+}
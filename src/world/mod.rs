@@ -1,6 +1,6 @@
 //! Self-contained ECS [`World`].
 
-use alloc::{borrow::ToOwned, vec, vec::Vec};
+use alloc::{borrow::ToOwned, boxed::Box, vec, vec::Vec};
 use core::{
     any::{type_name, TypeId},
     cell::Cell,
@@ -11,36 +11,64 @@ use core::{
     iter::FusedIterator,
     marker::PhantomData,
     ops::{Deref, DerefMut},
+    ptr::NonNull,
     sync::atomic::{AtomicU64, Ordering},
 };
 
 use atomicell::{Ref, RefMut};
+use hashbrown::{HashMap, HashSet};
+use parking_lot::Mutex;
+use smallvec::SmallVec;
 
 use crate::{
     action::{ActionBuffer, ActionChannel, ActionEncoder, ActionSender},
     archetype::{chunk_idx, Archetype},
     bundle::{
         Bundle, BundleDesc, ComponentBundle, ComponentBundleDesc, DynamicBundle,
-        DynamicComponentBundle,
+        DynamicComponentBundle, EntityBuilder,
     },
-    component::{Component, ComponentInfo, ComponentRegistry},
+    component::{Component, ComponentInfo, ComponentRegistry, RelationClone},
     entity::{EntityId, EntitySet},
     epoch::{EpochCounter, EpochId},
-    query::{DefaultQuery, Fetch, IntoQuery, Query, QueryItem},
-    relation::{OriginComponent, Relation, TargetComponent},
+    hash::NoOpHasherBuilder,
+    query::{visit_enabled, DefaultQuery, Fetch, IncludeDisabled, IntoQuery, Query, QueryItem},
+    relation::{
+        dynamic::{register_relation_dyn, DynOriginComponent, DynTargetComponent},
+        register_relation, ChildOf, OriginComponent, Relation, TargetComponent,
+    },
     res::Res,
 };
 
-use self::edges::Edges;
+use self::{edges::Edges, index::AnyIndex, observer::Observers, snapshot::ResourceClone};
 
 pub use self::{
+    append::ResourceConflict,
     builder::WorldBuilder,
+    disabled::Disabled,
+    index::Index,
+    name::{EntityDisplay, Name, NameError, RejectedName},
+    observer::{ObserverEvent, ObserverId},
+    prefab::{Instance, Prefab},
     query::{QueryOne, QueryRef},
+    snapshot::{SnapshotRing, WorldSnapshot},
+};
+
+pub(crate) use self::{
+    observer::Observer,
+    query::{acquire_archetypes, release_archetypes},
 };
 
+mod append;
 mod builder;
+mod disabled;
 mod edges;
+mod index;
+mod name;
+mod observer;
+mod prefab;
 mod query;
+mod snapshot;
+mod transfer;
 
 /// Limits on reserving of space for entities and components
 /// in archetypes when `spawn_batch` is used.
@@ -155,6 +183,22 @@ pub struct World {
     action_buffer: Option<ActionBuffer>,
 
     action_channel: ActionChannel,
+
+    /// Despawned entities with epochs of despawning.
+    /// `None` if tombstones are not recorded.
+    tombstones: Option<Vec<(EpochId, EntityId)>>,
+
+    /// Resources captured in snapshots.
+    resource_clones: Vec<ResourceClone>,
+
+    /// Hash indices on component values.
+    indices: HashMap<TypeId, Mutex<Box<dyn AnyIndex>>, NoOpHasherBuilder>,
+
+    /// Whether [`World::set_name`] rejects names used by other entities.
+    unique_names: bool,
+
+    /// Observers of component lifecycle events.
+    observers: Observers,
 }
 
 unsafe impl Sync for World {}
@@ -294,11 +338,21 @@ impl World {
     /// ```
     #[inline]
     pub fn spawn<B>(&mut self, bundle: B) -> EntityId
+    where
+        B: DynamicComponentBundle,
+    {
+        with_buffer!(self, buffer => {
+            self.spawn_with_buffer(bundle, buffer)
+        })
+    }
+
+    #[inline]
+    pub(crate) fn spawn_with_buffer<B>(&mut self, bundle: B, buffer: &mut ActionBuffer) -> EntityId
     where
         B: DynamicComponentBundle,
     {
         self.maintenance();
-        self.spawn_impl(bundle, register_bundle::<B>)
+        self.spawn_impl(bundle, register_bundle::<B>, buffer)
     }
 
     /// Spawns a new entity in this world with specific ID and bundle of components.
@@ -326,7 +380,9 @@ impl World {
         B: DynamicComponentBundle,
     {
         self.maintenance();
-        self.spawn_with_id_impl(id, bundle, register_bundle::<B>)
+        with_buffer!(self, buffer => {
+            self.spawn_with_id_impl(id, bundle, register_bundle::<B>, buffer)
+        })
     }
 
     /// Spawns entity with specific ID if it is not already spawned.
@@ -337,7 +393,7 @@ impl World {
         let spawned = self.entities.spawn_if_missing(id);
         if spawned {
             let epoch = self.epoch.next_mut();
-            let idx = self.archetypes[0].spawn(id, (), epoch);
+            let idx = self.archetypes[0].spawn(id, (), epoch, None);
             self.entities.set_location(id, 0, idx);
             true
         } else {
@@ -374,11 +430,25 @@ impl World {
     /// ```
     #[inline]
     pub fn spawn_external<B>(&mut self, bundle: B) -> EntityId
+    where
+        B: DynamicBundle,
+    {
+        with_buffer!(self, buffer => {
+            self.spawn_external_with_buffer(bundle, buffer)
+        })
+    }
+
+    #[inline]
+    pub(crate) fn spawn_external_with_buffer<B>(
+        &mut self,
+        bundle: B,
+        buffer: &mut ActionBuffer,
+    ) -> EntityId
     where
         B: DynamicBundle,
     {
         self.maintenance();
-        self.spawn_impl(bundle, assert_registered_bundle::<B>)
+        self.spawn_impl(bundle, assert_registered_bundle::<B>, buffer)
     }
 
     /// Spawns a new entity in this world with provided bundle of components.
@@ -414,10 +484,17 @@ impl World {
         B: DynamicBundle,
     {
         self.maintenance();
-        self.spawn_with_id_impl(id, bundle, assert_registered_bundle::<B>);
+        with_buffer!(self, buffer => {
+            self.spawn_with_id_impl(id, bundle, assert_registered_bundle::<B>, buffer)
+        })
     }
 
-    fn spawn_impl<B, F>(&mut self, bundle: B, register_bundle: F) -> EntityId
+    fn spawn_impl<B, F>(
+        &mut self,
+        bundle: B,
+        register_bundle: F,
+        buffer: &mut ActionBuffer,
+    ) -> EntityId
     where
         B: DynamicBundle,
         F: FnOnce(&mut ComponentRegistry, &B),
@@ -430,12 +507,17 @@ impl World {
         }
 
         let id = self.entities.alloc_mut();
-        self.spawn_with_id_impl(id, bundle, register_bundle);
+        self.spawn_with_id_impl(id, bundle, register_bundle, buffer);
         id
     }
 
-    fn spawn_with_id_impl<B, F>(&mut self, id: EntityId, bundle: B, register_bundle: F)
-    where
+    fn spawn_with_id_impl<B, F>(
+        &mut self,
+        id: EntityId,
+        bundle: B,
+        register_bundle: F,
+        buffer: &mut ActionBuffer,
+    ) where
         B: DynamicBundle,
         F: FnOnce(&mut ComponentRegistry, &B),
     {
@@ -455,8 +537,11 @@ impl World {
             |registry| register_bundle(registry, &bundle),
         );
         let epoch = self.epoch.next_mut();
-        let idx = self.archetypes[archetype_idx as usize].spawn(id, bundle, epoch);
+        let encoder = ActionEncoder::new(buffer, &self.entities);
+        let idx = self.archetypes[archetype_idx as usize].spawn(id, bundle, epoch, Some(encoder));
         self.entities.set_location(id, archetype_idx, idx);
+
+        self.notify_components(id, buffer, |_| Some(ObserverEvent::Insert));
     }
 
     /// Returns an iterator which spawns and yield entities
@@ -548,15 +633,11 @@ impl World {
 
         let epoch = self.epoch.next_mut();
 
-        let archetype = &mut self.archetypes[archetype_idx as usize];
-        let entities = &mut self.entities;
-
         SpawnBatch {
             bundles: bundles.into_iter(),
             epoch,
             archetype_idx,
-            archetype,
-            entities,
+            world: self,
         }
     }
 
@@ -603,6 +684,8 @@ impl World {
     ) -> Result<(), NoSuchEntity> {
         self.maintenance();
 
+        self.notify_components(id, buffer, |_| Some(ObserverEvent::Remove));
+
         let (archetype, idx) = self.entities.despawn(id)?;
 
         let encoder = ActionEncoder::new(buffer, &self.entities);
@@ -613,9 +696,146 @@ impl World {
             self.entities.set_location(id, archetype, idx)
         }
 
+        if let Some(tombstones) = &mut self.tombstones {
+            tombstones.push((self.epoch.next_mut(), id));
+        }
+
         Ok(())
     }
 
+    /// Spawns a new entity with clones of all components of specified entity.
+    /// Returns [`Err(CloneError::NoSuchEntity)`] if entity does not exists.
+    ///
+    /// Components are cloned with functions set by [`Component::clone_fn`]
+    /// or [`ComponentInfoRef::clone_fn`].
+    /// Relations where entity is origin are added to the clone with the same targets.
+    /// Clone does not become target of relations.
+    ///
+    /// Components that cannot be cloned are skipped.
+    /// If there are any, clone is spawned without them and
+    /// [`Err(CloneError::NotCloneable)`] is returned with id of the clone
+    /// and names of skipped components.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Clone, Component)]
+    /// #[edict(clone)]
+    /// struct Health(u32);
+    ///
+    /// let mut world = World::new();
+    /// let template = world.spawn((Health(100),));
+    /// let entity = world.clone_entity(template).unwrap();
+    /// assert_eq!(world.query_one_mut::<&Health>(entity).unwrap().0, 100);
+    /// ```
+    ///
+    /// [`Component::clone_fn`]: crate::component::Component::clone_fn
+    /// [`ComponentInfoRef::clone_fn`]: crate::component::ComponentInfoRef::clone_fn
+    /// [`Err(CloneError::NoSuchEntity)`]: CloneError::NoSuchEntity
+    /// [`Err(CloneError::NotCloneable)`]: CloneError::NotCloneable
+    #[inline]
+    pub fn clone_entity(&mut self, id: EntityId) -> Result<EntityId, CloneError> {
+        with_buffer!(self, buffer => self.clone_entity_with_buffer(id, None, buffer))
+    }
+
+    /// Clones entity into new entity or into existing entity if specified.
+    pub(crate) fn clone_entity_with_buffer(
+        &mut self,
+        id: EntityId,
+        clone: Option<EntityId>,
+        buffer: &mut ActionBuffer,
+    ) -> Result<EntityId, CloneError> {
+        self.maintenance();
+
+        let (archetype_idx, idx) = self.entities.get_location(id).ok_or(NoSuchEntity)?;
+
+        let clone_id = match clone {
+            None => self.entities.alloc_mut(),
+            Some(clone_id) => clone_id,
+        };
+
+        let mut builder = EntityBuilder::new();
+        let mut skipped = Vec::new();
+
+        let mut encoder = ActionEncoder::new(buffer, &self.entities);
+        let archetype = &self.archetypes[archetype_idx as usize];
+
+        for info in archetype.infos() {
+            let component = archetype.component(info.id()).unwrap();
+            let ptr = unsafe {
+                NonNull::new_unchecked(
+                    component
+                        .data()
+                        .ptr
+                        .as_ptr()
+                        .add(idx as usize * info.layout().size()),
+                )
+            };
+
+            match info.relation_clone() {
+                Some(RelationClone::Origin(add_relations)) => unsafe {
+                    add_relations(ptr, clone_id, &mut Some, &mut encoder);
+                },
+                Some(RelationClone::Target) => {}
+                None if info.is_cloneable() => unsafe {
+                    builder.add_clone(info, ptr);
+                },
+                None => skipped.push(info.name()),
+            }
+        }
+
+        match clone {
+            None => {
+                self.spawn_with_id_impl(clone_id, builder, register_bundle::<EntityBuilder>, buffer)
+            }
+            Some(clone_id) => self.insert_bundle_with_buffer(clone_id, builder, buffer)?,
+        }
+
+        if skipped.is_empty() {
+            Ok(clone_id)
+        } else {
+            skipped.sort_unstable();
+            Err(CloneError::NotCloneable {
+                id: clone_id,
+                components: skipped,
+            })
+        }
+    }
+
+    /// Returns iterator over entities despawned after specified epoch.
+    ///
+    /// Tombstones are recorded only if enabled with [`WorldBuilder::with_tombstones`].
+    /// Otherwise iterator is always empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::world::World;
+    /// let mut world = World::builder().with_tombstones().build();
+    /// let entity = world.spawn(());
+    /// let epoch = world.epoch();
+    /// world.despawn(entity).unwrap();
+    /// assert!(world.tombstones(epoch).eq([entity]));
+    /// ```
+    #[inline]
+    pub fn tombstones(&self, after_epoch: EpochId) -> impl Iterator<Item = EntityId> + '_ {
+        let tombstones = self.tombstones.as_deref().unwrap_or(&[]);
+        let start = tombstones.partition_point(|(epoch, _)| !epoch.after(after_epoch));
+        tombstones[start..].iter().map(|&(_, id)| id)
+    }
+
+    /// Forgets tombstones of entities despawned not after specified epoch.
+    ///
+    /// Call this once all consumers of incremental dumps have seen the epoch.
+    #[inline]
+    pub fn trim_tombstones(&mut self, up_to_epoch: EpochId) {
+        if let Some(tombstones) = &mut self.tombstones {
+            let end = tombstones.partition_point(|(epoch, _)| !epoch.after(up_to_epoch));
+            tombstones.drain(..end);
+        }
+    }
+
     /// Attempts to inserts component to the specified entity.
     ///
     /// If entity already had component of that type,
@@ -726,6 +946,10 @@ impl World {
                 self.archetypes[src_archetype as usize].set(id, idx, component, epoch, encoder);
             }
 
+            self.notify_components(id, buffer, |info| {
+                (info.id() == TypeId::of::<T>()).then_some(ObserverEvent::Replace)
+            });
+
             return Ok(());
         }
 
@@ -748,7 +972,8 @@ impl World {
             false => (&mut after[0], &mut before[dst_archetype as usize]),
         };
 
-        let (dst_idx, opt_src_id) = unsafe { src.insert(id, dst, idx, component, epoch) };
+        let encoder = ActionEncoder::new(buffer, &self.entities);
+        let (dst_idx, opt_src_id) = unsafe { src.insert(id, dst, idx, component, epoch, encoder) };
 
         self.entities.set_location(id, dst_archetype, dst_idx);
 
@@ -756,6 +981,10 @@ impl World {
             self.entities.set_location(src_id, src_archetype, idx);
         }
 
+        self.notify_components(id, buffer, |info| {
+            (info.id() == TypeId::of::<T>()).then_some(ObserverEvent::Insert)
+        });
+
         Ok(())
     }
 
@@ -765,6 +994,18 @@ impl World {
     /// If entity is not alive, fails with `Err(NoSuchEntity)`.
     #[inline]
     pub fn remove<T>(&mut self, id: EntityId) -> Result<T, EntityError>
+    where
+        T: 'static,
+    {
+        with_buffer!(self, buffer => self.remove_with_buffer(id, buffer))
+    }
+
+    #[inline]
+    pub(crate) fn remove_with_buffer<T>(
+        &mut self,
+        id: EntityId,
+        buffer: &mut ActionBuffer,
+    ) -> Result<T, EntityError>
     where
         T: 'static,
     {
@@ -777,6 +1018,10 @@ impl World {
             return Err(EntityError::MissingComponents);
         }
 
+        self.notify_components(id, buffer, |info| {
+            (info.id() == TypeId::of::<T>()).then_some(ObserverEvent::Remove)
+        });
+
         let dst_archetype =
             self.edges
                 .remove(&mut self.archetypes, src_archetype, TypeId::of::<T>());
@@ -842,6 +1087,10 @@ impl World {
             return Err(EntityError::MissingComponents);
         }
 
+        self.notify_components(id, buffer, |info| {
+            (info.id() == tid).then_some(ObserverEvent::Remove)
+        });
+
         let dst_archetype = self.edges.remove(&mut self.archetypes, src_archetype, tid);
 
         debug_assert_ne!(src_archetype, dst_archetype);
@@ -990,6 +1239,8 @@ impl World {
             return Ok(());
         }
 
+        let ids: SmallVec<[TypeId; 8]> = bundle.with_ids(|ids| ids.iter().copied().collect());
+
         let epoch = self.epoch.next_mut();
 
         let dst_archetype = self.edges.insert_bundle(
@@ -1010,6 +1261,11 @@ impl World {
                     ActionEncoder::new(buffer, &self.entities),
                 )
             }
+
+            self.notify_components(id, buffer, |info| {
+                ids.contains(&info.id()).then_some(ObserverEvent::Replace)
+            });
+
             return Ok(());
         }
 
@@ -1039,6 +1295,17 @@ impl World {
             self.entities.set_location(src_id, src_archetype, idx);
         }
 
+        let src = &self.archetypes[src_archetype as usize];
+        self.notify_components(id, buffer, |info| {
+            if !ids.contains(&info.id()) {
+                None
+            } else if src.has_component(info.id()) {
+                Some(ObserverEvent::Replace)
+            } else {
+                Some(ObserverEvent::Insert)
+            }
+        });
+
         Ok(())
     }
 
@@ -1106,6 +1373,10 @@ impl World {
             return Ok(());
         }
 
+        self.notify_components(id, buffer, |info| {
+            B::static_with_ids(|ids| ids.contains(&info.id())).then_some(ObserverEvent::Remove)
+        });
+
         let dst_archetype = self
             .edges
             .remove_bundle::<B>(&mut self.archetypes, src_archetype);
@@ -1146,13 +1417,15 @@ impl World {
     /// If relation is symmetric then it is added in both directions.
     /// If relation is exclusive, then previous relation on origin is replaced, otherwise relation is added.
     /// If relation is exclusive and symmetric, then previous relation on target is replaced, otherwise relation is added.
+    ///
+    /// If relation is acyclic and new relation would close a cycle, fails with `Err(AddRelationError::Cycle)`.
     #[inline]
     pub fn add_relation<R>(
         &mut self,
         origin: EntityId,
         relation: R,
         target: EntityId,
-    ) -> Result<(), NoSuchEntity>
+    ) -> Result<(), AddRelationError>
     where
         R: Relation,
     {
@@ -1168,7 +1441,7 @@ impl World {
         relation: R,
         target: EntityId,
         buffer: &mut ActionBuffer,
-    ) -> Result<(), NoSuchEntity>
+    ) -> Result<(), AddRelationError>
     where
         R: Relation,
     {
@@ -1177,6 +1450,12 @@ impl World {
         self.entities.get_location(origin).ok_or(NoSuchEntity)?;
         self.entities.get_location(target).ok_or(NoSuchEntity)?;
 
+        if R::ACYCLIC && !R::SYMMETRIC && self.relation_path_exists::<R>(target, origin) {
+            return Err(AddRelationError::Cycle);
+        }
+
+        register_relation::<R>(&mut self.registry);
+
         self.epoch.next_mut();
 
         if R::SYMMETRIC {
@@ -1185,7 +1464,9 @@ impl World {
                 origin,
                 relation,
                 |relation| OriginComponent::new(target, relation),
-                |component, relation, encoder| component.add(origin, target, relation, encoder),
+                |component, relation, encoder| {
+                    component.add(origin, target, relation, encoder);
+                },
                 buffer,
             );
 
@@ -1195,32 +1476,175 @@ impl World {
                     target,
                     relation,
                     |relation| OriginComponent::new(origin, relation),
-                    |component, relation, encoder| component.add(target, origin, relation, encoder),
+                    |component, relation, encoder| {
+                        component.add(target, origin, relation, encoder);
+                    },
                     buffer,
                 );
             }
         } else {
+            let mut new_target = true;
             insert_component(
                 self,
                 origin,
                 relation,
                 |relation| OriginComponent::new(target, relation),
-                |component, relation, encoder| component.add(origin, target, relation, encoder),
+                |component, relation, encoder| {
+                    new_target = component.add(origin, target, relation, encoder);
+                },
                 buffer,
             );
 
-            insert_component(
-                self,
-                target,
-                (),
-                |()| TargetComponent::<R>::new(origin),
-                |component, (), _| component.add(origin),
-                buffer,
-            );
+            // Relation re-added to the same target is already known to the target.
+            if new_target {
+                insert_component(
+                    self,
+                    target,
+                    (),
+                    |()| TargetComponent::<R>::new(origin),
+                    |component, (), _| component.add(origin),
+                    buffer,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks if `to` is reachable from `from` following relations of type `R`
+    /// from origins to targets.
+    fn relation_path_exists<R>(&self, from: EntityId, to: EntityId) -> bool
+    where
+        R: Relation,
+    {
+        let mut visited = HashSet::new();
+        let mut stack = vec![from];
+
+        while let Some(id) = stack.pop() {
+            if id == to {
+                return true;
+            }
+            if !visited.insert(id) {
+                continue;
+            }
+            if let Ok(mut origin) = self.query_one::<(&OriginComponent<R>, IncludeDisabled)>(id) {
+                if let Some((origin, _)) = origin.get() {
+                    stack.extend(origin.origins().iter().map(|o| o.target));
+                }
+            }
+        }
+        false
+    }
+
+    /// Adds relation between two entities to the [`World`]
+    /// and places origin at specified position among origins of the target.
+    ///
+    /// Index is clamped to the number of origins of the target.
+    /// Otherwise behaves like [`World::add_relation`].
+    ///
+    /// Order is kept stable only for [ordered](Relation::ORDERED) relations.
+    /// For symmetric relations position is ignored.
+    #[inline]
+    pub fn add_relation_at<R>(
+        &mut self,
+        origin: EntityId,
+        relation: R,
+        target: EntityId,
+        index: usize,
+    ) -> Result<(), AddRelationError>
+    where
+        R: Relation,
+    {
+        with_buffer!(self, buffer => {
+            self.add_relation_at_with_buffer(origin, relation, target, index, buffer)
+        })
+    }
+
+    #[inline]
+    pub(crate) fn add_relation_at_with_buffer<R>(
+        &mut self,
+        origin: EntityId,
+        relation: R,
+        target: EntityId,
+        index: usize,
+        buffer: &mut ActionBuffer,
+    ) -> Result<(), AddRelationError>
+    where
+        R: Relation,
+    {
+        self.add_relation_with_buffer(origin, relation, target, buffer)?;
+        if !R::SYMMETRIC {
+            let _ = self.move_relation::<R>(origin, target, index);
         }
         Ok(())
     }
 
+    /// Moves origin of the relation to specified position among origins of the target.
+    ///
+    /// Index is clamped to the number of origins of the target.
+    ///
+    /// If either entity is not alive, fails with `Err(EntityError::NoSuchEntity)`.
+    /// If relation does not exist or is symmetric, fails with `Err(EntityError::MissingComponents)`.
+    #[inline]
+    pub fn move_relation<R>(
+        &mut self,
+        origin: EntityId,
+        target: EntityId,
+        index: usize,
+    ) -> Result<(), EntityError>
+    where
+        R: Relation,
+    {
+        self.maintenance();
+
+        self.entities.get_location(origin).ok_or(NoSuchEntity)?;
+        let (archetype_idx, idx) = self.entities.get_location(target).ok_or(NoSuchEntity)?;
+
+        let archetype = &mut self.archetypes[archetype_idx as usize];
+        if !archetype.has_component(TypeId::of::<TargetComponent<R>>()) {
+            return Err(EntityError::MissingComponents);
+        }
+        if !unsafe { archetype.get::<TargetComponent<R>>(idx) }.has_origin(origin) {
+            return Err(EntityError::MissingComponents);
+        }
+
+        // Reorder is a modification of the target component.
+        let epoch = self.epoch.next_mut();
+        let target_component = unsafe { archetype.get_mut::<TargetComponent<R>>(idx, epoch) };
+        target_component.move_origin(origin, index);
+        Ok(())
+    }
+
+    /// Adds `child` to `parent` with [`ChildOf`] relation
+    /// and places it at specified position among children of the `parent`.
+    ///
+    /// See [`World::add_relation_at`].
+    #[inline]
+    pub fn insert_child_at(
+        &mut self,
+        child: EntityId,
+        parent: EntityId,
+        index: usize,
+    ) -> Result<(), AddRelationError> {
+        self.add_relation_at(child, ChildOf, parent, index)
+    }
+
+    /// Moves `child` to specified position among children of its parent.
+    ///
+    /// If entity is not alive, fails with `Err(EntityError::NoSuchEntity)`.
+    /// If entity has no parent, fails with `Err(EntityError::MissingComponents)`.
+    #[inline]
+    pub fn move_child(&mut self, child: EntityId, index: usize) -> Result<(), EntityError> {
+        let parent = self
+            .query_one::<(&OriginComponent<ChildOf>, IncludeDisabled)>(child)?
+            .get()
+            .ok_or(MissingComponents)?
+            .0
+            .origins()[0]
+            .target;
+
+        self.move_relation::<ChildOf>(child, parent, index)
+    }
+
     /// Drops relation between two entities in the [`World`].
     ///
     /// If either entity is not alive, fails with `Err(NoSuchEntity)`.
@@ -1258,7 +1682,9 @@ impl World {
         self.entities.get_location(target).ok_or(NoSuchEntity)?;
 
         unsafe {
-            if let Ok(c) = self.query_one_unchecked::<&mut OriginComponent<R>>(origin) {
+            if let Ok((c, _)) =
+                self.query_one_unchecked::<(&mut OriginComponent<R>, IncludeDisabled)>(origin)
+            {
                 if let Some(r) =
                     c.remove_relation(origin, target, ActionEncoder::new(buffer, &self.entities))
                 {
@@ -1269,6 +1695,122 @@ impl World {
         Err(EntityError::MissingComponents)
     }
 
+    /// Adds dynamic relation of specified kind between two entities to the [`World`].
+    ///
+    /// Kind of dynamic relation is an [`EntityId`]
+    /// and is not required to reference live entity.
+    ///
+    /// If either entity is not alive, fails with `Err(NoSuchEntity)`.
+    /// When either entity is despawned, relation is removed automatically.
+    ///
+    /// If relation already exists, does nothing.
+    ///
+    /// Dynamic relations can be queried using [`RelatesDyn`] and [`RelatedDyn`] queries.
+    ///
+    /// [`RelatesDyn`]: edict::relation::RelatesDyn
+    /// [`RelatedDyn`]: edict::relation::RelatedDyn
+    #[inline]
+    pub fn add_relation_dyn(
+        &mut self,
+        origin: EntityId,
+        kind: EntityId,
+        target: EntityId,
+    ) -> Result<(), NoSuchEntity> {
+        with_buffer!(self, buffer => {
+            self.add_relation_dyn_with_buffer(origin, kind, target, buffer)
+        })
+    }
+
+    #[inline]
+    pub(crate) fn add_relation_dyn_with_buffer(
+        &mut self,
+        origin: EntityId,
+        kind: EntityId,
+        target: EntityId,
+        buffer: &mut ActionBuffer,
+    ) -> Result<(), NoSuchEntity> {
+        self.maintenance();
+
+        self.entities.get_location(origin).ok_or(NoSuchEntity)?;
+        self.entities.get_location(target).ok_or(NoSuchEntity)?;
+
+        register_relation_dyn(&mut self.registry);
+
+        self.epoch.next_mut();
+
+        insert_component(
+            self,
+            origin,
+            (),
+            |()| DynOriginComponent::new(kind, target),
+            |component, (), _| component.add(kind, target),
+            buffer,
+        );
+
+        insert_component(
+            self,
+            target,
+            (),
+            |()| DynTargetComponent::new(kind, origin),
+            |component, (), _| component.add(kind, origin),
+            buffer,
+        );
+
+        Ok(())
+    }
+
+    /// Drops dynamic relation of specified kind between two entities in the [`World`].
+    ///
+    /// If either entity is not alive, fails with `Err(EntityError::NoSuchEntity)`.
+    /// If relation does not exist, fails with `Err(EntityError::MissingComponents)`.
+    #[inline]
+    pub fn remove_relation_dyn(
+        &mut self,
+        origin: EntityId,
+        kind: EntityId,
+        target: EntityId,
+    ) -> Result<(), EntityError> {
+        with_buffer!(self, buffer => {
+            self.remove_relation_dyn_with_buffer(origin, kind, target, buffer)
+        })
+    }
+
+    #[inline]
+    pub(crate) fn remove_relation_dyn_with_buffer(
+        &mut self,
+        origin: EntityId,
+        kind: EntityId,
+        target: EntityId,
+        buffer: &mut ActionBuffer,
+    ) -> Result<(), EntityError> {
+        self.maintenance();
+
+        self.entities.get_location(origin).ok_or(NoSuchEntity)?;
+        self.entities.get_location(target).ok_or(NoSuchEntity)?;
+
+        unsafe {
+            let mut encoder = ActionEncoder::new(buffer, &self.entities);
+
+            let removed = match self
+                .query_one_unchecked::<(&mut DynOriginComponent, IncludeDisabled)>(origin)
+            {
+                Ok((c, _)) => c.remove(origin, kind, target, encoder.reborrow()),
+                Err(_) => false,
+            };
+
+            if !removed {
+                return Err(EntityError::MissingComponents);
+            }
+
+            if let Ok((c, _)) =
+                self.query_one_unchecked::<(&mut DynTargetComponent, IncludeDisabled)>(target)
+            {
+                c.remove(target, kind, origin, encoder);
+            }
+        }
+        Ok(())
+    }
+
     /// Queries components from specified entity.
     /// Returns query item.
     ///
@@ -1347,7 +1889,7 @@ impl World {
 
         debug_assert!(archetype.len() >= idx as usize, "Entity index is valid");
 
-        if !query.visit_archetype(archetype) {
+        if !visit_enabled(&query, archetype) {
             return Err(QueryOneError::NotSatisfied);
         }
 
@@ -1651,6 +2193,11 @@ impl World {
         self.registry.iter_info()
     }
 
+    #[inline]
+    pub(crate) fn component_info(&self, id: TypeId) -> Option<&ComponentInfo> {
+        self.registry.get_info(id)
+    }
+
     /// Returns a slice of all materialized archetypes.
     pub fn archetypes(&self) -> &[Archetype] {
         &self.archetypes
@@ -2061,17 +2608,48 @@ impl World {
         let epoch = self.epoch.current_mut();
         let archetype = &mut self.archetypes[0];
         self.entities
-            .spawn_allocated(|id| archetype.spawn(id, (), epoch));
+            .spawn_allocated(|id| archetype.spawn(id, (), epoch, None));
     }
 }
 
 /// Spawning iterator. Produced by [`World::spawn_batch`].
+///
+/// Actions encoded by insert hooks of spawned components
+/// are executed when iterator is dropped.
 pub struct SpawnBatch<'a, I> {
     bundles: I,
     epoch: EpochId,
     archetype_idx: u32,
-    archetype: &'a mut Archetype,
-    entities: &'a mut EntitySet,
+    world: &'a mut World,
+}
+
+impl<I> Drop for SpawnBatch<'_, I> {
+    fn drop(&mut self) {
+        let world = &mut *self.world;
+        with_buffer!(world, _buffer => {});
+    }
+}
+
+impl<I> SpawnBatch<'_, I> {
+    fn reserve(&mut self, additional: usize) {
+        self.world.entities.reserve_space(additional);
+        self.world.archetypes[self.archetype_idx as usize].reserve(additional);
+    }
+}
+
+/// Spawns one entity of the batch.
+fn spawn_batch_one<B>(world: &mut World, archetype_idx: u32, epoch: EpochId, bundle: B) -> EntityId
+where
+    B: Bundle,
+{
+    let id = world.entities.spawn();
+    let mut buffer = world.action_buffer.take().unwrap();
+    let encoder = ActionEncoder::new(&mut buffer, &world.entities);
+    let idx = world.archetypes[archetype_idx as usize].spawn(id, bundle, epoch, Some(encoder));
+    world.entities.set_location(id, archetype_idx, idx);
+    world.notify_components(id, &mut buffer, |_| Some(ObserverEvent::Insert));
+    world.action_buffer = Some(buffer);
+    id
 }
 
 impl<B, I> SpawnBatch<'_, I>
@@ -2081,19 +2659,14 @@ where
 {
     /// Spawns the rest of the entities, dropping their ids.
     pub fn spawn_all(mut self) {
-        let additional = iter_reserve_hint(&self.bundles);
-        self.entities.reserve_space(additional);
-        self.archetype.reserve(additional);
+        self.reserve(iter_reserve_hint(&self.bundles));
 
-        let entities = &mut self.entities;
-        let archetype = &mut self.archetype;
+        let world = &mut *self.world;
         let archetype_idx = self.archetype_idx;
         let epoch = self.epoch;
 
-        self.bundles.for_each(|bundle| {
-            let id = entities.spawn();
-            let idx = archetype.spawn(id, bundle, epoch);
-            entities.set_location(id, archetype_idx, idx);
+        self.bundles.by_ref().for_each(|bundle| {
+            spawn_batch_one(world, archetype_idx, epoch, bundle);
         })
     }
 }
@@ -2107,23 +2680,23 @@ where
 
     fn next(&mut self) -> Option<EntityId> {
         let bundle = self.bundles.next()?;
-
-        let id = self.entities.spawn();
-        let idx = self.archetype.spawn(id, bundle, self.epoch);
-        self.entities.set_location(id, self.archetype_idx, idx);
-
-        Some(id)
+        Some(spawn_batch_one(
+            self.world,
+            self.archetype_idx,
+            self.epoch,
+            bundle,
+        ))
     }
 
     fn nth(&mut self, n: usize) -> Option<EntityId> {
         // `SpawnBatch` explicitly does NOT spawn entities that are skipped.
         let bundle = self.bundles.nth(n)?;
-
-        let id = self.entities.spawn();
-        let idx = self.archetype.spawn(id, bundle, self.epoch);
-        self.entities.set_location(id, self.archetype_idx, idx);
-
-        Some(id)
+        Some(spawn_batch_one(
+            self.world,
+            self.archetype_idx,
+            self.epoch,
+            bundle,
+        ))
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -2134,24 +2707,18 @@ where
     where
         F: FnMut(T, EntityId) -> T,
     {
-        let additional = iter_reserve_hint(&self.bundles);
-        self.entities.reserve_space(additional);
-        self.archetype.reserve(additional);
+        self.reserve(iter_reserve_hint(&self.bundles));
 
-        let entities = &mut self.entities;
-        let archetype = &mut self.archetype;
+        let world = &mut *self.world;
         let archetype_idx = self.archetype_idx;
         let epoch = self.epoch;
 
-        self.bundles.fold(init, |acc, bundle| {
-            let id = entities.spawn();
-            let idx = archetype.spawn(id, bundle, epoch);
-            entities.set_location(id, archetype_idx, idx);
-            f(acc, id)
+        self.bundles.by_ref().fold(init, |acc, bundle| {
+            f(acc, spawn_batch_one(world, archetype_idx, epoch, bundle))
         })
     }
 
-    fn collect<T>(self) -> T
+    fn collect<T>(mut self) -> T
     where
         T: FromIterator<EntityId>,
     {
@@ -2159,9 +2726,7 @@ where
         // until the end of the iterator.
         //
         // Hence we should reserve space in archetype here.
-        let additional = iter_reserve_hint(&self.bundles);
-        self.entities.reserve_space(additional);
-        self.archetype.reserve(additional);
+        self.reserve(iter_reserve_hint(&self.bundles));
 
         FromIterator::from_iter(self)
     }
@@ -2184,26 +2749,24 @@ where
 {
     fn next_back(&mut self) -> Option<EntityId> {
         let bundle = self.bundles.next_back()?;
-
-        let id = self.entities.spawn();
-        let idx = self.archetype.spawn(id, bundle, self.epoch);
-
-        self.entities.set_location(id, self.archetype_idx, idx);
-
-        Some(id)
+        Some(spawn_batch_one(
+            self.world,
+            self.archetype_idx,
+            self.epoch,
+            bundle,
+        ))
     }
 
     fn nth_back(&mut self, n: usize) -> Option<EntityId> {
         // No reason to create entities
         // for which the only reference is immediately dropped
         let bundle = self.bundles.nth_back(n)?;
-
-        let id = self.entities.spawn();
-        let idx = self.archetype.spawn(id, bundle, self.epoch);
-
-        self.entities.set_location(id, self.archetype_idx, idx);
-
-        Some(id)
+        Some(spawn_batch_one(
+            self.world,
+            self.archetype_idx,
+            self.epoch,
+            bundle,
+        ))
     }
 
     fn rfold<T, F>(mut self, init: T, mut f: F) -> T
@@ -2211,18 +2774,14 @@ where
         Self: Sized,
         F: FnMut(T, EntityId) -> T,
     {
-        self.archetype.reserve(iter_reserve_hint(&self.bundles));
+        self.reserve(iter_reserve_hint(&self.bundles));
 
-        let entities = &mut self.entities;
-        let archetype = &mut self.archetype;
+        let world = &mut *self.world;
         let archetype_idx = self.archetype_idx;
         let epoch = self.epoch;
 
-        self.bundles.rfold(init, |acc, bundle| {
-            let id = entities.spawn();
-            let idx = archetype.spawn(id, bundle, epoch);
-            entities.set_location(id, archetype_idx, idx);
-            f(acc, id)
+        self.bundles.by_ref().rfold(init, |acc, bundle| {
+            f(acc, spawn_batch_one(world, archetype_idx, epoch, bundle))
         })
     }
 }
@@ -2318,6 +2877,104 @@ impl PartialEq<MissingComponents> for EntityError {
     }
 }
 
+/// Error returned by [`World::add_relation`] method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AddRelationError {
+    /// Error returned in case specified [`EntityId`]
+    /// does not reference any live entity in the [`World`].
+    NoSuchEntity,
+
+    /// Error returned in case relation is acyclic
+    /// and adding it would close a cycle.
+    Cycle,
+}
+
+impl fmt::Display for AddRelationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchEntity => fmt::Display::fmt(&NoSuchEntity, f),
+            Self::Cycle => f.write_str("Relation would close a cycle"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AddRelationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NoSuchEntity => Some(&NoSuchEntity),
+            Self::Cycle => None,
+        }
+    }
+}
+
+impl From<NoSuchEntity> for AddRelationError {
+    fn from(_: NoSuchEntity) -> Self {
+        AddRelationError::NoSuchEntity
+    }
+}
+
+impl PartialEq<NoSuchEntity> for AddRelationError {
+    fn eq(&self, _: &NoSuchEntity) -> bool {
+        matches!(self, AddRelationError::NoSuchEntity)
+    }
+}
+
+/// Error returned by [`World::clone_entity`] method.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CloneError {
+    /// Error returned in case specified [`EntityId`]
+    /// does not reference any live entity in the [`World`].
+    NoSuchEntity,
+
+    /// Error returned in case some components cannot be cloned.
+    /// Clone is spawned without them.
+    NotCloneable {
+        /// Id of the spawned clone.
+        id: EntityId,
+
+        /// Names of components that were not cloned.
+        components: Vec<&'static str>,
+    },
+}
+
+impl fmt::Display for CloneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchEntity => fmt::Display::fmt(&NoSuchEntity, f),
+            Self::NotCloneable { components, .. } => {
+                f.write_str("Components cannot be cloned:")?;
+                for name in components {
+                    write!(f, " `{}`", name)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CloneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NoSuchEntity => Some(&NoSuchEntity),
+            Self::NotCloneable { .. } => None,
+        }
+    }
+}
+
+impl From<NoSuchEntity> for CloneError {
+    fn from(_: NoSuchEntity) -> Self {
+        CloneError::NoSuchEntity
+    }
+}
+
+impl PartialEq<NoSuchEntity> for CloneError {
+    fn eq(&self, _: &NoSuchEntity) -> bool {
+        matches!(self, CloneError::NoSuchEntity)
+    }
+}
+
 /// Error returned by [`World::query_one`] method family
 /// when query is not satisfied by the entity.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -2412,14 +3069,19 @@ fn insert_component<T, C>(
         false => (&mut after[0], &mut before[dst_archetype as usize]),
     };
 
+    let encoder = ActionEncoder::new(buffer, &world.entities);
     let (dst_idx, opt_src_id) =
-        unsafe { src.insert(id, dst, idx, component, world.epoch.current_mut()) };
+        unsafe { src.insert(id, dst, idx, component, world.epoch.current_mut(), encoder) };
 
     world.entities.set_location(id, dst_archetype, dst_idx);
 
     if let Some(src_id) = opt_src_id {
         world.entities.set_location(src_id, src_archetype, idx);
     }
+
+    world.notify_components(id, buffer, |info| {
+        (info.id() == TypeId::of::<C>()).then_some(ObserverEvent::Insert)
+    });
 }
 
 fn register_one<T: Component>(registry: &mut ComponentRegistry) -> &ComponentInfo {
@@ -0,0 +1,295 @@
+//! Entity names and lookup by name.
+
+use alloc::{string::String, vec::Vec};
+use core::{borrow::Borrow, fmt, ops::Deref};
+
+use crate::{
+    action::ActionEncoder, component::Component, entity::EntityId, query::IncludeDisabled,
+};
+
+use super::{NoSuchEntity, World};
+
+/// Component with name of an entity.
+///
+/// Names are indexed by the [`World`],
+/// so entities can be found with [`World::find_by_name`].
+///
+/// If names are unique, see [`WorldBuilder::with_unique_names`],
+/// name inserted into an entity while used by another one is dropped
+/// and [`RejectedName`] is inserted instead.
+/// The entity that had the name first keeps it,
+/// including when several entities get the same name in one batch.
+///
+/// [`WorldBuilder::with_unique_names`]: super::WorldBuilder::with_unique_names
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Name(String);
+
+impl Component for Name {
+    #[inline]
+    fn on_insert(&mut self, id: EntityId, mut encoder: ActionEncoder) {
+        encoder.closure(move |world| world.drop_duplicate_name(id));
+    }
+
+    #[inline]
+    fn on_replace(&mut self, _value: &Self, id: EntityId, mut encoder: ActionEncoder) -> bool {
+        encoder.closure(move |world| world.drop_duplicate_name(id));
+        true
+    }
+
+    #[inline]
+    fn clone_fn() -> Option<fn(&Self) -> Self> {
+        Some(Self::clone)
+    }
+}
+
+impl Name {
+    /// Returns new name.
+    #[inline]
+    pub fn new(name: impl Into<String>) -> Self {
+        Name(name.into())
+    }
+
+    /// Returns name as string slice.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Name {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Name {
+    #[inline]
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Name {
+    #[inline]
+    fn from(name: &str) -> Self {
+        Name(String::from(name))
+    }
+}
+
+impl From<String> for Name {
+    #[inline]
+    fn from(name: String) -> Self {
+        Name(name)
+    }
+}
+
+impl fmt::Display for Name {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Component inserted into an entity
+/// which name is dropped because names are unique
+/// and another entity already has it.
+///
+/// Stays until removed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RejectedName {
+    name: Name,
+    holder: EntityId,
+}
+
+impl Component for RejectedName {
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        self.holder = mapper(self.holder);
+    }
+
+    #[inline]
+    fn clone_fn() -> Option<fn(&Self) -> Self> {
+        Some(Self::clone)
+    }
+}
+
+impl RejectedName {
+    /// Returns name that was rejected.
+    #[inline]
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    /// Returns id of the entity that had the name at the moment.
+    #[inline]
+    pub fn holder(&self) -> EntityId {
+        self.holder
+    }
+}
+
+/// Error returned by [`World::set_name`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NameError {
+    /// Error returned in case specified [`EntityId`]
+    /// does not reference any live entity in the [`World`].
+    NoSuchEntity,
+
+    /// Names are unique and the name is already used by another entity.
+    Taken(EntityId),
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchEntity => fmt::Display::fmt(&NoSuchEntity, f),
+            Self::Taken(id) => write!(f, "Name is already used by entity {}", id),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NameError {}
+
+impl From<NoSuchEntity> for NameError {
+    fn from(_: NoSuchEntity) -> Self {
+        NameError::NoSuchEntity
+    }
+}
+
+/// Displays entity id with name of the entity if it has one.
+/// Produced by [`World::entity_display`].
+pub struct EntityDisplay<'a> {
+    world: &'a World,
+    id: EntityId,
+}
+
+impl EntityDisplay<'_> {
+    fn with_name<R>(&self, f: impl FnOnce(Option<&Name>) -> R) -> R {
+        match self.world.query_one::<(&Name, IncludeDisabled)>(self.id) {
+            Err(NoSuchEntity) => f(None),
+            Ok(mut query) => f(query.get().map(|(name, ())| name)),
+        }
+    }
+}
+
+impl fmt::Display for EntityDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with_name(|name| match name {
+            None => fmt::Display::fmt(&self.id, f),
+            Some(name) => write!(f, "{} {}", name, self.id),
+        })
+    }
+}
+
+impl fmt::Debug for EntityDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with_name(|name| match name {
+            None => fmt::Debug::fmt(&self.id, f),
+            Some(name) => f
+                .debug_struct("EntityId")
+                .field("value", &self.id.bits())
+                .field("name", &name.as_str())
+                .finish(),
+        })
+    }
+}
+
+impl World {
+    /// Sets name of the entity.
+    ///
+    /// If names are unique, see [`WorldBuilder::with_unique_names`],
+    /// returns [`Err(NameError::Taken)`] with id of the entity that already has this name.
+    ///
+    /// Inserting [`Name`] component directly with a taken name
+    /// leaves the entity without a name and with [`RejectedName`] instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::world::{NameError, World};
+    /// let mut world = World::builder().with_unique_names().build();
+    /// let player = world.spawn(());
+    /// let enemy = world.spawn(());
+    ///
+    /// world.set_name(player, "player").unwrap();
+    /// assert_eq!(world.set_name(enemy, "player"), Err(NameError::Taken(player)));
+    /// assert_eq!(world.find_by_name("player"), Some(player));
+    /// ```
+    ///
+    /// [`WorldBuilder::with_unique_names`]: super::WorldBuilder::with_unique_names
+    /// [`Err(NameError::Taken)`]: NameError::Taken
+    pub fn set_name(&mut self, id: EntityId, name: impl Into<Name>) -> Result<(), NameError> {
+        let name = name.into();
+
+        if self.unique_names {
+            if let Some(other) = self.find_by_name(&name) {
+                if other != id {
+                    return Err(NameError::Taken(other));
+                }
+            }
+        }
+
+        self.insert(id, name)?;
+        Ok(())
+    }
+
+    /// Returns entity with specified name.
+    /// If names are not unique, returns any of them.
+    ///
+    /// Disabled entities are found too.
+    ///
+    /// Names are looked up in the index, see [`World::add_index`].
+    #[inline]
+    pub fn find_by_name(&self, name: &str) -> Option<EntityId> {
+        self.index::<Name>().get_one(name)
+    }
+
+    /// Returns all entities with specified name.
+    #[inline]
+    pub fn find_all_by_name(&self, name: &str) -> Vec<EntityId> {
+        self.index::<Name>().get(name).to_vec()
+    }
+
+    /// Drops name of the entity if names are unique
+    /// and another entity had the same name first.
+    ///
+    /// Index appends entities that get a name to the end of its entry,
+    /// so while duplicates wait for this check the holder stays first.
+    fn drop_duplicate_name(&mut self, id: EntityId) {
+        if !self.unique_names {
+            return;
+        }
+
+        let Ok(name) =
+            self.for_one::<(&Name, IncludeDisabled), _, _>(id, |(name, ())| name.clone())
+        else {
+            return;
+        };
+
+        match self.find_by_name(&name) {
+            Some(holder) if holder != id => {
+                let _ = self.drop::<Name>(id);
+                let _ = self.insert(id, RejectedName { name, holder });
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns value that displays entity id with name of the entity if it has one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::world::{Name, World};
+    /// let mut world = World::new();
+    /// let player = world.spawn((Name::new("player"),));
+    /// assert_eq!(world.entity_display(player).to_string(), format!("player {}", player));
+    /// ```
+    #[inline]
+    pub fn entity_display(&self, id: EntityId) -> EntityDisplay<'_> {
+        EntityDisplay { world: self, id }
+    }
+}
@@ -0,0 +1,352 @@
+//! Observers of component and relation lifecycle events.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    any::TypeId,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use hashbrown::HashMap;
+use smallvec::SmallVec;
+
+use crate::{
+    action::{ActionBuffer, ActionEncoder},
+    archetype::{chunk_idx, Archetype},
+    component::{Component, ComponentInfo},
+    entity::EntityId,
+    epoch::EpochId,
+    hash::NoOpHasherBuilder,
+    query::{visit_enabled, Fetch, ImmutableQuery, IntoQuery, Query},
+    relation::{register_relation, OriginComponent, Relation},
+};
+
+use super::World;
+
+/// Lifecycle event of a component that observers react to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ObserverEvent {
+    /// Component is attached to an entity.
+    Insert,
+
+    /// New value is assigned to existing component.
+    Replace,
+
+    /// Component is removed from an entity, including despawning of the entity.
+    Remove,
+}
+
+/// Identifier of an observer.
+/// Returned when observer is attached and used to detach it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObserverId(u64);
+
+/// Ids are unique across all worlds, so they can be allocated without access to the world.
+static NEXT_OBSERVER_ID: AtomicU64 = AtomicU64::new(1);
+
+impl ObserverId {
+    fn next() -> Self {
+        ObserverId(NEXT_OBSERVER_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+type FilterFn = Box<dyn Fn(&Archetype, u32, EpochId) -> bool + Send + Sync>;
+type CallbackFn = Box<dyn Fn(EntityId, &World, ActionEncoder) + Send + Sync>;
+
+/// Observer that is not yet attached to a world.
+pub(crate) struct Observer {
+    ty: TypeId,
+    attached: AttachedObserver,
+    register: fn(&mut World),
+}
+
+/// Observer attached to a world.
+/// Filter is kept next to the callback,
+/// so it is evaluated when event happens.
+struct AttachedObserver {
+    id: ObserverId,
+    event: ObserverEvent,
+    filter: FilterFn,
+    callback: CallbackFn,
+}
+
+impl AttachedObserver {
+    /// Checks if observer reacts to the event
+    /// that happened to the entity at `idx` in the archetype.
+    fn matches(
+        &self,
+        event: ObserverEvent,
+        archetype: &Archetype,
+        idx: u32,
+        epoch: EpochId,
+    ) -> bool {
+        self.event == event && (self.filter)(archetype, idx, epoch)
+    }
+}
+
+impl Observer {
+    fn new<T, F, C>(event: ObserverEvent, filter: F, callback: C, register: fn(&mut World)) -> Self
+    where
+        T: 'static,
+        F: IntoQuery + Clone + Send + Sync + 'static,
+        F::Query: ImmutableQuery,
+        C: Fn(EntityId, &World, ActionEncoder) + Send + Sync + 'static,
+    {
+        Observer {
+            ty: TypeId::of::<T>(),
+            attached: AttachedObserver {
+                id: ObserverId::next(),
+                event,
+                filter: Box::new(move |archetype, idx, epoch| {
+                    let mut query = filter.clone().into_query();
+                    if !visit_enabled(&query, archetype) {
+                        return false;
+                    }
+
+                    let chunk_idx = chunk_idx(idx as usize);
+                    unsafe {
+                        let mut fetch = query.fetch(archetype, epoch);
+                        if !fetch.visit_chunk(chunk_idx) {
+                            return false;
+                        }
+                        fetch.touch_chunk(chunk_idx);
+                        fetch.visit_item(idx as usize)
+                    }
+                }),
+                callback: Box::new(callback),
+            },
+            register,
+        }
+    }
+
+    /// Returns observer of component `T`.
+    pub(crate) fn component<T, F, C>(event: ObserverEvent, filter: F, callback: C) -> Self
+    where
+        T: Component,
+        F: IntoQuery + Clone + Send + Sync + 'static,
+        F::Query: ImmutableQuery,
+        C: Fn(EntityId, &World, ActionEncoder) + Send + Sync + 'static,
+    {
+        Observer::new::<T, F, C>(event, filter, callback, |world| {
+            world.registry.ensure_component_registered::<T>();
+        })
+    }
+
+    /// Returns observer of relation `R` on origin entities.
+    pub(crate) fn relation<R, F, C>(event: ObserverEvent, filter: F, callback: C) -> Self
+    where
+        R: Relation,
+        F: IntoQuery + Clone + Send + Sync + 'static,
+        F::Query: ImmutableQuery,
+        C: Fn(EntityId, &World, ActionEncoder) + Send + Sync + 'static,
+    {
+        Observer::new::<OriginComponent<R>, F, C>(event, filter, callback, |world| {
+            register_relation::<R>(&mut world.registry);
+        })
+    }
+
+    pub(crate) fn id(&self) -> ObserverId {
+        self.attached.id
+    }
+}
+
+/// Observers attached to the world.
+pub(crate) struct Observers {
+    by_type: HashMap<TypeId, Vec<AttachedObserver>, NoOpHasherBuilder>,
+}
+
+impl Observers {
+    pub(crate) fn new() -> Self {
+        Observers {
+            by_type: HashMap::with_hasher(NoOpHasherBuilder),
+        }
+    }
+}
+
+impl World {
+    /// Attaches observer that is called when event happens to component `T`
+    /// of an entity that matches `filter`.
+    /// Use `()` as filter to observe all entities.
+    ///
+    /// `filter` is checked when event happens:
+    /// after component is inserted or replaced
+    /// and before it is removed, including despawning of the entity.
+    /// Observers are executed as actions after the triggering operation finishes,
+    /// when the world is in consistent state.
+    ///
+    /// Returns [`ObserverId`] to detach observer with [`World::detach_observer`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, query::With, world::{ObserverEvent, World}};
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// #[derive(Component)]
+    /// struct Player;
+    ///
+    /// #[derive(Component)]
+    /// struct Dead;
+    ///
+    /// let mut world = World::new();
+    /// world.observe::<Health, _, _>(
+    ///     ObserverEvent::Remove,
+    ///     With::<Player>::query(),
+    ///     |id, _world, mut encoder| encoder.insert(id, Dead),
+    /// );
+    ///
+    /// let player = world.spawn((Player, Health(100)));
+    /// let monster = world.spawn((Health(100),));
+    /// world.drop::<Health>(player).unwrap();
+    /// world.drop::<Health>(monster).unwrap();
+    ///
+    /// assert_eq!(world.has_component::<Dead>(player), Ok(true));
+    /// assert_eq!(world.has_component::<Dead>(monster), Ok(false));
+    /// ```
+    pub fn observe<T, F, C>(&mut self, event: ObserverEvent, filter: F, callback: C) -> ObserverId
+    where
+        T: Component,
+        F: IntoQuery + Clone + Send + Sync + 'static,
+        F::Query: ImmutableQuery,
+        C: Fn(EntityId, &World, ActionEncoder) + Send + Sync + 'static,
+    {
+        self.attach_observer(Observer::component::<T, F, C>(event, filter, callback))
+    }
+
+    /// Attaches observer that is called when event happens to relations `R`
+    /// of an origin entity that matches `filter`.
+    ///
+    /// [`ObserverEvent::Insert`] happens when entity becomes origin of the first relation `R`
+    /// and [`ObserverEvent::Remove`] when entity loses the last one.
+    ///
+    /// See [`World::observe`] for details.
+    pub fn observe_relation<R, F, C>(
+        &mut self,
+        event: ObserverEvent,
+        filter: F,
+        callback: C,
+    ) -> ObserverId
+    where
+        R: Relation,
+        F: IntoQuery + Clone + Send + Sync + 'static,
+        F::Query: ImmutableQuery,
+        C: Fn(EntityId, &World, ActionEncoder) + Send + Sync + 'static,
+    {
+        self.attach_observer(Observer::relation::<R, F, C>(event, filter, callback))
+    }
+
+    /// Detaches observer.
+    /// Returns `false` if observer is not attached to this world.
+    pub fn detach_observer(&mut self, id: ObserverId) -> bool {
+        for (&ty, observers) in self.observers.by_type.iter_mut() {
+            if let Some(idx) = observers.iter().position(|o| o.id == id) {
+                observers.swap_remove(idx);
+                if observers.is_empty() {
+                    self.observers.by_type.remove(&ty);
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    pub(crate) fn attach_observer(&mut self, observer: Observer) -> ObserverId {
+        (observer.register)(self);
+
+        let id = observer.attached.id;
+        self.observers
+            .by_type
+            .entry(observer.ty)
+            .or_default()
+            .push(observer.attached);
+        id
+    }
+
+    /// Updates indices of components of the entity,
+    /// evaluates filters of observers and encodes calls of matching observers.
+    /// `f` returns the event that happens to the component, if any.
+    ///
+    /// Must be called after component is inserted or replaced
+    /// and before it is removed,
+    /// while world is borrowed mutably by the caller.
+    pub(crate) fn notify_components(
+        &self,
+        id: EntityId,
+        buffer: &mut ActionBuffer,
+        f: impl Fn(&ComponentInfo) -> Option<ObserverEvent>,
+    ) {
+        if self.observers.by_type.is_empty() && self.indices.is_empty() {
+            return;
+        }
+
+        let Some((archetype_idx, idx)) = self.entities.get_location(id) else {
+            return;
+        };
+
+        if archetype_idx == u32::MAX {
+            return;
+        }
+
+        let archetype = &self.archetypes[archetype_idx as usize];
+        let mut epoch = None;
+
+        for info in archetype.infos() {
+            let index = self.indices.get(&info.id());
+            let observers = self.observers.by_type.get(&info.id());
+            if index.is_none() && observers.is_none() {
+                continue;
+            }
+
+            let event = f(info);
+
+            // Entries of other components are refreshed too,
+            // as entity may be moved to another archetype
+            // with modifications not yet picked up by the index.
+            if let Some(index) = index {
+                match event {
+                    Some(ObserverEvent::Remove) => index.lock().remove(id),
+                    // Safety: world is borrowed mutably by the caller,
+                    // so components are not borrowed.
+                    _ => unsafe { index.lock().insert(archetype, idx) },
+                }
+            }
+
+            let (Some(event), Some(observers)) = (event, observers) else {
+                continue;
+            };
+
+            let epoch = *epoch.get_or_insert_with(|| self.epoch.next());
+            let ids: SmallVec<[ObserverId; 4]> = observers
+                .iter()
+                .filter(|observer| observer.matches(event, archetype, idx, epoch))
+                .map(|observer| observer.id)
+                .collect();
+
+            if !ids.is_empty() {
+                let ty = info.id();
+                ActionEncoder::new(buffer, &self.entities)
+                    .push_fn(move |world, buffer| world.run_observers(ty, &ids, id, buffer));
+            }
+        }
+    }
+
+    /// Calls specified observers of component of specified type.
+    /// Observers detached since the event are skipped.
+    pub(crate) fn run_observers(
+        &self,
+        ty: TypeId,
+        ids: &[ObserverId],
+        id: EntityId,
+        buffer: &mut ActionBuffer,
+    ) {
+        let Some(observers) = self.observers.by_type.get(&ty) else {
+            return;
+        };
+
+        for observer in observers {
+            if ids.contains(&observer.id) {
+                (observer.callback)(id, self, ActionEncoder::new(buffer, &self.entities));
+            }
+        }
+    }
+}
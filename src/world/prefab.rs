@@ -0,0 +1,322 @@
+//! Prefabs and their instances.
+
+use alloc::{
+    alloc::{alloc, dealloc, handle_alloc_error},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{
+    any::{type_name, TypeId},
+    ptr::NonNull,
+};
+
+use hashbrown::HashMap;
+
+use crate::{
+    bundle::DynamicComponentBundle,
+    component::{Component, ComponentInfo},
+    entity::EntityId,
+    hash::NoOpHasherBuilder,
+    query::IncludeDisabled,
+    relation::{ChildOf, Related},
+};
+
+use super::{EntityError, QueryOneError, World};
+
+/// Component value owned by a prefab.
+struct PrefabValue {
+    info: ComponentInfo,
+    ptr: NonNull<u8>,
+}
+
+impl Drop for PrefabValue {
+    fn drop(&mut self) {
+        self.info.final_drop(self.ptr, 1);
+
+        if self.info.layout().size() != 0 {
+            unsafe {
+                dealloc(self.ptr.as_ptr(), self.info.layout());
+            }
+        }
+    }
+}
+
+/// Components of a prefab shared by all its instances.
+/// Values never move and are never mutated.
+pub(crate) struct PrefabData {
+    values: HashMap<TypeId, PrefabValue, NoOpHasherBuilder>,
+}
+
+// Values are `Send`, ensured by `World::spawn_prefab`.
+// Values are only accessed through `&T` queries that require `T: Sync`.
+unsafe impl Send for PrefabData {}
+unsafe impl Sync for PrefabData {}
+
+impl PrefabData {
+    /// Returns pointer to the value of specified component type.
+    #[inline]
+    pub(crate) fn get_ptr(&self, id: TypeId) -> Option<NonNull<u8>> {
+        Some(self.values.get(&id)?.ptr)
+    }
+
+    #[inline]
+    fn get<T: 'static>(&self) -> Option<&T> {
+        let ptr = self.get_ptr(TypeId::of::<T>())?;
+        Some(unsafe { ptr.cast::<T>().as_ref() })
+    }
+}
+
+/// Component of a prefab entity.
+/// Holds components inherited by instances of the prefab.
+///
+/// Prefab components are not visible to queries on the prefab itself.
+/// Use [`Prefab::get`] to read them.
+#[derive(Clone)]
+pub struct Prefab {
+    data: Arc<PrefabData>,
+}
+
+impl Component for Prefab {
+    #[inline]
+    fn clone_fn() -> Option<fn(&Self) -> Self> {
+        Some(Self::clone)
+    }
+}
+
+impl Prefab {
+    /// Returns component of the prefab.
+    #[inline]
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: Sync + 'static,
+    {
+        self.data.get::<T>()
+    }
+
+    /// Returns `true` if prefab has component of specified type.
+    #[inline]
+    pub fn has_component<T: 'static>(&self) -> bool {
+        self.data.values.contains_key(&TypeId::of::<T>())
+    }
+}
+
+/// Component of an entity instantiated from a prefab.
+///
+/// `&T` queries fall back to the prefab's `T`
+/// for instances that do not have own `T`.
+#[derive(Clone)]
+pub struct Instance {
+    prefab: EntityId,
+    data: Arc<PrefabData>,
+}
+
+impl Component for Instance {
+    #[inline]
+    fn map_entities(&mut self, mapper: &mut dyn FnMut(EntityId) -> EntityId) {
+        self.prefab = mapper(self.prefab);
+    }
+
+    #[inline]
+    fn clone_fn() -> Option<fn(&Self) -> Self> {
+        Some(Self::clone)
+    }
+}
+
+impl Instance {
+    /// Returns id of the prefab this entity is instantiated from.
+    /// The prefab may be already despawned,
+    /// inherited components stay available to instances.
+    #[inline]
+    pub fn prefab(&self) -> EntityId {
+        self.prefab
+    }
+
+    /// Returns component inherited from the prefab.
+    /// Ignores overrides.
+    #[inline]
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: Sync + 'static,
+    {
+        self.data.get::<T>()
+    }
+
+    #[inline]
+    pub(crate) fn data(&self) -> &PrefabData {
+        &self.data
+    }
+}
+
+impl World {
+    /// Spawns a new prefab entity with specified bundle of components.
+    ///
+    /// Components are stored once and shared by all instances
+    /// spawned with [`World::instantiate`].
+    /// They are not visible to queries on the prefab itself.
+    ///
+    /// Prefab entities related to this one with [`ChildOf`]
+    /// are instantiated as children of each instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let prefab = world.spawn_prefab((ExampleComponent,));
+    /// let entity = world.instantiate(prefab).unwrap();
+    /// assert!(world.query_one_mut::<&ExampleComponent>(entity).is_ok());
+    /// ```
+    ///
+    /// Prefab components are shared between threads, so they must be `Send`.
+    ///
+    /// ```compile_fail
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Local(std::rc::Rc<u32>);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn_prefab((Local(std::rc::Rc::new(1)),));
+    /// ```
+    pub fn spawn_prefab<B>(&mut self, bundle: B) -> EntityId
+    where
+        B: DynamicComponentBundle + Send,
+    {
+        assert!(
+            bundle.valid(),
+            "Specified bundle `{}` is not valid. Check for duplicate component types",
+            type_name::<B>()
+        );
+
+        let infos: Vec<ComponentInfo> = bundle.with_components(|infos| {
+            infos
+                .iter()
+                .map(|info| self.registry.get_or_register_raw(info.clone()).clone())
+                .collect()
+        });
+
+        let mut values = HashMap::with_hasher(NoOpHasherBuilder);
+        bundle.put(|src, id, size| {
+            let info = infos.iter().find(|info| info.id() == id).unwrap();
+            debug_assert_eq!(info.layout().size(), size);
+
+            let ptr = if size == 0 {
+                unsafe { NonNull::new_unchecked(info.layout().align() as *mut u8) }
+            } else {
+                match NonNull::new(unsafe { alloc(info.layout()) }) {
+                    None => handle_alloc_error(info.layout()),
+                    Some(ptr) => ptr,
+                }
+            };
+
+            unsafe {
+                core::ptr::copy_nonoverlapping(src.as_ptr(), ptr.as_ptr(), size);
+            }
+
+            values.insert(
+                id,
+                PrefabValue {
+                    info: info.clone(),
+                    ptr,
+                },
+            );
+        });
+
+        self.spawn((Prefab {
+            data: Arc::new(PrefabData { values }),
+        },))
+    }
+
+    /// Spawns a new instance of the prefab.
+    ///
+    /// Instance inherits all components of the prefab.
+    /// `&T` queries yield prefab's `T` for instances without own `T`.
+    /// Inserting `T` into the instance overrides inherited value,
+    /// dropping it brings inherited value back.
+    /// Other queries, like `&mut T` or `With<T>`, see only own components.
+    /// There is no copy-on-write: `&mut T` skips instances that inherit `T`
+    /// until [`World::override_component`] gives them own copy.
+    ///
+    /// Children of the prefab that are prefabs themselves
+    /// are instantiated recursively and added as children of the instance
+    /// in the same order.
+    /// Children that are not prefabs are skipped,
+    /// as they are regular entities and can't be shared between instances.
+    ///
+    /// Returns [`Err(EntityError::MissingComponents)`]
+    /// if entity is not a prefab.
+    ///
+    /// [`Err(EntityError::MissingComponents)`]: EntityError::MissingComponents
+    pub fn instantiate(&mut self, prefab: EntityId) -> Result<EntityId, EntityError> {
+        let data = self
+            .for_one::<(&Prefab, IncludeDisabled), _, _>(prefab, |(prefab, _)| prefab.data.clone())
+            .map_err(|err| match err {
+                QueryOneError::NoSuchEntity => EntityError::NoSuchEntity,
+                QueryOneError::NotSatisfied => EntityError::MissingComponents,
+            })?;
+
+        let children = self
+            .for_one::<(Related<ChildOf>, IncludeDisabled), _, _>(prefab, |(children, _)| {
+                children.to_vec()
+            })
+            .unwrap_or_default();
+
+        let instance = self.spawn((Instance { prefab, data },));
+
+        for child in children {
+            if let Ok(true) = self.has_component::<Prefab>(child) {
+                let child = self.instantiate(child)?;
+                self.add_relation(child, ChildOf, instance).unwrap();
+            }
+        }
+
+        Ok(instance)
+    }
+
+    /// Inserts clone of the inherited component into the instance,
+    /// so it can be modified without affecting the prefab and other instances.
+    ///
+    /// Does nothing if the instance already has own component.
+    ///
+    /// Returns [`Err(EntityError::MissingComponents)`]
+    /// if entity is not an instance or its prefab has no such component.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Clone, Component)]
+    /// struct Health(u32);
+    ///
+    /// let mut world = World::new();
+    /// let prefab = world.spawn_prefab((Health(100),));
+    /// let a = world.instantiate(prefab).unwrap();
+    /// let b = world.instantiate(prefab).unwrap();
+    ///
+    /// world.override_component::<Health>(a).unwrap();
+    /// world.query_one_mut::<&mut Health>(a).unwrap().0 = 50;
+    ///
+    /// assert_eq!(world.query_one_mut::<&Health>(a).unwrap().0, 50);
+    /// assert_eq!(world.query_one_mut::<&Health>(b).unwrap().0, 100);
+    /// ```
+    ///
+    /// [`Err(EntityError::MissingComponents)`]: EntityError::MissingComponents
+    pub fn override_component<T>(&mut self, id: EntityId) -> Result<(), EntityError>
+    where
+        T: Component + Clone + Sync,
+    {
+        if self.has_component::<T>(id)? {
+            return Ok(());
+        }
+
+        let value = self
+            .for_one::<(&Instance, IncludeDisabled), _, _>(id, |(instance, _)| {
+                instance.get::<T>().cloned()
+            })
+            .ok()
+            .flatten()
+            .ok_or(EntityError::MissingComponents)?;
+
+        self.insert(id, value)?;
+        Ok(())
+    }
+}
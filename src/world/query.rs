@@ -12,11 +12,14 @@ use crate::{
     archetype::{chunk_idx, first_of_chunk, Archetype, CHUNK_LEN_USIZE},
     entity::{EntityId, EntitySet},
     query::{
-        Copied, Fetch, FilteredQuery, ImmutableQuery, IntoQuery, Modified, MutQuery, Not,
-        PhantomQuery, Query, QueryBorrowAll, QueryBorrowAny, QueryBorrowOne, QueryItem, QueryIter,
-        With, Without,
+        visit_enabled, Copied, DefaultQuery, Fetch, FilteredQuery, ImmutableQuery, IncludeDisabled,
+        IntoQuery, Modified, MutQuery, Not, PhantomQuery, Query, QueryBorrowAll, QueryBorrowAny,
+        QueryBorrowOne, QueryItem, QueryIter, With, Without,
+    },
+    relation::{
+        Related, RelatedDyn, Relates, RelatesDyn, RelatesExclusive, RelatesJoin, RelatesTo,
+        Relation, Up,
     },
-    relation::{Related, Relates, RelatesExclusive, RelatesTo},
     world::{NoSuchEntity, QueryOneError},
 };
 
@@ -207,6 +210,12 @@ where
         }
     }
 
+    /// Adds filter that makes query visit disabled entities.
+    #[inline]
+    pub fn include_disabled(self) -> QueryRef<'a, Q, (IncludeDisabled, F)> {
+        self.filter(IncludeDisabled)
+    }
+
     /// Adds filter to the query.
     #[inline]
     pub fn filter<T>(self, filter: T) -> QueryRef<'a, Q, (T, F)>
@@ -371,6 +380,35 @@ where
         }
     }
 
+    /// Adds query to fetch component from the nearest ancestor
+    /// along exclusive relation.
+    ///
+    /// See [`Up`] for details.
+    #[inline]
+    pub fn up<R, T>(self) -> QueryRef<'a, TuplePlus<Q, Up<'a, R, T>>, F>
+    where
+        R: Relation + Sync,
+        T: Sync + 'static,
+        Q: ExtendTuple<Up<'a, R, T>>,
+        Q::Query: ExtendTuple<Up<'a, R, T>>,
+        TuplePlus<Q, Up<'a, R, T>>: IntoQuery<Query = TuplePlus<Q::Query, Up<'a, R, T>>>,
+    {
+        let parts = self.deconstruct();
+
+        let up = Up::from_parts(parts.archetypes, parts.entities);
+
+        QueryRef {
+            archetypes: parts.archetypes,
+            entities: parts.entities,
+            epoch: parts.epoch,
+            filtered_query: FilteredQuery {
+                query: parts.filtered_query.query.extend_tuple(up),
+                filter: parts.filtered_query.filter,
+            },
+            borrowed: Cell::new(parts.borrowed),
+        }
+    }
+
     /// Adds query to fetch relation.
     #[inline]
     pub fn relates<R>(self) -> QueryRef<'a, TuplePlus<Q, Relates<R>>, F>
@@ -442,6 +480,85 @@ where
         }
     }
 
+    /// Adds query to fetch origins of dynamic relation of specified kind.
+    #[inline]
+    pub fn relates_dyn(self, kind: EntityId) -> QueryRef<'a, TuplePlus<Q, RelatesDyn>, F>
+    where
+        Q: ExtendTuple<RelatesDyn>,
+        Q::Query: ExtendTuple<RelatesDyn>,
+        TuplePlus<Q, RelatesDyn>: IntoQuery<Query = TuplePlus<Q::Query, RelatesDyn>>,
+    {
+        let parts = self.deconstruct();
+
+        QueryRef {
+            archetypes: parts.archetypes,
+            entities: parts.entities,
+            epoch: parts.epoch,
+            filtered_query: FilteredQuery {
+                query: parts
+                    .filtered_query
+                    .query
+                    .extend_tuple(RelatesDyn::new(kind)),
+                filter: parts.filtered_query.filter,
+            },
+            borrowed: Cell::new(parts.borrowed),
+        }
+    }
+
+    /// Adds query to fetch targets of dynamic relation of specified kind.
+    #[inline]
+    pub fn related_dyn(self, kind: EntityId) -> QueryRef<'a, TuplePlus<Q, RelatedDyn>, F>
+    where
+        Q: ExtendTuple<RelatedDyn>,
+        Q::Query: ExtendTuple<RelatedDyn>,
+        TuplePlus<Q, RelatedDyn>: IntoQuery<Query = TuplePlus<Q::Query, RelatedDyn>>,
+    {
+        let parts = self.deconstruct();
+
+        QueryRef {
+            archetypes: parts.archetypes,
+            entities: parts.entities,
+            epoch: parts.epoch,
+            filtered_query: FilteredQuery {
+                query: parts
+                    .filtered_query
+                    .query
+                    .extend_tuple(RelatedDyn::new(kind)),
+                filter: parts.filtered_query.filter,
+            },
+            borrowed: Cell::new(parts.borrowed),
+        }
+    }
+
+    /// Adds query to fetch relation joined with query on relation targets.
+    ///
+    /// See [`RelatesJoin`] for details.
+    #[inline]
+    pub fn relates_join<R, T>(self) -> QueryRef<'a, TuplePlus<Q, RelatesJoin<'a, R, T::Query>>, F>
+    where
+        T: DefaultQuery,
+        RelatesJoin<'a, R, T::Query>: Query,
+        Q: ExtendTuple<RelatesJoin<'a, R, T::Query>>,
+        Q::Query: ExtendTuple<RelatesJoin<'a, R, T::Query>>,
+        TuplePlus<Q, RelatesJoin<'a, R, T::Query>>:
+            IntoQuery<Query = TuplePlus<Q::Query, RelatesJoin<'a, R, T::Query>>>,
+    {
+        let parts = self.deconstruct();
+
+        let join = RelatesJoin::from_parts(parts.archetypes, parts.entities, T::default_query());
+
+        QueryRef {
+            archetypes: parts.archetypes,
+            entities: parts.entities,
+            epoch: parts.epoch,
+            filtered_query: FilteredQuery {
+                query: parts.filtered_query.query.extend_tuple(join),
+                filter: parts.filtered_query.filter,
+            },
+            borrowed: Cell::new(parts.borrowed),
+        }
+    }
+
     /// Adds query to fetch relation.
     #[inline]
     pub fn related<R>(self) -> QueryRef<'a, TuplePlus<Q, Related<R>>, F>
@@ -1069,7 +1186,7 @@ where
             QueryOneState::Reserved(id) => return self.query.reserved_entity_item(id),
         };
 
-        if !self.query.visit_archetype(archetype) {
+        if !visit_enabled(&self.query, archetype) {
             return None;
         }
 
@@ -1106,7 +1223,7 @@ where
     }
 }
 
-fn acquire_archetypes(archetypes: &[Archetype], query: &impl Query) {
+pub(crate) fn acquire_archetypes(archetypes: &[Archetype], query: &impl Query) {
     struct ReleaseOnFailure<'a, Q: Query> {
         archetypes: &'a [Archetype],
         query: &'a Q,
@@ -1151,7 +1268,7 @@ fn acquire_archetypes(archetypes: &[Archetype], query: &impl Query) {
     core::mem::forget(guard);
 }
 
-fn release_archetypes(archetypes: &[Archetype], query: &impl Query) {
+pub(crate) fn release_archetypes(archetypes: &[Archetype], query: &impl Query) {
     for archetype in archetypes {
         unsafe {
             if query.visit_archetype(archetype) {
@@ -0,0 +1,273 @@
+//! Snapshots of the [`World`] state for rollback.
+
+use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
+use core::any::{Any, TypeId};
+
+use crate::{
+    archetype::ArchetypeSnapshot,
+    entity::{EntityId, EntitySetSnapshot},
+    epoch::EpochId,
+    res::Res,
+};
+
+use super::World;
+
+/// Functions that clone and restore one resource type.
+pub(crate) struct ResourceClone {
+    id: TypeId,
+    clone: fn(&Res) -> Option<Box<dyn Any + Send + Sync>>,
+    restore: fn(&mut Res, Option<&(dyn Any + Send + Sync)>),
+}
+
+impl ResourceClone {
+    pub(crate) fn of<T>() -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        ResourceClone {
+            id: TypeId::of::<T>(),
+            clone: |res| {
+                let value = res.get::<T>()?;
+                Some(Box::new(T::clone(&value)))
+            },
+            restore: |res, value| match value {
+                None => {
+                    res.remove::<T>();
+                }
+                Some(value) => res.insert(value.downcast_ref::<T>().unwrap().clone()),
+            },
+        }
+    }
+
+    pub(crate) fn id(&self) -> TypeId {
+        self.id
+    }
+}
+
+/// Full copy of the [`World`] state.
+///
+/// Captured with [`World::snapshot`] and restored with [`World::restore`].
+/// Contains clones of all components, relations, entity locations,
+/// state of the id allocator, epoch counter and tombstones.
+/// Resources are captured only if registered with [`WorldBuilder::with_snapshot_resource`].
+///
+/// [`WorldBuilder::with_snapshot_resource`]: super::WorldBuilder::with_snapshot_resource
+pub struct WorldSnapshot {
+    epoch: EpochId,
+    entities: EntitySetSnapshot,
+    archetypes: Vec<ArchetypeSnapshot>,
+    resources: Vec<Option<Box<dyn Any + Send + Sync>>>,
+    tombstones: Option<Vec<(EpochId, EntityId)>>,
+}
+
+impl WorldSnapshot {
+    /// Returns epoch of the world when snapshot was taken.
+    #[inline]
+    pub fn epoch(&self) -> EpochId {
+        self.epoch
+    }
+}
+
+impl World {
+    /// Captures full state of the world.
+    ///
+    /// All components must be cloneable.
+    /// See [`Component::clone_fn`] and [`ComponentInfoRef::clone_fn`].
+    /// Relations are always cloneable.
+    ///
+    /// Entities reserved with [`World::allocate`] and not yet spawned are not captured.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any component cannot be cloned
+    /// or if any component or registered resource is borrowed mutably.
+    ///
+    /// [`Component::clone_fn`]: crate::component::Component::clone_fn
+    /// [`ComponentInfoRef::clone_fn`]: crate::component::ComponentInfoRef::clone_fn
+    #[must_use]
+    pub fn snapshot(&self) -> WorldSnapshot {
+        WorldSnapshot {
+            epoch: self.epoch.current(),
+            entities: self.entities.snapshot(),
+            archetypes: self.archetypes.iter().map(|a| a.snapshot()).collect(),
+            resources: self
+                .resource_clones
+                .iter()
+                .map(|r| (r.clone)(&self.res))
+                .collect(),
+            tombstones: self.tombstones.clone(),
+        }
+    }
+
+    /// Restores state of the world captured with [`World::snapshot`].
+    ///
+    /// Current components are dropped without calling hooks.
+    /// Registered resources are replaced with clones from the snapshot
+    /// or removed if they were absent. Other resources are left untouched.
+    ///
+    /// Id allocator continues from the captured position,
+    /// so entities spawned after restoring get the same ids as in the original timeline.
+    /// Custom [`IdRangeAllocator`] is not rewound,
+    /// ranges requested after the snapshot are not reused.
+    ///
+    /// Actions sent through [`ActionSender`]s and not yet executed are discarded,
+    /// as they were recorded against the state that is rolled back.
+    ///
+    /// Snapshot must be taken from this world.
+    ///
+    /// # Panics
+    ///
+    /// Panics if snapshot is taken from another world.
+    ///
+    /// [`IdRangeAllocator`]: crate::entity::IdRangeAllocator
+    /// [`ActionSender`]: crate::action::ActionSender
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) {
+        assert!(
+            snapshot.archetypes.len() <= self.archetypes.len()
+                && snapshot.resources.len() == self.resource_clones.len(),
+            "Snapshot is taken from another world"
+        );
+
+        // Entities stay despawned if cloning panics.
+        self.entities.clear();
+
+        for (idx, archetype) in self.archetypes.iter_mut().enumerate() {
+            match snapshot.archetypes.get(idx) {
+                None => archetype.restore(&ArchetypeSnapshot::empty()),
+                Some(archetype_snapshot) => archetype.restore(archetype_snapshot),
+            }
+        }
+
+        self.entities.restore(&snapshot.entities);
+        self.epoch.reset_mut(snapshot.epoch);
+
+        for (r, value) in self.resource_clones.iter().zip(&snapshot.resources) {
+            (r.restore)(&mut self.res, value.as_deref());
+        }
+
+        self.tombstones.clone_from(&snapshot.tombstones);
+        self.rebuild_indices();
+        self.action_channel.clear();
+    }
+}
+
+/// Ring buffer of [`WorldSnapshot`]s keyed by tick.
+///
+/// Keeps up to `capacity` most recent snapshots.
+/// Pushing new snapshot into full buffer discards the oldest one.
+///
+/// Intended for rollback netcode where world is rewound
+/// to the tick of the last confirmed input and re-simulated.
+pub struct SnapshotRing {
+    snapshots: VecDeque<(u64, WorldSnapshot)>,
+    capacity: usize,
+}
+
+impl SnapshotRing {
+    /// Returns new empty ring that keeps up to `capacity` snapshots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        assert_ne!(capacity, 0, "Snapshot ring capacity must not be zero");
+        SnapshotRing {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Returns maximum number of kept snapshots.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns number of kept snapshots.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Returns `true` if ring contains no snapshots.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Pushes snapshot for specified tick.
+    /// Discards the oldest snapshot if ring is full.
+    ///
+    /// Snapshots for the same or later ticks are discarded first,
+    /// so ticks in the ring are always increasing.
+    pub fn push(&mut self, tick: u64, snapshot: WorldSnapshot) {
+        self.discard_from(tick);
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back((tick, snapshot));
+    }
+
+    /// Captures snapshot of the world for specified tick.
+    /// See [`SnapshotRing::push`].
+    pub fn capture(&mut self, tick: u64, world: &World) {
+        self.push(tick, world.snapshot());
+    }
+
+    /// Returns snapshot for specified tick.
+    #[must_use]
+    pub fn get(&self, tick: u64) -> Option<&WorldSnapshot> {
+        let idx = self
+            .snapshots
+            .binary_search_by_key(&tick, |(t, _)| *t)
+            .ok()?;
+        Some(&self.snapshots[idx].1)
+    }
+
+    /// Returns the most recent snapshot with its tick.
+    #[must_use]
+    pub fn latest(&self) -> Option<(u64, &WorldSnapshot)> {
+        let (tick, snapshot) = self.snapshots.back()?;
+        Some((*tick, snapshot))
+    }
+
+    /// Returns the oldest kept snapshot with its tick.
+    #[must_use]
+    pub fn oldest(&self) -> Option<(u64, &WorldSnapshot)> {
+        let (tick, snapshot) = self.snapshots.front()?;
+        Some((*tick, snapshot))
+    }
+
+    /// Restores world to the snapshot for specified tick.
+    /// Snapshots for later ticks are discarded.
+    ///
+    /// Returns `false` if there is no snapshot for the tick.
+    pub fn rollback(&mut self, tick: u64, world: &mut World) -> bool {
+        match self.get(tick) {
+            None => false,
+            Some(snapshot) => {
+                world.restore(snapshot);
+                if let Some(next) = tick.checked_add(1) {
+                    self.discard_from(next);
+                }
+                true
+            }
+        }
+    }
+
+    /// Discards snapshots for specified and later ticks.
+    pub fn discard_from(&mut self, tick: u64) {
+        while let Some((t, _)) = self.snapshots.back() {
+            if *t < tick {
+                break;
+            }
+            self.snapshots.pop_back();
+        }
+    }
+
+    /// Discards all snapshots.
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
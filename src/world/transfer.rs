@@ -0,0 +1,270 @@
+//! Moving entities between worlds.
+
+use alloc::{vec, vec::Vec};
+use core::ptr::NonNull;
+
+use hashbrown::HashMap;
+
+use crate::{
+    action::{ActionBuffer, ActionEncoder},
+    bundle::EntityBuilder,
+    component::RelationClone,
+    entity::EntityId,
+    hash::NoOpHasherBuilder,
+};
+
+use super::{register_bundle, NoSuchEntity, ObserverEvent, World};
+
+impl World {
+    /// Moves entity with all its components into another world.
+    /// Returns id of the entity in `other` world.
+    /// Returns [`Err(NoSuchEntity)`] if entity does not exists.
+    ///
+    /// See [`World::transfer_batch`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut side = World::new();
+    /// let mut main = World::new();
+    ///
+    /// let entity = side.spawn((ExampleComponent,));
+    /// let moved = side.transfer(entity, &mut main).unwrap();
+    ///
+    /// assert!(!side.is_alive(entity));
+    /// assert!(main.query_one_mut::<&ExampleComponent>(moved).is_ok());
+    /// ```
+    ///
+    /// [`Err(NoSuchEntity)`]: NoSuchEntity
+    #[inline]
+    pub fn transfer(&mut self, id: EntityId, other: &mut World) -> Result<EntityId, NoSuchEntity> {
+        let ids = self.transfer_batch([id], other)?;
+        Ok(ids[0])
+    }
+
+    /// Moves entities with all their components into another world.
+    /// Returns ids of the entities in `other` world in the same order.
+    /// Returns [`Err(NoSuchEntity)`] without moving anything
+    /// if any of the entities does not exists.
+    ///
+    /// Components are moved without cloning, so non-cloneable components are moved too.
+    /// Entities get new ids in `other` world.
+    /// Ids stored in components are rewritten with [`Component::map_entities`]
+    /// for entities moved together.
+    ///
+    /// Relations are kept if both origin and target are moved together.
+    /// Other relations of moved entities are removed,
+    /// as if moved entities were despawned in this world.
+    /// So owned relation despawns origins left behind when their target is moved.
+    ///
+    /// This world sees all components of moved entities removed:
+    /// indices forget them and [`ObserverEvent::Remove`] observers are notified.
+    /// `other` world sees them inserted, running insert hooks
+    /// and notifying [`ObserverEvent::Insert`] observers.
+    /// Drop hooks are called only for relations,
+    /// as other components are moved and not dropped.
+    ///
+    /// When all entities of an archetype are moved
+    /// and it has no relations, its columns are moved as a whole.
+    ///
+    /// This allows building entities in a side world,
+    /// e.g. when streaming content in background,
+    /// and moving them into the main world in one step.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{relation::{ChildOf, Related}, world::World, ExampleComponent};
+    /// let mut side = World::new();
+    /// let mut main = World::new();
+    ///
+    /// let parent = side.spawn((ExampleComponent,));
+    /// let child = side.spawn(());
+    /// side.add_relation(child, ChildOf, parent).unwrap();
+    ///
+    /// let ids = side.transfer_batch([parent, child], &mut main).unwrap();
+    ///
+    /// let children = main.query_one_mut::<Related<ChildOf>>(ids[0]).unwrap();
+    /// assert_eq!(children, &[ids[1]]);
+    /// ```
+    ///
+    /// [`Err(NoSuchEntity)`]: NoSuchEntity
+    /// [`Component::map_entities`]: crate::component::Component::map_entities
+    pub fn transfer_batch<I>(
+        &mut self,
+        ids: I,
+        other: &mut World,
+    ) -> Result<Vec<EntityId>, NoSuchEntity>
+    where
+        I: IntoIterator<Item = EntityId>,
+    {
+        self.maintenance();
+        other.maintenance();
+
+        let ids: Vec<EntityId> = ids.into_iter().collect();
+        for &id in &ids {
+            self.entities.get_location(id).ok_or(NoSuchEntity)?;
+        }
+
+        let mut mapping = HashMap::with_hasher(NoOpHasherBuilder);
+        let mut moved = Vec::with_capacity(ids.len());
+        for &id in &ids {
+            let new_id = *mapping
+                .entry(id)
+                .or_insert_with(|| other.entities.alloc_mut());
+            moved.push(new_id);
+        }
+
+        let mut buffer = self.action_buffer.take().unwrap();
+        let mut other_buffer = ActionBuffer::new();
+
+        // Archetypes without relations which entities are all moved.
+        let mut counts = vec![0; self.archetypes.len()];
+        for id in mapping.keys() {
+            let (archetype_idx, _) = self.entities.get_location(*id).unwrap();
+            if archetype_idx != u32::MAX {
+                counts[archetype_idx as usize] += 1;
+            }
+        }
+        let whole: Vec<u32> = (0..self.archetypes.len() as u32)
+            .filter(|&idx| {
+                let archetype = &self.archetypes[idx as usize];
+                !archetype.is_empty()
+                    && counts[idx as usize] == archetype.len()
+                    && archetype
+                        .infos()
+                        .all(|info| info.relation_clone().is_none())
+            })
+            .collect();
+
+        for &archetype_idx in &whole {
+            self.transfer_archetype(
+                archetype_idx,
+                other,
+                &mapping,
+                &mut buffer,
+                &mut other_buffer,
+            );
+        }
+
+        for &id in &ids {
+            let new_id = mapping[&id];
+
+            // Skip duplicates and entities moved with whole archetype.
+            if self.entities.get_location(id).is_none() {
+                continue;
+            }
+
+            self.notify_components(id, &mut buffer, |_| Some(ObserverEvent::Remove));
+
+            let (archetype_idx, idx) = self.entities.despawn(id).unwrap();
+
+            let mut builder = EntityBuilder::new();
+            let mut other_encoder = ActionEncoder::new(&mut other_buffer, &other.entities);
+
+            let encoder = ActionEncoder::new(&mut buffer, &self.entities);
+            let opt_id = unsafe {
+                self.archetypes[archetype_idx as usize].take_unchecked(
+                    id,
+                    idx,
+                    encoder,
+                    |info, ptr, encoder| match info.relation_clone() {
+                        Some(RelationClone::Origin(add_relations)) => {
+                            add_relations(
+                                ptr,
+                                new_id,
+                                &mut |target| mapping.get(&target).copied(),
+                                &mut other_encoder,
+                            );
+                            info.drop_one(ptr, id, encoder);
+                        }
+                        Some(RelationClone::Target) => info.drop_one(ptr, id, encoder),
+                        None => {
+                            let ptr = builder.add_raw(info, ptr);
+                            info.map_entities(ptr, &mut |id| {
+                                mapping.get(&id).copied().unwrap_or(id)
+                            });
+                        }
+                    },
+                )
+            };
+
+            if let Some(id) = opt_id {
+                self.entities.set_location(id, archetype_idx, idx);
+            }
+
+            if let Some(tombstones) = &mut self.tombstones {
+                tombstones.push((self.epoch.next_mut(), id));
+            }
+
+            other.spawn_with_id_impl(
+                new_id,
+                builder,
+                register_bundle::<EntityBuilder>,
+                &mut other_buffer,
+            );
+        }
+
+        other_buffer.execute(other);
+        buffer.execute(self);
+        self.action_buffer = Some(buffer);
+
+        Ok(moved)
+    }
+
+    /// Moves all entities of the archetype without relations into another world
+    /// by moving its columns.
+    fn transfer_archetype(
+        &mut self,
+        archetype_idx: u32,
+        other: &mut World,
+        mapping: &HashMap<EntityId, EntityId, NoOpHasherBuilder>,
+        buffer: &mut ActionBuffer,
+        other_buffer: &mut ActionBuffer,
+    ) {
+        let ids = self.archetypes[archetype_idx as usize].entities().to_vec();
+        for &id in &ids {
+            self.notify_components(id, buffer, |_| Some(ObserverEvent::Remove));
+        }
+
+        let src = &mut self.archetypes[archetype_idx as usize];
+        let dst_idx = other.matching_archetype(src);
+        let new_ids: Vec<EntityId> = ids.iter().map(|id| mapping[id]).collect();
+
+        let epoch = other.epoch.next_mut();
+        let dst = &mut other.archetypes[dst_idx as usize];
+        let start = dst.append(src, &new_ids, epoch);
+
+        for &id in &ids {
+            self.entities.despawn(id).unwrap();
+            if let Some(tombstones) = &mut self.tombstones {
+                tombstones.push((self.epoch.next_mut(), id));
+            }
+        }
+
+        for (idx, &id) in new_ids.iter().enumerate() {
+            other.entities.spawn_at(id);
+            other.entities.set_location(id, dst_idx, start + idx as u32);
+        }
+
+        for info in dst.infos() {
+            let component = dst.component(info.id()).unwrap();
+            let size = info.layout().size();
+            let ptr = unsafe { component.data_mut().ptr };
+
+            for (idx, &id) in new_ids.iter().enumerate() {
+                unsafe {
+                    let ptr =
+                        NonNull::new_unchecked(ptr.as_ptr().add((start as usize + idx) * size));
+                    info.map_entities(ptr, &mut |id| mapping.get(&id).copied().unwrap_or(id));
+                    info.insert_one(ptr, id, ActionEncoder::new(other_buffer, &other.entities));
+                }
+            }
+        }
+
+        for &id in &new_ids {
+            other.notify_components(id, other_buffer, |_| Some(ObserverEvent::Insert));
+        }
+    }
+}